@@ -0,0 +1,118 @@
+//! Anchors that keep pointing at the same text while a buffer is edited.
+
+use std::collections::HashMap;
+
+/// Where an anchor ends up when text is inserted exactly at its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gravity {
+    /// Stay before the inserted text
+    Left,
+    /// Move after the inserted text
+    Right,
+}
+
+/// Identifies an anchor created in a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnchorId(u64);
+
+/// A tracked position.
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    /// Character index in the buffer
+    position: usize,
+    /// Behaviour for insertions at `position`
+    gravity: Gravity,
+}
+
+/// The live anchors of a buffer.
+#[derive(Debug, Clone, Default)]
+pub struct AnchorSet {
+    /// Anchors by id
+    anchors: HashMap<AnchorId, Anchor>,
+    /// Id given to the next anchor
+    next_id: u64,
+}
+
+impl AnchorSet {
+    /// Creates an empty set of anchors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking `position` and returns the id of the new anchor.
+    pub fn create(&mut self, position: usize, gravity: Gravity) -> AnchorId {
+        let id = AnchorId(self.next_id);
+        self.next_id += 1;
+        self.anchors.insert(id, Anchor { position, gravity });
+        id
+    }
+
+    /// Returns the current position of an anchor, or `None` if it was removed.
+    pub fn position(&self, id: AnchorId) -> Option<usize> {
+        self.anchors.get(&id).map(|anchor| anchor.position)
+    }
+
+    /// Stops tracking an anchor.
+    pub fn remove(&mut self, id: AnchorId) {
+        self.anchors.remove(&id);
+    }
+
+    /// Updates the anchors after `len` characters were inserted at `position`.
+    pub fn apply_insert(&mut self, position: usize, len: usize) {
+        for anchor in self.anchors.values_mut() {
+            let moves = match anchor.gravity {
+                Gravity::Left => anchor.position > position,
+                Gravity::Right => anchor.position >= position,
+            };
+            if moves {
+                anchor.position += len;
+            }
+        }
+    }
+
+    /// Updates the anchors after the characters in `start..end` were removed.
+    ///
+    /// Anchors inside the removed range collapse to its start.
+    pub fn apply_remove(&mut self, start: usize, end: usize) {
+        for anchor in self.anchors.values_mut() {
+            if anchor.position >= end {
+                anchor.position -= end - start;
+            } else if anchor.position > start {
+                anchor.position = start;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gravity() {
+        let mut anchors = AnchorSet::new();
+        let left = anchors.create(5, Gravity::Left);
+        let right = anchors.create(5, Gravity::Right);
+        let before = anchors.create(2, Gravity::Right);
+
+        anchors.apply_insert(5, 3);
+        assert_eq!(anchors.position(left), Some(5));
+        assert_eq!(anchors.position(right), Some(8));
+        assert_eq!(anchors.position(before), Some(2));
+    }
+
+    #[test]
+    fn test_remove_collapses() {
+        let mut anchors = AnchorSet::new();
+        let inside = anchors.create(4, Gravity::Left);
+        let after = anchors.create(10, Gravity::Left);
+
+        anchors.apply_remove(2, 6);
+        assert_eq!(anchors.position(inside), Some(2));
+        assert_eq!(anchors.position(after), Some(6));
+
+        anchors.remove(inside);
+        assert_eq!(anchors.position(inside), None);
+        assert_eq!(anchors.position(after), Some(6));
+    }
+}
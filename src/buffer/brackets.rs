@@ -0,0 +1,159 @@
+//! Bracket matching and the pairs of brackets and quotes typed together.
+
+use ropey::Rope;
+use std::ops::Range;
+
+/// Number of characters searched for a matching bracket, so huge files stay
+/// quick to edit.
+const SEARCH_LIMIT: usize = 100_000;
+
+/// Replacements of ranges with text, from the last to the first.
+type Edits = Vec<(Range<usize>, String)>;
+
+/// Returns whether `pair` nests like brackets, rather than being quotes.
+fn is_bracket(&(open, close): &(char, char)) -> bool {
+    open != close
+}
+
+/// Returns the position of the bracket matching the one at `bracket`.
+pub fn find_match(content: &Rope, bracket: usize, pairs: &[(char, char)]) -> Option<usize> {
+    let c = content.get_char(bracket)?;
+    let &(open, close) = pairs.iter().filter(|pair| is_bracket(pair)).find(|(open, close)| c == *open || c == *close)?;
+    let mut depth = 0usize;
+    if c == open {
+        for (i, next) in content.chars_at(bracket + 1).take(SEARCH_LIMIT).enumerate() {
+            if next == open {
+                depth += 1;
+            } else if next == close {
+                if depth == 0 {
+                    return Some(bracket + 1 + i);
+                }
+                depth -= 1;
+            }
+        }
+    } else {
+        for (i, prev) in content.chars_at(bracket).reversed().take(SEARCH_LIMIT).enumerate() {
+            if prev == close {
+                depth += 1;
+            } else if prev == open {
+                if depth == 0 {
+                    return Some(bracket - 1 - i);
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+/// Returns the bracket next to the cursor at `pos` and the one matching it,
+/// preferring the bracket after the cursor.
+pub fn matching_pair(content: &Rope, pos: usize, pairs: &[(char, char)]) -> Option<(usize, usize)> {
+    [Some(pos), pos.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .find_map(|bracket| find_match(content, bracket, pairs).map(|other| (bracket, other)))
+}
+
+/// Returns the opening and closing brackets of the innermost pair around `pos`.
+pub fn enclosing_pair(content: &Rope, pos: usize, pairs: &[(char, char)]) -> Option<(usize, usize)> {
+    let pos = pos.min(content.len_chars());
+    let mut depth = 0usize;
+    for (i, prev) in content.chars_at(pos).reversed().take(SEARCH_LIMIT).enumerate() {
+        let brackets = || pairs.iter().filter(|pair| is_bracket(pair));
+        if brackets().any(|&(_, close)| prev == close) {
+            depth += 1;
+        } else if brackets().any(|&(open, _)| prev == open) {
+            if depth == 0 {
+                let open = pos - 1 - i;
+                return find_match(content, open, pairs).map(|close| (open, close));
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Returns the edits typing `c` over `selection` makes in place of inserting
+/// it, from the last to the first, and the selection after them.
+///
+/// An opening bracket or quote typed over a selection wraps it in the pair,
+/// and typed before whitespace, a closing bracket or the end of the text it
+/// is closed right away. Quotes are not closed after a letter or digit, as in
+/// `don't`. A closing character typed right before the same character moves
+/// over it. Everything else is left to plain typing.
+pub fn type_char(
+    content: &Rope,
+    selection: Range<usize>,
+    c: char,
+    pairs: &[(char, char)],
+) -> Option<(Edits, Range<usize>)> {
+    if !selection.is_empty() {
+        let &(open, close) = pairs.iter().find(|(open, _)| c == *open)?;
+        let edits = vec![
+            (selection.end..selection.end, close.to_string()),
+            (selection.start..selection.start, open.to_string()),
+        ];
+        return Some((edits, selection.start + 1..selection.end + 1));
+    }
+
+    let pos = selection.start;
+    let next = content.get_char(pos);
+    if next == Some(c) && pairs.iter().any(|&(_, close)| c == close) {
+        return Some((Vec::new(), pos + 1..pos + 1));
+    }
+
+    let &(open, close) = pairs.iter().find(|(open, _)| c == *open)?;
+    let closes_before = match next {
+        Some(next) => next.is_whitespace() || pairs.iter().any(|pair| is_bracket(pair) && pair.1 == next),
+        None => true,
+    };
+    let prev = pos.checked_sub(1).and_then(|prev| content.get_char(prev));
+    let after_word = prev.is_some_and(|prev| prev.is_alphanumeric() || prev == '_' || prev == open);
+    if !closes_before || (open == close && after_word) {
+        return None;
+    }
+    Some((vec![(pos..pos, format!("{}{}", open, close))], pos + 1..pos + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')];
+
+    #[test]
+    fn test_matching() {
+        let content = Rope::from_str("f(a[1], (b)) {x}");
+        assert_eq!(find_match(&content, 1, PAIRS), Some(11));
+        assert_eq!(find_match(&content, 11, PAIRS), Some(1));
+        assert_eq!(find_match(&content, 3, PAIRS), Some(5));
+        assert_eq!(find_match(&content, 0, PAIRS), None);
+
+        // The bracket after the cursor wins over the one before it
+        assert_eq!(matching_pair(&content, 8, PAIRS), Some((8, 10)));
+        assert_eq!(matching_pair(&content, 12, PAIRS), Some((11, 1)));
+        assert_eq!(matching_pair(&content, 13, PAIRS), Some((13, 15)));
+        assert_eq!(matching_pair(&content, 7, PAIRS), None);
+
+        assert_eq!(enclosing_pair(&content, 9, PAIRS), Some((8, 10)));
+        assert_eq!(enclosing_pair(&content, 7, PAIRS), Some((1, 11)));
+        assert_eq!(enclosing_pair(&content, 12, PAIRS), None);
+        assert_eq!(find_match(&Rope::from_str("(()"), 0, PAIRS), None);
+    }
+
+    #[test]
+    fn test_type_char() {
+        let content = Rope::from_str("ab ) don");
+        assert_eq!(type_char(&content, 2..2, '(', PAIRS), Some((vec![(2..2, "()".to_string())], 3..3)));
+        assert_eq!(type_char(&content, 3..3, '[', PAIRS), Some((vec![(3..3, "[]".to_string())], 4..4)));
+        assert_eq!(type_char(&content, 8..8, '"', PAIRS), None);
+        assert_eq!(type_char(&content, 1..1, '(', PAIRS), None);
+        assert_eq!(type_char(&content, 3..3, ')', PAIRS), Some((Vec::new(), 4..4)));
+        assert_eq!(type_char(&content, 2..2, ')', PAIRS), None);
+        assert_eq!(type_char(&content, 0..0, 'x', PAIRS), None);
+
+        let wrapped = type_char(&content, 0..2, '"', PAIRS);
+        assert_eq!(wrapped, Some((vec![(2..2, "\"".to_string()), (0..0, "\"".to_string())], 1..3)));
+    }
+}
@@ -0,0 +1,158 @@
+//! Edits that comment out and uncomment lines and blocks of text.
+
+use ropey::Rope;
+use std::ops::Range;
+
+use super::indent::leading_whitespace;
+
+/// Returns the edits that toggle line comments starting with `token` on
+/// `lines`, as ranges with their replacement, from the last line to the first.
+///
+/// When every non-blank line already starts with the token, the tokens are
+/// removed along with one space after each. Otherwise the token and a space go
+/// in front of every non-blank line at the smallest indentation among them,
+/// so the markers line up. Blank lines are only commented when all are blank.
+pub fn toggle_line_comments(content: &Rope, lines: Range<usize>, token: &str) -> Vec<(Range<usize>, String)> {
+    let texts: Vec<(usize, String)> = lines
+        .filter(|&line| line < content.len_lines())
+        .map(|line| (line, content.line(line).to_string()))
+        .collect();
+    let non_blank: Vec<&(usize, String)> = texts.iter().filter(|(_, text)| !text.trim().is_empty()).collect();
+    let targets = if non_blank.is_empty() { texts.iter().collect() } else { non_blank };
+
+    let commented = targets
+        .iter()
+        .all(|(_, text)| text.trim_start_matches([' ', '\t']).starts_with(token));
+    let mut edits = Vec::new();
+    if commented {
+        for (line, text) in targets.iter().rev() {
+            let indent = leading_whitespace(text).chars().count();
+            let rest = &text[leading_whitespace(text).len() + token.len()..];
+            let len = token.chars().count() + usize::from(rest.starts_with(' '));
+            let start = content.line_to_char(*line) + indent;
+            edits.push((start..start + len, String::new()));
+        }
+        return edits;
+    }
+
+    let column = targets
+        .iter()
+        .map(|(_, text)| leading_whitespace(text.trim_end_matches(['\n', '\r'])).chars().count())
+        .min()
+        .unwrap_or(0);
+    for (line, _) in targets.iter().rev() {
+        let start = content.line_to_char(*line) + column;
+        edits.push((start..start, format!("{} ", token)));
+    }
+    edits
+}
+
+/// Returns the edits that wrap `range` in a block comment from `open` to
+/// `close`, or remove the comment around it, from the last edit to the first.
+///
+/// An empty range stands for the text of its line without the indentation.
+/// The comment is removed when the range starts and ends with the markers or
+/// sits right inside them, along with a space inside each marker.
+pub fn toggle_block_comment(content: &Rope, range: Range<usize>, open: &str, close: &str) -> Vec<(Range<usize>, String)> {
+    let range = if range.is_empty() { line_body(content, range.start) } else { range };
+    let text = content.slice(range.clone()).to_string();
+    if text.trim().is_empty() {
+        return vec![(range.start..range.start, format!("{}  {}", open, close))];
+    }
+
+    // Leave whitespace around the selected text outside the comment
+    let start = range.start + (text.chars().count() - text.trim_start().chars().count());
+    let end = range.end - (text.chars().count() - text.trim_end().chars().count());
+    let inner = content.slice(start..end).to_string();
+
+    let open_len = open.chars().count();
+    let close_len = close.chars().count();
+    if inner.starts_with(open) && inner.ends_with(close) && inner.chars().count() >= open_len + close_len {
+        let body = &inner[open.len()..inner.len() - close.len()];
+        let after_open = usize::from(body.starts_with(' '));
+        let before_close = usize::from(body.len() > 1 && body.ends_with(' '));
+        return vec![
+            (end - close_len - before_close..end, String::new()),
+            (start..start + open_len + after_open, String::new()),
+        ];
+    }
+
+    // A selection inside a comment uncomments it too
+    let before = content.slice(start.saturating_sub(open_len + 1)..start).to_string();
+    let after = content.slice(end..(end + close_len + 1).min(content.len_chars())).to_string();
+    let open_at = before
+        .strip_suffix(' ')
+        .unwrap_or(&before)
+        .ends_with(open)
+        .then(|| start - open_len - usize::from(before.ends_with(' ')));
+    let close_at = after
+        .strip_prefix(' ')
+        .unwrap_or(&after)
+        .starts_with(close)
+        .then(|| end + close_len + usize::from(after.starts_with(' ')));
+    if let (Some(open_at), Some(close_at)) = (open_at, close_at) {
+        return vec![(end..close_at, String::new()), (open_at..start, String::new())];
+    }
+
+    vec![(end..end, format!(" {}", close)), (start..start, format!("{} ", open))]
+}
+
+/// Returns the range of the line at `pos` without its indentation and line break.
+fn line_body(content: &Rope, pos: usize) -> Range<usize> {
+    let line = content.char_to_line(pos);
+    let start = content.line_to_char(line);
+    let text = content.line(line).to_string();
+    let body = text.trim_end_matches(['\n', '\r']);
+    start + leading_whitespace(body).chars().count()..start + body.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies `edits`, given from last to first, to `text`.
+    fn apply(text: &str, edits: Vec<(Range<usize>, String)>) -> String {
+        let mut content = Rope::from_str(text);
+        for (range, replacement) in edits {
+            content.remove(range.clone());
+            content.insert(range.start, &replacement);
+        }
+        content.to_string()
+    }
+
+    #[test]
+    fn test_toggle_line_comments() {
+        let text = "fn a() {\n    b();\n\n  c();\n}\n";
+        let content = Rope::from_str(text);
+        let commented = apply(text, toggle_line_comments(&content, 1..4, "//"));
+        assert_eq!(commented, "fn a() {\n  //   b();\n\n  // c();\n}\n");
+
+        let content = Rope::from_str(&commented);
+        assert_eq!(apply(&commented, toggle_line_comments(&content, 1..4, "//")), text);
+
+        // A mix of commented and plain lines gets commented
+        let mixed = "# a\nb\n";
+        let content = Rope::from_str(mixed);
+        assert_eq!(apply(mixed, toggle_line_comments(&content, 0..2, "#")), "# # a\n# b\n");
+        assert_eq!(apply("#a\n", toggle_line_comments(&Rope::from_str("#a\n"), 0..1, "#")), "a\n");
+        assert_eq!(apply("  \n", toggle_line_comments(&Rope::from_str("  \n"), 0..1, "#")), "  # \n");
+    }
+
+    #[test]
+    fn test_toggle_block_comment() {
+        let text = "let x = a + b;\n";
+        let content = Rope::from_str(text);
+        let commented = apply(text, toggle_block_comment(&content, 8..13, "/*", "*/"));
+        assert_eq!(commented, "let x = /* a + b */;\n");
+
+        // Selecting the comment, or the text inside it, uncomments
+        let content = Rope::from_str(&commented);
+        assert_eq!(apply(&commented, toggle_block_comment(&content, 8..19, "/*", "*/")), text);
+        assert_eq!(apply(&commented, toggle_block_comment(&content, 11..16, "/*", "*/")), text);
+
+        // Without a selection the line is commented
+        let line = "    call();\n";
+        let content = Rope::from_str(line);
+        assert_eq!(apply(line, toggle_block_comment(&content, 2..2, "<!--", "-->")), "    <!-- call(); -->\n");
+    }
+}
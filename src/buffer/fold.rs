@@ -0,0 +1,375 @@
+//! Fold regions and the mapping between a buffer and its folded display text.
+
+use ropey::Rope;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Text shown at the end of a folded line in place of the hidden lines.
+pub const PLACEHOLDER: &str = " ⋯";
+/// Number of characters in [`PLACEHOLDER`].
+const PLACEHOLDER_LEN: usize = 2;
+/// Number of columns a tab counts for when comparing indentation.
+const TAB_WIDTH: usize = 4;
+
+/// A range of lines that can be folded away under its first line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldRegion {
+    /// The line that stays visible when the region is folded
+    pub start_line: usize,
+    /// The last line hidden by folding the region
+    pub end_line: usize,
+    /// Nesting level, 1 for regions not inside any other
+    pub depth: usize,
+}
+
+impl FoldRegion {
+    /// Returns whether `line` is inside the region, including its first line.
+    pub fn contains(&self, line: usize) -> bool {
+        (self.start_line..=self.end_line).contains(&line)
+    }
+}
+
+/// Returns the indentation width of a line, or `None` for blank lines.
+fn indent_width(line: ropey::RopeSlice) -> Option<usize> {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += TAB_WIDTH - width % TAB_WIDTH,
+            '\n' | '\r' => return None,
+            _ => return Some(width),
+        }
+    }
+    None
+}
+
+/// Finds the blocks of lines indented deeper than the line before them.
+fn indent_regions(content: &Rope) -> Vec<(usize, usize)> {
+    let mut regions = Vec::new();
+    // Lines that may start a region, with their indentation
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut last_text_line = 0;
+
+    for (line, text) in content.lines().enumerate() {
+        let Some(indent) = indent_width(text) else { continue };
+        while let Some(&(start, start_indent)) = open.last() {
+            if indent > start_indent {
+                break;
+            }
+            open.pop();
+            if last_text_line > start {
+                regions.push((start, last_text_line));
+            }
+        }
+        open.push((line, indent));
+        last_text_line = line;
+    }
+    for (start, _) in open {
+        if last_text_line > start {
+            regions.push((start, last_text_line));
+        }
+    }
+    regions
+}
+
+/// Finds the lines between bracket pairs that span several lines.
+///
+/// The line with the closing bracket stays visible, and brackets inside double
+/// quoted strings are ignored.
+fn bracket_regions(content: &Rope) -> Vec<(usize, usize)> {
+    let mut regions = Vec::new();
+    let mut open: Vec<(char, usize)> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut line = 0;
+
+    for c in content.chars() {
+        if c == '\n' {
+            line += 1;
+            in_string = false;
+            escaped = false;
+            continue;
+        }
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        let opening = match c {
+            '"' => {
+                in_string = true;
+                continue;
+            }
+            '{' | '[' | '(' => {
+                open.push((c, line));
+                continue;
+            }
+            '}' => '{',
+            ']' => '[',
+            ')' => '(',
+            _ => continue,
+        };
+        if let Some(index) = open.iter().rposition(|&(bracket, _)| bracket == opening) {
+            let start = open[index].1;
+            open.truncate(index);
+            if line > start + 1 {
+                regions.push((start, line - 1));
+            }
+        }
+    }
+    regions
+}
+
+/// Computes the fold regions of `content` from its indentation and brackets.
+///
+/// Regions are sorted by their first line, and at most one starts on a line.
+pub fn regions(content: &Rope) -> Vec<FoldRegion> {
+    let mut ends: BTreeMap<usize, usize> = BTreeMap::new();
+    for (start, end) in indent_regions(content).into_iter().chain(bracket_regions(content)) {
+        let entry = ends.entry(start).or_insert(end);
+        *entry = (*entry).max(end);
+    }
+
+    // Last lines of the regions enclosing the current one
+    let mut enclosing: Vec<usize> = Vec::new();
+    ends.into_iter()
+        .map(|(start_line, end_line)| {
+            while enclosing.last().is_some_and(|&end| end < start_line) {
+                enclosing.pop();
+            }
+            enclosing.push(end_line);
+            FoldRegion { start_line, end_line, depth: enclosing.len() }
+        })
+        .collect()
+}
+
+/// A folded region and the characters it hides.
+#[derive(Debug, Clone)]
+struct Fold {
+    /// The folded region
+    region: FoldRegion,
+    /// Hidden characters, from the end of the first line to the end of the last
+    hidden: Range<usize>,
+}
+
+/// Maps positions between a buffer and its display text, in which every folded
+/// region is replaced by [`PLACEHOLDER`] at the end of its first line.
+#[derive(Debug, Clone, Default)]
+pub struct FoldMap {
+    /// Folds in order, none inside another
+    folds: Vec<Fold>,
+}
+
+/// Returns the character index of the end of `line`, before its line break.
+fn line_end(content: &Rope, line: usize) -> usize {
+    let start = content.line_to_char(line);
+    let text = content.line(line);
+    let mut len = text.len_chars();
+    while len > 0 && matches!(text.char(len - 1), '\n' | '\r') {
+        len -= 1;
+    }
+    start + len
+}
+
+/// Returns how many more characters a fold hides than its placeholder shows.
+fn shift(fold: &Fold) -> isize {
+    fold.hidden.len() as isize - PLACEHOLDER_LEN as isize
+}
+
+impl FoldMap {
+    /// Creates the map for `content` with the `folded` regions collapsed.
+    ///
+    /// Regions starting inside an earlier folded region are already hidden and
+    /// are ignored.
+    pub fn new(content: &Rope, folded: &[FoldRegion]) -> Self {
+        let mut folded = folded.to_vec();
+        folded.sort_by_key(|region| region.start_line);
+
+        let mut folds: Vec<Fold> = Vec::new();
+        for region in folded {
+            if region.end_line >= content.len_lines() {
+                continue;
+            }
+            if folds.last().is_some_and(|fold| region.start_line <= fold.region.end_line) {
+                continue;
+            }
+            let hidden = line_end(content, region.start_line)..line_end(content, region.end_line);
+            folds.push(Fold { region, hidden });
+        }
+        Self { folds }
+    }
+
+    /// Returns whether `line` is the first line of a folded region.
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.folds.iter().any(|fold| fold.region.start_line == line)
+    }
+
+    /// Returns the first line of the folded region hiding `position`, if any.
+    pub fn hiding(&self, position: usize) -> Option<usize> {
+        self.folds
+            .iter()
+            .find(|fold| fold.hidden.start < position && position < fold.hidden.end)
+            .map(|fold| fold.region.start_line)
+    }
+
+    /// Builds the text shown in the editor.
+    pub fn display_text(&self, content: &Rope) -> String {
+        let mut text = String::with_capacity(content.len_bytes());
+        let mut position = 0;
+        for fold in &self.folds {
+            text.extend(content.slice(position..fold.hidden.start).chunks());
+            text.push_str(PLACEHOLDER);
+            position = fold.hidden.end;
+        }
+        text.extend(content.slice(position..).chunks());
+        text
+    }
+
+    /// Returns where each fold's placeholder starts in the display text.
+    fn placeholders(&self) -> impl Iterator<Item = (usize, &Fold)> {
+        let mut removed = 0isize;
+        self.folds.iter().map(move |fold| {
+            let start = (fold.hidden.start as isize - removed) as usize;
+            removed += shift(fold);
+            (start, fold)
+        })
+    }
+
+    /// Converts a buffer position to a display position.
+    ///
+    /// Hidden positions map to the end of the placeholder hiding them.
+    pub fn to_display(&self, position: usize) -> usize {
+        let mut display = position as isize;
+        for (placeholder, fold) in self.placeholders() {
+            if position <= fold.hidden.start {
+                break;
+            }
+            if position < fold.hidden.end {
+                return placeholder + PLACEHOLDER_LEN;
+            }
+            display -= shift(fold);
+        }
+        display as usize
+    }
+
+    /// Converts a display position to a buffer position.
+    ///
+    /// Positions in or right after a placeholder map to the end of the visible
+    /// part of the folded line, so text typed there extends that line.
+    pub fn to_buffer(&self, display: usize) -> usize {
+        let mut position = display as isize;
+        for (placeholder, fold) in self.placeholders() {
+            if display <= placeholder {
+                break;
+            }
+            if display <= placeholder + PLACEHOLDER_LEN {
+                return fold.hidden.start;
+            }
+            position += shift(fold);
+        }
+        position as usize
+    }
+
+    /// Converts the display range of an edit to the buffer range it replaces.
+    ///
+    /// A range covering a whole placeholder includes the text it hides. Ranges
+    /// starting or ending inside a placeholder cannot be mapped and return the
+    /// first line of its region instead, so the region can be unfolded.
+    pub fn to_buffer_range(&self, range: Range<usize>) -> Result<Range<usize>, usize> {
+        if range.is_empty() {
+            let position = self.to_buffer(range.start);
+            return Ok(position..position);
+        }
+
+        let mut start = range.start as isize;
+        let mut end = range.end as isize;
+        for (placeholder, fold) in self.placeholders() {
+            let placeholder_end = placeholder + PLACEHOLDER_LEN;
+            for (edge, bound) in [(&mut start, range.start), (&mut end, range.end)] {
+                if bound > placeholder && bound < placeholder_end {
+                    return Err(fold.region.start_line);
+                }
+                if bound >= placeholder_end {
+                    *edge += shift(fold);
+                }
+            }
+        }
+        Ok(start as usize..end as usize)
+    }
+
+    /// Converts a line of the display text to the buffer line it shows.
+    pub fn buffer_line(&self, display_line: usize) -> usize {
+        let mut line = display_line;
+        for fold in &self.folds {
+            if fold.region.start_line >= line {
+                break;
+            }
+            line += fold.region.end_line - fold.region.start_line;
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "fn main() {\n    let x = [\n        1,\n    ];\n\n    call(x);\n}\nfn other() {}\n";
+
+    #[test]
+    fn test_regions() {
+        let content = Rope::from_str(CODE);
+        let found: Vec<(usize, usize, usize)> = regions(&content)
+            .iter()
+            .map(|region| (region.start_line, region.end_line, region.depth))
+            .collect();
+        assert_eq!(found, vec![(0, 5, 1), (1, 2, 2)]);
+
+        // Indentation alone is enough for languages without brackets
+        let python = Rope::from_str("def f():\n    a\n    b\n\nx = \"{\"\n");
+        assert_eq!(regions(&python), vec![FoldRegion { start_line: 0, end_line: 2, depth: 1 }]);
+    }
+
+    #[test]
+    fn test_fold_map() {
+        let content = Rope::from_str(CODE);
+        let map = FoldMap::new(&content, &[regions(&content)[1]]);
+
+        let display = map.display_text(&content);
+        assert_eq!(display, "fn main() {\n    let x = [ ⋯\n    ];\n\n    call(x);\n}\nfn other() {}\n");
+
+        // "    ];" starts at 37 in the buffer and 28 in the display
+        assert_eq!(map.to_display(37), 28);
+        assert_eq!(map.to_buffer(28), 37);
+        assert_eq!(map.to_display(5), 5);
+        assert_eq!(map.to_display(30), 27);
+        assert_eq!(map.to_buffer(27), 25);
+        assert_eq!(map.hiding(30), Some(1));
+        assert_eq!(map.hiding(25), None);
+
+        assert_eq!(map.buffer_line(1), 1);
+        assert_eq!(map.buffer_line(2), 3);
+        assert!(map.is_folded(1));
+    }
+
+    #[test]
+    fn test_fold_map_edits() {
+        let content = Rope::from_str(CODE);
+        let map = FoldMap::new(&content, &[regions(&content)[1]]);
+
+        // Deleting the placeholder with its surroundings deletes the hidden text
+        assert_eq!(map.to_buffer_range(24..27), Ok(24..36));
+        // Cutting into the placeholder is refused
+        assert_eq!(map.to_buffer_range(25..26), Err(1));
+        // Edits after the fold are shifted
+        assert_eq!(map.to_buffer_range(28..32), Ok(37..41));
+        // Typing at the end of the folded line extends it
+        assert_eq!(map.to_buffer_range(27..27), Ok(25..25));
+    }
+}
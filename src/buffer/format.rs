@@ -0,0 +1,308 @@
+//! How a buffer is written to disk: line endings, encoding and the clean-ups
+//! made when saving.
+
+use anyhow::{bail, Context, Result};
+use ropey::Rope;
+use std::ops::Range;
+
+/// The characters that end a line on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as on Linux and macOS
+    Lf,
+    /// `\r\n`, as on Windows
+    Crlf,
+    /// `\r`, as on classic Mac OS
+    Cr,
+}
+
+impl LineEnding {
+    /// Every line ending, in the order they are offered.
+    pub const ALL: [LineEnding; 3] = [LineEnding::Lf, LineEnding::Crlf, LineEnding::Cr];
+
+    /// Returns the characters of the line ending.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    /// Returns the name of the line ending shown in the status bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+
+    /// Returns the line ending of the first line of `content` that has one.
+    pub fn detect(content: &Rope) -> Option<LineEnding> {
+        let mut chars = content.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\n' => return Some(LineEnding::Lf),
+                '\r' if chars.next() == Some('\n') => return Some(LineEnding::Crlf),
+                '\r' => return Some(LineEnding::Cr),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// The character encoding a file is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// UTF-8 without a byte order mark
+    Utf8,
+    /// UTF-8 starting with a byte order mark
+    Utf8Bom,
+    /// ISO-8859-1, which only holds the characters up to U+00FF
+    Latin1,
+    /// Big endian UTF-16 with a byte order mark
+    Utf16Be,
+    /// Little endian UTF-16 with a byte order mark
+    Utf16Le,
+}
+
+/// Settings for writing one buffer to disk. Unset settings leave the content
+/// as it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileFormat {
+    /// Line ending every line is written with
+    pub line_ending: Option<LineEnding>,
+    /// Encoding the file is written in
+    pub charset: Option<Charset>,
+    /// Whether to remove whitespace at the end of lines when saving
+    pub trim_trailing_whitespace: Option<bool>,
+    /// Whether trimming only touches lines edited since the last save
+    pub trim_modified_lines_only: Option<bool>,
+    /// Whether to make sure the file ends with a line break when saving
+    pub insert_final_newline: Option<bool>,
+    /// Whether to remove blank lines after the last line break when saving
+    pub trim_final_newlines: Option<bool>,
+    /// Column lines should not go past, shown as a guide in the editor
+    pub max_line_length: Option<usize>,
+}
+
+impl FileFormat {
+    /// Returns the format with the settings unset here taken from `defaults`.
+    pub fn or(&self, defaults: &FileFormat) -> FileFormat {
+        FileFormat {
+            line_ending: self.line_ending.or(defaults.line_ending),
+            charset: self.charset.or(defaults.charset),
+            trim_trailing_whitespace: self.trim_trailing_whitespace.or(defaults.trim_trailing_whitespace),
+            trim_modified_lines_only: self.trim_modified_lines_only.or(defaults.trim_modified_lines_only),
+            insert_final_newline: self.insert_final_newline.or(defaults.insert_final_newline),
+            trim_final_newlines: self.trim_final_newlines.or(defaults.trim_final_newlines),
+            max_line_length: self.max_line_length.or(defaults.max_line_length),
+        }
+    }
+}
+
+/// Returns the ranges of spaces and tabs at the end of `lines`, or of every
+/// line if not given, from the last line to the first.
+pub fn trailing_whitespace(content: &Rope, lines: Option<&[usize]>) -> Vec<Range<usize>> {
+    let all: Vec<usize>;
+    let lines = match lines {
+        Some(lines) => lines,
+        None => {
+            all = (0..content.len_lines()).collect();
+            &all
+        }
+    };
+    let mut ranges = Vec::new();
+    for &line in lines.iter().rev().filter(|&&line| line < content.len_lines()) {
+        let text = content.line(line).to_string();
+        let body = text.trim_end_matches(['\n', '\r']);
+        let trimmed = body.trim_end_matches([' ', '\t']);
+        if trimmed.len() < body.len() {
+            let start = content.line_to_char(line) + trimmed.chars().count();
+            ranges.push(start..start + body[trimmed.len()..].chars().count());
+        }
+    }
+    ranges
+}
+
+/// Returns the edit that fixes the line breaks at the end of `content`, as a
+/// range and its replacement.
+///
+/// A missing final line break is added with `insert`, ending in `ending`, and
+/// with `trim` any blank lines after the last line break are removed. Content
+/// with nothing but line breaks is left alone.
+pub fn final_newline(content: &Rope, insert: bool, trim: bool, ending: LineEnding) -> Option<(Range<usize>, String)> {
+    let len = content.len_chars();
+    let mut end = len;
+    while end > 0 && matches!(content.char(end - 1), '\n' | '\r') {
+        end -= 1;
+    }
+    if end == 0 {
+        return None;
+    }
+    let tail = content.slice(end..len).to_string();
+    let wanted = if tail.is_empty() {
+        if !insert {
+            return None;
+        }
+        ending.as_str().to_string()
+    } else if trim {
+        // Keep the line break the last line already has
+        let last = LineEnding::detect(&Rope::from_str(&tail)).unwrap_or(ending);
+        last.as_str().to_string()
+    } else {
+        return None;
+    };
+    (tail != wanted).then_some((end..len, wanted))
+}
+
+/// Returns `text` with every line ending changed to `ending`.
+pub fn normalize_line_endings(text: &str, ending: LineEnding) -> String {
+    let unix = text.replace("\r\n", "\n").replace('\r', "\n");
+    match ending {
+        LineEnding::Lf => unix,
+        _ => unix.replace('\n', ending.as_str()),
+    }
+}
+
+/// Reads the text of a file from its `bytes`, returning it and the charset it
+/// was written in.
+///
+/// A byte order mark decides the charset and is dropped. Otherwise the bytes
+/// are read in `charset` if given, else as UTF-8 if they are valid UTF-8 and
+/// as Latin-1 if not, which every byte is valid in.
+pub fn decode(bytes: &[u8], charset: Option<Charset>) -> Result<(String, Charset)> {
+    let (charset, bytes) = match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => (Charset::Utf8Bom, rest),
+        [0xFE, 0xFF, rest @ ..] => (Charset::Utf16Be, rest),
+        [0xFF, 0xFE, rest @ ..] => (Charset::Utf16Le, rest),
+        _ => match charset {
+            Some(charset) => (charset, bytes),
+            None if std::str::from_utf8(bytes).is_ok() => (Charset::Utf8, bytes),
+            None => (Charset::Latin1, bytes),
+        },
+    };
+    let text = match charset {
+        Charset::Utf8 | Charset::Utf8Bom => String::from_utf8(bytes.to_vec()).context("The file is not valid UTF-8")?,
+        Charset::Latin1 => bytes.iter().map(|&byte| char::from(byte)).collect(),
+        Charset::Utf16Be | Charset::Utf16Le => {
+            if bytes.len() % 2 != 0 {
+                bail!("The file is not valid UTF-16");
+            }
+            let units = bytes.chunks(2).map(|pair| match charset {
+                Charset::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                _ => u16::from_le_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units).collect::<Result<String, _>>().context("The file is not valid UTF-16")?
+        }
+    };
+    Ok((text, charset))
+}
+
+/// Returns `text` as the bytes written to disk in `format`, or an error naming
+/// the first character the charset can't hold rather than losing it.
+pub fn encode(text: &str, format: &FileFormat) -> Result<Vec<u8>> {
+    // A byte order mark read from the file is replaced by the charset's own
+    let text = match format.charset {
+        Some(_) => text.strip_prefix('\u{feff}').unwrap_or(text),
+        None => text,
+    };
+    let text = match format.line_ending {
+        Some(ending) => normalize_line_endings(text, ending),
+        None => text.to_string(),
+    };
+
+    Ok(match format.charset.unwrap_or(Charset::Utf8) {
+        Charset::Utf8 => text.into_bytes(),
+        Charset::Utf8Bom => [&[0xEF, 0xBB, 0xBF], text.as_bytes()].concat(),
+        Charset::Latin1 => {
+            if let Some((index, c)) = text.char_indices().find(|&(_, c)| u32::from(c) > 0xFF) {
+                let line = text[..index].matches('\n').count() + 1;
+                bail!("'{}' on line {} can't be saved as Latin-1", c, line);
+            }
+            text.chars().map(|c| u32::from(c) as u8).collect()
+        }
+        Charset::Utf16Be => [0xFEFF].into_iter().chain(text.encode_utf16()).flat_map(u16::to_be_bytes).collect(),
+        Charset::Utf16Le => [0xFEFF].into_iter().chain(text.encode_utf16()).flat_map(u16::to_le_bytes).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trailing_whitespace() {
+        let content = Rope::from_str("a  \nb\t\r\n  \nc");
+        assert_eq!(trailing_whitespace(&content, None), vec![8..10, 5..6, 1..3]);
+        assert_eq!(trailing_whitespace(&content, Some(&[0, 2, 9])), vec![8..10, 1..3]);
+    }
+
+    #[test]
+    fn test_final_newline() {
+        let lf = LineEnding::Lf;
+        assert_eq!(final_newline(&Rope::from_str("c"), true, false, lf), Some((1..1, "\n".to_string())));
+        assert_eq!(final_newline(&Rope::from_str("c"), false, true, lf), None);
+        assert_eq!(final_newline(&Rope::from_str("c\r\n\r\n\n"), true, true, lf), Some((1..6, "\r\n".to_string())));
+        assert_eq!(final_newline(&Rope::from_str("c\n\n"), true, false, lf), None);
+        assert_eq!(final_newline(&Rope::from_str("c\n"), true, true, lf), None);
+        assert_eq!(final_newline(&Rope::from_str("\n\n"), true, true, lf), None);
+
+        assert_eq!(LineEnding::detect(&Rope::from_str("a\r\nb\n")), Some(LineEnding::Crlf));
+        assert_eq!(LineEnding::detect(&Rope::from_str("a")), None);
+    }
+
+    #[test]
+    fn test_encode() {
+        let crlf = FileFormat {
+            line_ending: Some(LineEnding::Crlf),
+            ..FileFormat::default()
+        };
+        assert_eq!(encode("a\nb\r\nc\r", &crlf).unwrap(), b"a\r\nb\r\nc\r\n");
+        assert_eq!(encode("a\r\nb", &FileFormat::default()).unwrap(), b"a\r\nb");
+
+        let bom = FileFormat {
+            charset: Some(Charset::Utf8Bom),
+            ..FileFormat::default()
+        };
+        assert_eq!(encode("\u{feff}é", &bom).unwrap(), [0xEF, 0xBB, 0xBF, 0xC3, 0xA9]);
+        let latin1 = FileFormat {
+            charset: Some(Charset::Latin1),
+            ..FileFormat::default()
+        };
+        assert_eq!(encode("aé", &latin1).unwrap(), [b'a', 0xE9]);
+        // Characters Latin-1 can't hold stop the save instead of being lost
+        let error = encode("é\n€", &latin1).unwrap_err().to_string();
+        assert_eq!(error, "'€' on line 2 can't be saved as Latin-1");
+        let utf16 = FileFormat {
+            charset: Some(Charset::Utf16Le),
+            ..FileFormat::default()
+        };
+        assert_eq!(encode("a", &utf16).unwrap(), [0xFF, 0xFE, b'a', 0]);
+    }
+
+    #[test]
+    fn test_decode() {
+        let decoded = |bytes: &[u8], charset| decode(bytes, charset).unwrap();
+        assert_eq!(decoded("é".as_bytes(), None), ("é".to_string(), Charset::Utf8));
+        assert_eq!(decoded(&[0xEF, 0xBB, 0xBF, b'a'], None), ("a".to_string(), Charset::Utf8Bom));
+        assert_eq!(decoded(&[0xFF, 0xFE, b'a', 0], None), ("a".to_string(), Charset::Utf16Le));
+        assert_eq!(decoded(&[0xFE, 0xFF, 0, b'a'], Some(Charset::Utf8)), ("a".to_string(), Charset::Utf16Be));
+        assert_eq!(decoded(&[0, b'a'], Some(Charset::Utf16Be)), ("a".to_string(), Charset::Utf16Be));
+
+        // Bytes that aren't UTF-8 are read as Latin-1 unless UTF-8 was asked for
+        assert_eq!(decoded(&[b'a', 0xE9], None), ("aé".to_string(), Charset::Latin1));
+        assert_eq!(decoded("é".as_bytes(), Some(Charset::Latin1)), ("Ã©".to_string(), Charset::Latin1));
+        assert!(decode(&[b'a', 0xE9], Some(Charset::Utf8)).is_err());
+        assert!(decode(&[0xFF, 0xFE, b'a'], None).is_err());
+
+        // Decoding and encoding again gives back the same bytes
+        let latin1 = FileFormat {
+            charset: Some(Charset::Latin1),
+            ..FileFormat::default()
+        };
+        assert_eq!(encode(&decoded(&[0xE9, 0xFF], None).0, &latin1).unwrap(), [0xE9, 0xFF]);
+    }
+}
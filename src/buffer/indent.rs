@@ -0,0 +1,334 @@
+//! Indentation units and the edits that indent, outdent and re-indent lines.
+
+use ropey::Rope;
+use std::ops::Range;
+
+/// How one level of indentation is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentStyle {
+    /// Whether to indent with spaces instead of tabs
+    pub use_spaces: bool,
+    /// Number of columns in a tab and in one level of indentation
+    pub tab_size: usize,
+}
+
+impl IndentStyle {
+    /// Creates a style, treating a tab size of zero as one.
+    pub fn new(use_spaces: bool, tab_size: usize) -> Self {
+        Self {
+            use_spaces,
+            tab_size: tab_size.max(1),
+        }
+    }
+
+    /// Returns the text of one level of indentation.
+    pub fn unit(&self) -> String {
+        if self.use_spaces {
+            " ".repeat(self.tab_size)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// Returns the number of columns `whitespace` spans from the start of a line.
+    pub fn width(&self, whitespace: &str) -> usize {
+        whitespace.chars().fold(0, |width, c| match c {
+            '\t' => width + self.tab_size - width % self.tab_size,
+            _ => width + 1,
+        })
+    }
+
+    /// Returns whitespace spanning `width` columns, using tabs where they fit
+    /// unless the style uses spaces.
+    pub fn make(&self, width: usize) -> String {
+        if self.use_spaces {
+            " ".repeat(width)
+        } else {
+            "\t".repeat(width / self.tab_size) + &" ".repeat(width % self.tab_size)
+        }
+    }
+
+    /// Returns the text a Tab press inserts at `column`, reaching the next tab stop.
+    pub fn fill_to_next_stop(&self, column: usize) -> String {
+        if self.use_spaces {
+            " ".repeat(self.tab_size - column % self.tab_size)
+        } else {
+            "\t".to_string()
+        }
+    }
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        Self::new(true, 4)
+    }
+}
+
+/// Indentation settings of one buffer that take the place of the configured
+/// ones where they are set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndentOverride {
+    /// Whether to indent with spaces instead of tabs
+    pub use_spaces: Option<bool>,
+    /// Number of columns in a tab and in one level of indentation
+    pub tab_size: Option<usize>,
+}
+
+impl IndentOverride {
+    /// Returns the overrides with the settings unset here taken from `defaults`.
+    pub fn or(&self, defaults: &IndentOverride) -> IndentOverride {
+        IndentOverride {
+            use_spaces: self.use_spaces.or(defaults.use_spaces),
+            tab_size: self.tab_size.or(defaults.tab_size),
+        }
+    }
+
+    /// Returns the style with the overridden settings in place of the given ones.
+    pub fn resolve(&self, use_spaces: bool, tab_size: usize) -> IndentStyle {
+        IndentStyle::new(self.use_spaces.unwrap_or(use_spaces), self.tab_size.unwrap_or(tab_size))
+    }
+}
+
+/// Number of lines looked at when detecting the indentation of a file.
+const DETECT_LINES: usize = 10_000;
+
+/// Infers how `content` is indented.
+///
+/// Lines indented with tabs and with spaces are counted, and for spaces the
+/// width is the most common step by which indentation grows from one line to
+/// the next. Whatever cannot be told from the content is left unset.
+pub fn detect(content: &Rope) -> IndentOverride {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps = [0usize; 9];
+    let mut previous = Some(0);
+
+    for line in content.lines().take(DETECT_LINES) {
+        let text = line.to_string();
+        if text.trim().is_empty() {
+            continue;
+        }
+        let whitespace = leading_whitespace(&text);
+        if whitespace.contains('\t') {
+            if whitespace.starts_with('\t') {
+                tab_lines += 1;
+            }
+            previous = None;
+            continue;
+        }
+        // Block comment continuation lines are aligned one column past the opener
+        if text[whitespace.len()..].starts_with('*') && whitespace.len() % 2 == 1 {
+            continue;
+        }
+        let width = whitespace.len();
+        if width > 0 {
+            space_lines += 1;
+        }
+        if let Some(step) = previous.filter(|&previous| width > previous).map(|previous| width - previous) {
+            if step < steps.len() {
+                steps[step] += 1;
+            }
+        }
+        previous = Some(width);
+    }
+
+    if tab_lines == 0 && space_lines == 0 {
+        return IndentOverride::default();
+    }
+    if tab_lines > space_lines {
+        return IndentOverride {
+            use_spaces: Some(false),
+            tab_size: None,
+        };
+    }
+    // Single column steps are alignment rather than indentation; ties go to the
+    // narrower width
+    let tab_size = (2..steps.len())
+        .filter(|&step| steps[step] > 0)
+        .max_by_key(|&step| (steps[step], std::cmp::Reverse(step)));
+    IndentOverride {
+        use_spaces: Some(true),
+        tab_size,
+    }
+}
+
+/// Returns the spaces and tabs at the start of `line`.
+pub fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Returns whether the next line after `line` should be indented one level
+/// deeper, because it ends by opening a block.
+pub fn opens_block(line: &str) -> bool {
+    line.trim_end().ends_with(['{', '[', '(', ':'])
+}
+
+/// Returns the number of leading characters of `line` that make up one level
+/// of indentation to remove when outdenting.
+pub fn outdent_len(line: &str, style: IndentStyle) -> usize {
+    if line.starts_with('\t') {
+        return 1;
+    }
+    line.chars().take(style.tab_size).take_while(|&c| c == ' ').count()
+}
+
+/// Returns the text to insert for Enter at `pos` and where the cursor goes in it.
+///
+/// The new line copies the indentation of the current one and gets one more
+/// level after an opening bracket or colon. Between a pair of brackets the
+/// closing one moves to a line of its own below the cursor.
+pub fn newline(content: &Rope, pos: usize, style: IndentStyle) -> (String, usize) {
+    let line = content.char_to_line(pos);
+    let line_start = content.line_to_char(line);
+    let before = content.slice(line_start..pos).to_string();
+    let indent = leading_whitespace(&before);
+
+    if !opens_block(&before) {
+        let text = format!("\n{}", indent);
+        let cursor = text.chars().count();
+        return (text, cursor);
+    }
+
+    let inner = format!("\n{}{}", indent, style.unit());
+    let cursor = inner.chars().count();
+    let next = content.get_char(pos);
+    let closes = matches!(
+        (before.trim_end().chars().last(), next),
+        (Some('{'), Some('}')) | (Some('['), Some(']')) | (Some('('), Some(')'))
+    );
+    if closes {
+        (format!("{}\n{}", inner, indent), cursor)
+    } else {
+        (inner, cursor)
+    }
+}
+
+/// Returns the range Backspace at `pos` removes when it deletes indentation.
+///
+/// When only spaces precede the cursor on its line, Backspace goes back to the
+/// previous tab stop instead of removing a single space. Returns `None` where
+/// Backspace should remove one character as usual.
+pub fn backspace_range(content: &Rope, pos: usize, style: IndentStyle) -> Option<Range<usize>> {
+    if pos == 0 || pos > content.len_chars() {
+        return None;
+    }
+    let line_start = content.line_to_char(content.char_to_line(pos));
+    let before = content.slice(line_start..pos).to_string();
+    if before.len() < 2 || !before.chars().all(|c| c == ' ') {
+        return None;
+    }
+    let width = before.len();
+    let remove = match width % style.tab_size {
+        0 => style.tab_size,
+        partial => partial,
+    };
+    Some(pos - remove..pos)
+}
+
+/// Returns the edits that rewrite the indentation of every line from the
+/// `from` style to the `to` one, as ranges with their replacement, from the
+/// last line to the first.
+///
+/// Each level of `from` becomes a level of `to`, and columns left over past
+/// the last level are kept as they are.
+pub fn conversions(content: &Rope, from: IndentStyle, to: IndentStyle) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::new();
+    for line in (0..content.len_lines()).rev() {
+        let line_start = content.line_to_char(line);
+        let text = content.line(line).to_string();
+        let whitespace = leading_whitespace(&text);
+        if whitespace.is_empty() || text.trim().is_empty() {
+            continue;
+        }
+        let width = from.width(whitespace);
+        let converted = to.make(width / from.tab_size * to.tab_size + width % from.tab_size);
+        if converted != whitespace {
+            edits.push((line_start..line_start + whitespace.chars().count(), converted));
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style() {
+        let spaces = IndentStyle::new(true, 4);
+        let tabs = IndentStyle::new(false, 4);
+        assert_eq!(spaces.unit(), "    ");
+        assert_eq!(tabs.unit(), "\t");
+        assert_eq!(tabs.width("  \t "), 5);
+        assert_eq!(tabs.make(10), "\t\t  ");
+        assert_eq!(spaces.make(6), "      ");
+        assert_eq!(spaces.fill_to_next_stop(6), "  ");
+        assert_eq!(IndentStyle::new(true, 0).tab_size, 1);
+
+        assert_eq!(outdent_len("\t\tx", spaces), 1);
+        assert_eq!(outdent_len("      x", spaces), 4);
+        assert_eq!(outdent_len("  x", spaces), 2);
+        assert_eq!(outdent_len("x", spaces), 0);
+    }
+
+    #[test]
+    fn test_newline() {
+        let style = IndentStyle::new(true, 4);
+        let content = Rope::from_str("    let x = 1;\n    if x {}\n    match x {\n");
+        assert_eq!(newline(&content, 14, style), ("\n    ".to_string(), 5));
+
+        // Between brackets the closing one goes to its own line
+        let inside = content.line_to_char(1) + 10;
+        assert_eq!(newline(&content, inside, style), ("\n        \n    ".to_string(), 9));
+
+        let after_open = content.line_to_char(3) - 1;
+        assert_eq!(newline(&content, after_open, style), ("\n        ".to_string(), 9));
+    }
+
+    #[test]
+    fn test_backspace_range() {
+        let style = IndentStyle::new(true, 4);
+        let content = Rope::from_str("      x\n ab\n\tc\n");
+        assert_eq!(backspace_range(&content, 6, style), Some(4..6));
+        assert_eq!(backspace_range(&content, 4, style), Some(0..4));
+        assert_eq!(backspace_range(&content, 1, style), None);
+        assert_eq!(backspace_range(&content, 7, style), None);
+        assert_eq!(backspace_range(&content, 10, style), None);
+        assert_eq!(backspace_range(&content, 13, style), None);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let content = Rope::from_str("a\n    b\n\t  c\n   \n");
+        let spaces = IndentStyle::new(true, 4);
+        let tabs = IndentStyle::new(false, 4);
+        assert_eq!(conversions(&content, spaces, tabs), vec![(2..6, "\t".to_string())]);
+        assert_eq!(conversions(&content, tabs, spaces), vec![(8..11, "      ".to_string())]);
+
+        // Changing the width re-indents every level
+        let narrow = IndentStyle::new(true, 2);
+        assert_eq!(
+            conversions(&content, spaces, narrow),
+            vec![(8..11, "    ".to_string()), (2..6, "  ".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detect() {
+        let two = Rope::from_str("a:\n  b:\n    c\n  d\n/*\n *\n */\n");
+        assert_eq!(detect(&two), IndentOverride { use_spaces: Some(true), tab_size: Some(2) });
+
+        let four = Rope::from_str("fn a() {\n    if b {\n        c(x,\n          y);\n    }\n}\n");
+        assert_eq!(detect(&four).tab_size, Some(4));
+
+        let tabs = Rope::from_str("a {\n\tb\n\t\tc\n  d\n");
+        assert_eq!(detect(&tabs), IndentOverride { use_spaces: Some(false), tab_size: None });
+
+        assert_eq!(detect(&Rope::from_str("flat\ntext\n")), IndentOverride::default());
+        let resolved = IndentOverride { use_spaces: Some(false), tab_size: None }.resolve(true, 8);
+        assert_eq!(resolved, IndentStyle::new(false, 8));
+        let language = IndentOverride { use_spaces: Some(true), tab_size: Some(2) };
+        let merged = IndentOverride { use_spaces: Some(false), tab_size: None }.or(&language);
+        assert_eq!(merged.resolve(true, 8), IndentStyle::new(false, 2));
+    }
+}
@@ -0,0 +1,99 @@
+//! Transformations of whole lines: sorting and removing duplicates or blanks.
+
+use std::cmp::Ordering;
+
+/// How lines are compared when sorting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOptions {
+    /// Whether the last line in order comes first
+    pub descending: bool,
+    /// Whether upper and lower case letters compare the same
+    pub case_insensitive: bool,
+    /// Whether lines starting with numbers are ordered by their value
+    pub numeric: bool,
+    /// Whether only the first of lines comparing equal is kept
+    pub unique: bool,
+}
+
+/// Returns the number `line` starts with, after any indentation.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let sign = usize::from(line.starts_with(['-', '+']));
+    let digits = line[sign..].find(|c: char| !c.is_ascii_digit() && c != '.').map_or(line.len(), |end| sign + end);
+    line[..digits].parse().ok()
+}
+
+/// Compares two lines as `options` ask, apart from the direction.
+fn compare(a: &str, b: &str, options: SortOptions) -> Ordering {
+    if options.numeric {
+        // Lines without a number go before those with one
+        let by_number = match (leading_number(a), leading_number(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if by_number != Ordering::Equal {
+            return by_number;
+        }
+    }
+    if options.case_insensitive {
+        a.to_lowercase().cmp(&b.to_lowercase())
+    } else {
+        a.cmp(b)
+    }
+}
+
+/// Sorts `lines` as `options` ask. Lines that compare equal keep their order.
+pub fn sort(mut lines: Vec<String>, options: SortOptions) -> Vec<String> {
+    lines.sort_by(|a, b| {
+        let order = compare(a, b, options);
+        if options.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+    if options.unique {
+        lines.dedup_by(|b, a| compare(a, b, options) == Ordering::Equal);
+    }
+    lines
+}
+
+/// Removes every line that repeats an earlier one.
+pub fn remove_duplicates(lines: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    lines.into_iter().filter(|line| seen.insert(line.clone())).collect()
+}
+
+/// Removes every line that is empty or only whitespace.
+pub fn remove_blank(lines: Vec<String>) -> Vec<String> {
+    lines.into_iter().filter(|line| !line.trim().is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split(',').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let text = lines("b,10,A,a,9,b");
+        assert_eq!(sort(text.clone(), SortOptions::default()), lines("10,9,A,a,b,b"));
+        let descending = SortOptions { descending: true, ..SortOptions::default() };
+        assert_eq!(sort(text.clone(), descending), lines("b,b,a,A,9,10"));
+        let insensitive = SortOptions { case_insensitive: true, unique: true, ..SortOptions::default() };
+        assert_eq!(sort(text.clone(), insensitive), lines("10,9,A,b"));
+        let numeric = SortOptions { numeric: true, ..SortOptions::default() };
+        assert_eq!(sort(lines("x 2,-1.5,10,3"), numeric), lines("x 2,-1.5,3,10"));
+    }
+
+    #[test]
+    fn test_remove() {
+        assert_eq!(remove_duplicates(lines("a,b,a,c,b")), lines("a,b,c"));
+        assert_eq!(remove_blank(lines("a,, ,b")), lines("a,b"));
+    }
+}
@@ -3,12 +3,35 @@
 //! This module provides an efficient text buffer implementation using the Ropey crate,
 //! which is optimized for handling large text files and efficient editing operations.
 
+pub mod anchor;
+pub mod brackets;
+pub mod comment;
+pub mod fold;
+pub mod format;
+pub mod indent;
+pub mod lines;
+pub mod transform;
+
 use anyhow::{Context, Result};
 use ropey::Rope;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::fs;
 
+pub use anchor::{AnchorId, Gravity};
+use anchor::AnchorSet;
+pub use fold::{FoldMap, FoldRegion};
+pub use format::FileFormat;
+use format::{Charset, LineEnding};
+pub use indent::{IndentOverride, IndentStyle};
+
+use crate::language::{self, Language};
+
 /// Represents an edit operation that can be undone or redone.
 #[derive(Debug, Clone)]
 enum EditOperation {
@@ -25,6 +48,99 @@ enum EditOperation {
     },
 }
 
+/// An edit in the undo history, with the revisions before and after it.
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    /// The operations of the edit, in the order they were made
+    operations: Vec<EditOperation>,
+    /// Revision of the buffer before the edit
+    before: u64,
+    /// Revision of the buffer after the edit
+    after: u64,
+}
+
+/// Describes a change made to a buffer.
+///
+/// The characters starting at `start` that read `old_text` were replaced by
+/// `new_text`. Insertions have an empty `old_text` and removals an empty `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    /// Revision of the buffer after the change
+    pub revision: u64,
+    /// Character index where the change starts
+    pub start: usize,
+    /// The text that was replaced
+    pub old_text: String,
+    /// The text that replaced it
+    pub new_text: String,
+}
+
+impl ChangeEvent {
+    /// Returns the character index where the new text ends.
+    pub fn new_end(&self) -> usize {
+        self.start + self.new_text.chars().count()
+    }
+}
+
+/// Channels of the listeners to a buffer's changes.
+///
+/// A clone of a buffer starts without listeners, so it never reports its own
+/// edits as changes to the original.
+#[derive(Debug, Default)]
+struct Subscribers(Vec<Sender<ChangeEvent>>);
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// A bookmarked line that stays on the same text as the buffer is edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bookmark {
+    /// Anchor inside the bookmarked line
+    anchor: AnchorId,
+    /// Optional name shown in the bookmark list
+    name: Option<String>,
+}
+
+/// The size and modification time of a file when a buffer last read or
+/// wrote it, to notice when another program changes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiskStamp {
+    /// The file the stamp is for
+    path: PathBuf,
+    /// When the file was last modified, where the platform records it
+    modified: Option<SystemTime>,
+    /// Size of the file in bytes
+    len: u64,
+}
+
+impl DiskStamp {
+    /// Returns the stamp of the file at `path`, or `None` if it can't be read.
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Error returned when saving would overwrite changes another program made to
+/// the file since the buffer last read or wrote it.
+#[derive(Debug)]
+pub struct ChangedOnDisk(pub PathBuf);
+
+impl std::fmt::Display for ChangedOnDisk {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} was changed on disk by another program", self.0.display())
+    }
+}
+
+impl std::error::Error for ChangedOnDisk {}
+
 /// Represents a text buffer in the editor.
 #[derive(Debug, Clone)]
 pub struct TextBuffer {
@@ -32,14 +148,38 @@ pub struct TextBuffer {
     pub content: Rope,
     /// The file path associated with this buffer, if any
     pub file_path: Option<PathBuf>,
-    /// Whether the buffer has unsaved changes
-    pub modified: bool,
+    /// Indentation of this buffer in place of the configured one
+    pub indent: IndentOverride,
+    /// How the buffer is written to disk
+    pub format: FileFormat,
+    /// Language the content is written in
+    pub language: &'static Language,
     /// History of edit operations for undo
-    undo_stack: Vec<EditOperation>,
+    undo_stack: Vec<HistoryEntry>,
     /// History of edit operations for redo
-    redo_stack: Vec<EditOperation>,
-    /// Whether we're currently in an undo/redo operation
-    in_undo_redo: bool,
+    redo_stack: Vec<HistoryEntry>,
+    /// Identifies the current state of the content
+    revision: u64,
+    /// Revision given to the next new edit
+    next_revision: u64,
+    /// Revision of the content last loaded from or written to disk
+    saved_revision: u64,
+    /// Listeners notified of every change
+    subscribers: Subscribers,
+    /// Positions that follow edits
+    anchors: AnchorSet,
+    /// Bookmarked lines
+    bookmarks: Vec<Bookmark>,
+    /// Anchors at the start of the first line of folded regions
+    folds: Vec<AnchorId>,
+    /// Lines edited since the content was last saved
+    changed_lines: BTreeSet<usize>,
+    /// Whether edits are being collected into one undo step
+    grouping: bool,
+    /// Whether the undo step collecting the current group was started
+    group_recorded: bool,
+    /// The file as it was when last read or written
+    disk_stamp: Option<DiskStamp>,
 }
 
 impl TextBuffer {
@@ -48,10 +188,22 @@ impl TextBuffer {
         Self {
             content: Rope::new(),
             file_path: None,
-            modified: false,
+            indent: IndentOverride::default(),
+            format: FileFormat::default(),
+            language: language::plain_text(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
-            in_undo_redo: false,
+            revision: 0,
+            next_revision: 1,
+            saved_revision: 0,
+            subscribers: Subscribers::default(),
+            anchors: AnchorSet::new(),
+            bookmarks: Vec::new(),
+            folds: Vec::new(),
+            changed_lines: BTreeSet::new(),
+            grouping: false,
+            group_recorded: false,
+            disk_stamp: None,
         }
     }
 
@@ -60,70 +212,280 @@ impl TextBuffer {
         Self {
             content: Rope::from_str(content),
             file_path: None,
-            modified: false,
+            indent: IndentOverride::default(),
+            format: FileFormat::default(),
+            language: language::plain_text(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
-            in_undo_redo: false,
+            revision: 0,
+            next_revision: 1,
+            saved_revision: 0,
+            subscribers: Subscribers::default(),
+            anchors: AnchorSet::new(),
+            bookmarks: Vec::new(),
+            folds: Vec::new(),
+            changed_lines: BTreeSet::new(),
+            grouping: false,
+            group_recorded: false,
+            disk_stamp: None,
         }
     }
 
-    /// Loads a text buffer from a file.
-    pub async fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+    /// Loads a text buffer from a file written in `charset`, or in the one
+    /// detected from its bytes if not given.
+    ///
+    /// A charset other than plain UTF-8 is kept in the buffer's file format so
+    /// the file is saved the way it was read.
+    pub async fn from_file<P: AsRef<Path>>(path: P, charset: Option<Charset>) -> Result<Self> {
         let path = path.as_ref();
-        let content = fs::read_to_string(path)
+        let bytes = fs::read(path)
             .await
             .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        let (content, charset) = format::decode(&bytes, charset)
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        let disk_stamp = DiskStamp::read(path);
         
+        let content = Rope::from_str(&content);
         Ok(Self {
-            content: Rope::from_str(&content),
+            indent: indent::detect(&content),
+            format: FileFormat {
+                charset: (charset != Charset::Utf8).then_some(charset),
+                ..FileFormat::default()
+            },
+            language: language::detect(Some(path), &content),
+            content,
             file_path: Some(path.to_path_buf()),
-            modified: false,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
-            in_undo_redo: false,
+            revision: 0,
+            next_revision: 1,
+            saved_revision: 0,
+            subscribers: Subscribers::default(),
+            anchors: AnchorSet::new(),
+            bookmarks: Vec::new(),
+            folds: Vec::new(),
+            changed_lines: BTreeSet::new(),
+            grouping: false,
+            group_recorded: false,
+            disk_stamp,
         })
     }
 
     /// Saves the buffer content to the associated file.
     pub async fn save(&mut self) -> Result<()> {
         if let Some(path) = self.file_path.clone() {
-            self.save_to(path).await?;
-            self.modified = false;
-            Ok(())
+            self.save_to(path).await
         } else {
             Err(anyhow::anyhow!("No file path associated with this buffer"))
         }
     }
 
-    /// Saves the buffer content to a specific file path.
+    /// Lets the next save overwrite changes another program made to the file.
+    pub fn ignore_disk_changes(&mut self) {
+        self.disk_stamp = None;
+    }
+
+    /// Saves the buffer content to a specific file path, with the line endings
+    /// and charset of its file format.
+    ///
+    /// The content is written to a temporary file next to the target, which
+    /// then replaces it, so a failed save leaves the file as it was. Saving
+    /// fails with [`ChangedOnDisk`] if the file changed since the buffer last
+    /// read or wrote it.
     pub async fn save_to<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
         let path = path.as_ref();
-        let content = self.content.to_string();
+        let content = format::encode(&self.content.to_string(), &self.format)
+            .with_context(|| format!("Failed to save {}", path.display()))?;
         
-        fs::write(path, content)
-            .await
+        self.check_disk_at(path)?;
+        write_atomic(path, &content)
             .with_context(|| format!("Failed to write to file: {}", path.display()))?;
         
         self.file_path = Some(path.to_path_buf());
-        self.modified = false;
+        self.disk_stamp = DiskStamp::read(path);
+        self.mark_saved();
         Ok(())
     }
 
+    /// Fails with [`ChangedOnDisk`] if another program changed the buffer's
+    /// file since the buffer last read or wrote it, so saving would overwrite
+    /// those changes.
+    pub fn check_disk(&self) -> Result<()> {
+        match &self.file_path {
+            Some(path) => self.check_disk_at(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the file at `path` like [`TextBuffer::check_disk`], if it is the
+    /// file the buffer last read or wrote.
+    fn check_disk_at(&self, path: &Path) -> Result<()> {
+        if let Some(stamp) = self.disk_stamp.as_ref().filter(|stamp| stamp.path == path) {
+            if DiskStamp::read(path).is_some_and(|current| current != *stamp) {
+                return Err(ChangedOnDisk(path.to_path_buf()).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the changes asked for before saving, as one undo step.
+    ///
+    /// `formatted` text from a formatter replaces the content first. Then the
+    /// buffer's file format is applied, with the settings it leaves unset taken
+    /// from `defaults`.
+    pub fn prepare_save(&mut self, defaults: &FileFormat, formatted: Option<&str>) -> Result<()> {
+        let format = self.format.or(defaults);
+        self.edit_group(|buffer| {
+            if let Some(text) = formatted {
+                buffer.update_content(text)?;
+            }
+            if let Some(ending) = format.line_ending {
+                let text = buffer.content.to_string();
+                buffer.update_content(&format::normalize_line_endings(&text, ending))?;
+            }
+            if format.trim_trailing_whitespace == Some(true) {
+                let changed: Vec<usize> = buffer.changed_lines().collect();
+                let lines = (format.trim_modified_lines_only == Some(true)).then_some(changed.as_slice());
+                for range in format::trailing_whitespace(&buffer.content, lines) {
+                    buffer.remove(range.start, range.end)?;
+                }
+            }
+            let ending = format.line_ending
+                .or_else(|| LineEnding::detect(&buffer.content))
+                .unwrap_or(LineEnding::Lf);
+            let insert = format.insert_final_newline == Some(true);
+            let trim = format.trim_final_newlines == Some(true);
+            if let Some((range, text)) = format::final_newline(&buffer.content, insert, trim, ending) {
+                buffer.replace(range, &text)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the revision of the current content.
+    ///
+    /// Every edit produces a new revision, and undo and redo return to the
+    /// revision the content had before, so equal revisions mean equal content.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Records the current content as the one on disk.
+    pub fn mark_saved(&mut self) {
+        self.saved_revision = self.revision;
+        self.changed_lines.clear();
+    }
+
+    /// Returns whether the content differs from the last saved revision.
+    pub fn is_modified(&self) -> bool {
+        self.revision != self.saved_revision
+    }
+
+    /// Returns a channel that receives an event for every change to the buffer.
+    ///
+    /// The subscription ends when the receiver is dropped.
+    pub fn subscribe(&mut self) -> Receiver<ChangeEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.0.push(sender);
+        receiver
+    }
+
+    /// Sends a change event to the subscribers, forgetting the ones that are gone.
+    fn notify(&mut self, start: usize, old_text: String, new_text: String) {
+        let event = ChangeEvent {
+            revision: self.revision,
+            start,
+            old_text,
+            new_text,
+        };
+        self.subscribers.0.retain(|sender| sender.send(event.clone()).is_ok());
+    }
+
+    /// Records a new edit in the undo history and moves to a fresh revision.
+    ///
+    /// Inside [`TextBuffer::edit_group`] every edit after the first joins the
+    /// first one's undo step and revision.
+    fn record(&mut self, operation: EditOperation) {
+        if self.grouping && self.group_recorded {
+            if let Some(entry) = self.undo_stack.last_mut() {
+                entry.operations.push(operation);
+                return;
+            }
+        }
+        self.group_recorded = self.grouping;
+        
+        let entry = HistoryEntry {
+            operations: vec![operation],
+            before: self.revision,
+            after: self.next_revision,
+        };
+        self.next_revision += 1;
+        self.revision = entry.after;
+        self.undo_stack.push(entry);
+        // Clear redo stack when a new edit is made
+        self.redo_stack.clear();
+    }
+
+    /// Returns the lines edited since the content was last saved, in order.
+    ///
+    /// Lines are tracked as edits are made, so an edit that is typed back to the
+    /// saved text still counts until the content returns to the saved revision.
+    pub fn changed_lines(&self) -> impl Iterator<Item = usize> + '_ {
+        self.changed_lines.iter().copied()
+    }
+
+    /// Updates the changed lines for an edit at `line` that replaced `removed`
+    /// line breaks with `added` ones.
+    fn track_changed_lines(&mut self, line: usize, removed: usize, added: usize) {
+        if !self.is_modified() {
+            self.changed_lines.clear();
+            return;
+        }
+        let shifted: BTreeSet<usize> = self
+            .changed_lines
+            .iter()
+            .filter_map(|&changed| {
+                if changed < line {
+                    Some(changed)
+                } else if changed > line + removed {
+                    Some(changed + added - removed)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        self.changed_lines = shifted;
+        self.changed_lines.extend(line..=line + added);
+    }
+
+    /// Inserts text into the content and notifies anchors and subscribers.
+    fn apply_insert(&mut self, char_idx: usize, text: &str) {
+        let line = self.content.char_to_line(char_idx);
+        self.track_changed_lines(line, 0, text.matches('\n').count());
+        self.content.insert(char_idx, text);
+        self.anchors.apply_insert(char_idx, text.chars().count());
+        self.notify(char_idx, String::new(), text.to_string());
+    }
+
+    /// Removes text from the content and notifies anchors and subscribers.
+    fn apply_remove(&mut self, char_start: usize, char_end: usize) {
+        let removed_text = self.content.slice(char_start..char_end).to_string();
+        let line = self.content.char_to_line(char_start);
+        self.track_changed_lines(line, removed_text.matches('\n').count(), 0);
+        self.content.remove(char_start..char_end);
+        self.anchors.apply_remove(char_start, char_end);
+        self.notify(char_start, removed_text, String::new());
+    }
+
     /// Inserts text at the specified character position.
     pub fn insert(&mut self, char_idx: usize, text: &str) -> Result<()> {
         if char_idx <= self.content.len_chars() {
-            // If not in an undo/redo operation, record this edit for undo
-            if !self.in_undo_redo {
-                self.undo_stack.push(EditOperation::Insert {
-                    position: char_idx,
-                    text: text.to_string(),
-                });
-                // Clear redo stack when a new edit is made
-                self.redo_stack.clear();
-            }
-            
-            self.content.insert(char_idx, text);
-            self.modified = true;
+            // Record this edit for undo
+            self.record(EditOperation::Insert {
+                position: char_idx,
+                text: text.to_string(),
+            });
+            self.apply_insert(char_idx, text);
             Ok(())
         } else {
             Err(anyhow::anyhow!("Character index out of bounds"))
@@ -133,20 +495,14 @@ impl TextBuffer {
     /// Removes text in the specified character range.
     pub fn remove(&mut self, char_start: usize, char_end: usize) -> Result<()> {
         if char_start <= char_end && char_end <= self.content.len_chars() {
-            // If not in an undo/redo operation, record this edit for undo
-            if !self.in_undo_redo {
-                let removed_text = self.content.slice(char_start..char_end).to_string();
-                self.undo_stack.push(EditOperation::Delete {
-                    start: char_start,
-                    end: char_end,
-                    text: removed_text,
-                });
-                // Clear redo stack when a new edit is made
-                self.redo_stack.clear();
-            }
-            
-            self.content.remove(char_start..char_end);
-            self.modified = true;
+            // Record this edit for undo
+            let removed_text = self.content.slice(char_start..char_end).to_string();
+            self.record(EditOperation::Delete {
+                start: char_start,
+                end: char_end,
+                text: removed_text,
+            });
+            self.apply_remove(char_start, char_end);
             Ok(())
         } else {
             Err(anyhow::anyhow!("Character range out of bounds"))
@@ -155,77 +511,52 @@ impl TextBuffer {
 
     /// Performs an undo operation, reverting the last edit.
     pub fn undo(&mut self) -> Result<()> {
-        if let Some(operation) = self.undo_stack.pop() {
-            self.in_undo_redo = true;
+        if let Some(entry) = self.undo_stack.pop() {
+            // Undoing returns to the revision the content had before the edit
+            self.revision = entry.before;
             
-            match operation {
-                EditOperation::Insert { position, text } => {
-                    // To undo an insert, we delete the inserted text
-                    let end_pos = position + text.chars().count();
-                    self.remove(position, end_pos)?;
-                    
-                    // Add to redo stack
-                    self.redo_stack.push(EditOperation::Insert {
-                        position,
-                        text,
-                    });
-                },
-                EditOperation::Delete { start, end: _, text } => {
-                    // To undo a delete, we insert the deleted text
-                    self.insert(start, &text)?;
-                    
-                    // Add to redo stack
-                    self.redo_stack.push(EditOperation::Delete {
-                        start,
-                        end: start + text.chars().count(),
-                        text,
-                    });
-                },
+            for operation in entry.operations.iter().rev() {
+                match operation {
+                    EditOperation::Insert { position, text } => {
+                        // To undo an insert, we delete the inserted text
+                        let end_pos = position + text.chars().count();
+                        self.apply_remove(*position, end_pos);
+                    },
+                    EditOperation::Delete { start, end: _, text } => {
+                        // To undo a delete, we insert the deleted text
+                        self.apply_insert(*start, text);
+                    },
+                }
             }
             
-            self.in_undo_redo = false;
-            Ok(())
-        } else {
-            // Nothing to undo
-            Ok(())
+            // Add to redo stack
+            self.redo_stack.push(entry);
         }
+        Ok(())
     }
 
     /// Performs a redo operation, reapplying a previously undone edit.
     pub fn redo(&mut self) -> Result<()> {
-        if let Some(operation) = self.redo_stack.pop() {
-            self.in_undo_redo = true;
+        if let Some(entry) = self.redo_stack.pop() {
+            self.revision = entry.after;
             
-            match operation {
-                EditOperation::Insert { position, text } => {
-                    // To redo an insert, we insert the text again
-                    self.insert(position, &text)?;
-                    
-                    // Add back to undo stack
-                    self.undo_stack.push(EditOperation::Insert {
-                        position,
-                        text,
-                    });
-                },
-                EditOperation::Delete { start, end, text } => {
-                    // To redo a delete, we delete the text again
-                    self.remove(start, end)?;
-                    
-                    // Add back to undo stack
-                    self.undo_stack.push(EditOperation::Delete {
-                        start,
-                        end,
-                        text,
-                    });
-                },
+            for operation in &entry.operations {
+                match operation {
+                    EditOperation::Insert { position, text } => {
+                        // To redo an insert, we insert the text again
+                        self.apply_insert(*position, text);
+                    },
+                    EditOperation::Delete { start, end, text: _ } => {
+                        // To redo a delete, we delete the text again
+                        self.apply_remove(*start, *end);
+                    },
+                }
             }
             
-            self.in_undo_redo = false;
-            Ok(())
-        } else {
-            // Nothing to redo
-            Ok(())
+            // Add back to undo stack
+            self.undo_stack.push(entry);
         }
+        Ok(())
     }
 
     /// Returns the total number of characters in the buffer.
@@ -289,32 +620,527 @@ impl TextBuffer {
         }
     }
     
-    /// Updates the buffer content from a string and records it as a single edit operation.
-    pub fn update_content(&mut self, new_content: &str) -> Result<()> {
-        // Record the entire change as a single edit operation
-        if !self.in_undo_redo {
-            let old_content = self.content.to_string();
-            
-            // Only record if there's an actual change
-            if old_content != new_content {
-                // For simplicity, we'll treat this as a delete-all + insert-all operation
-                self.undo_stack.push(EditOperation::Delete {
-                    start: 0,
-                    end: old_content.chars().count(),
-                    text: old_content,
-                });
-                
-                // Clear redo stack when a new edit is made
-                self.redo_stack.clear();
+    /// Makes the edits done by `edit` a single step for undo and redo.
+    ///
+    /// Groups inside a group join the outer one.
+    pub fn edit_group<T>(&mut self, edit: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.grouping {
+            return edit(self);
+        }
+        self.grouping = true;
+        self.group_recorded = false;
+        let result = edit(self);
+        self.grouping = false;
+        result
+    }
+    
+    /// Replaces the characters in `range` with `text` in a single undo step.
+    pub fn replace(&mut self, range: Range<usize>, text: &str) -> Result<()> {
+        self.edit_group(|buffer| {
+            if !range.is_empty() {
+                buffer.remove(range.start, range.end)?;
+            }
+            if !text.is_empty() {
+                buffer.insert(range.start, text)?;
+            }
+            Ok(())
+        })
+    }
+    
+    /// Indents every line in `lines` by one level in a single undo step.
+    ///
+    /// Blank lines are left alone when more than one line is indented.
+    pub fn indent_lines(&mut self, lines: Range<usize>, style: IndentStyle) -> Result<()> {
+        let unit = style.unit();
+        let skip_blank = lines.len() > 1;
+        self.edit_group(|buffer| {
+            for line in lines.rev() {
+                let text = buffer.line(line)?;
+                if skip_blank && text.trim().is_empty() {
+                    continue;
+                }
+                buffer.insert(buffer.content.line_to_char(line), &unit)?;
+            }
+            Ok(())
+        })
+    }
+    
+    /// Removes one level of indentation from every line in `lines` in a single
+    /// undo step.
+    pub fn outdent_lines(&mut self, lines: Range<usize>, style: IndentStyle) -> Result<()> {
+        self.edit_group(|buffer| {
+            for line in lines.rev() {
+                let remove = indent::outdent_len(&buffer.line(line)?, style);
+                if remove > 0 {
+                    let start = buffer.content.line_to_char(line);
+                    buffer.remove(start, start + remove)?;
+                }
             }
+            Ok(())
+        })
+    }
+    
+    /// Rewrites the indentation of every line from the `from` style to the `to`
+    /// one in a single undo step, returning the number of lines changed.
+    pub fn convert_indentation(&mut self, from: IndentStyle, to: IndentStyle) -> Result<usize> {
+        let edits = indent::conversions(&self.content, from, to);
+        self.edit_group(|buffer| {
+            for (range, text) in &edits {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(edits.len())
+        })
+    }
+    
+    /// Comments out the lines in `lines` with `token`, or uncomments them when
+    /// they all are, in a single undo step.
+    pub fn toggle_line_comments(&mut self, lines: Range<usize>, token: &str) -> Result<()> {
+        let edits = comment::toggle_line_comments(&self.content, lines, token);
+        self.edit_group(|buffer| {
+            for (range, text) in &edits {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(())
+        })
+    }
+    
+    /// Wraps `range` in a block comment from `open` to `close`, or removes the
+    /// comment around it, in a single undo step. Returns the range the text
+    /// and any added markers cover afterwards.
+    pub fn toggle_block_comment(&mut self, range: Range<usize>, open: &str, close: &str) -> Result<Range<usize>> {
+        let edits = comment::toggle_block_comment(&self.content, range, open, close);
+        let (first, last) = match (edits.last(), edits.first()) {
+            (Some(first), Some(last)) => (first.0.start, last.0.end),
+            _ => return Ok(0..0),
+        };
+        let growth: isize = edits
+            .iter()
+            .map(|(range, text)| text.chars().count() as isize - range.len() as isize)
+            .sum();
+        self.edit_group(|buffer| {
+            for (range, text) in &edits {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(first..(last as isize + growth) as usize)
+        })
+    }
+    
+    /// Types `c` over `selection` in a single undo step, closing, wrapping in
+    /// or typing over the pairs in `pairs` as [`brackets::type_char`] does.
+    /// Returns the selection after typing.
+    pub fn type_char(&mut self, selection: Range<usize>, c: char, pairs: &[(char, char)]) -> Result<Range<usize>> {
+        let Some((edits, after)) = brackets::type_char(&self.content, selection.clone(), c, pairs) else {
+            self.replace(selection.clone(), &c.to_string())?;
+            return Ok(selection.start + 1..selection.start + 1);
+        };
+        self.edit_group(|buffer| {
+            for (range, text) in &edits {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(after)
+        })
+    }
+    
+    /// Returns the number of lines, not counting the empty line after a final
+    /// line break.
+    pub fn text_lines(&self) -> usize {
+        let lines = self.len_lines();
+        if lines > 1 && self.content.line(lines - 1).len_chars() == 0 {
+            lines - 1
+        } else {
+            lines
+        }
+    }
+    
+    /// Returns the range covering `lines` from the start of the first to the
+    /// end of the last, without its line break.
+    pub fn lines_range(&self, lines: &Range<usize>) -> Range<usize> {
+        let start = self.content.line_to_char(lines.start);
+        let last = lines.end - 1;
+        let text = self.content.line(last).to_string();
+        let end = self.content.line_to_char(last) + text.trim_end_matches(['\n', '\r']).chars().count();
+        start..end
+    }
+    
+    /// Returns the text of each of `lines` without its line break.
+    fn line_texts(&self, lines: &Range<usize>) -> Vec<String> {
+        lines
+            .clone()
+            .map(|line| self.content.line(line).to_string().trim_end_matches(['\n', '\r']).to_string())
+            .collect()
+    }
+    
+    /// Returns the line break new lines are written with, following the first
+    /// line that has one.
+    fn line_break(&self) -> &'static str {
+        LineEnding::detect(&self.content).unwrap_or(LineEnding::Lf).as_str()
+    }
+    
+    /// Replaces `lines` by what `transform` makes of their text, in a single
+    /// undo step. Returns the range the new lines cover.
+    pub fn transform_lines(
+        &mut self,
+        lines: Range<usize>,
+        transform: impl FnOnce(Vec<String>) -> Vec<String>,
+    ) -> Result<Range<usize>> {
+        let range = self.lines_range(&lines);
+        let text = transform(self.line_texts(&lines)).join(self.line_break());
+        self.edit_group(|buffer| buffer.replace(range.clone(), &text))?;
+        Ok(range.start..range.start + text.chars().count())
+    }
+    
+    /// Moves `lines` up or down past the neighbouring line in a single undo
+    /// step. Returns how far the text moved, or `None` at the start or end.
+    pub fn move_lines(&mut self, lines: Range<usize>, up: bool) -> Result<Option<isize>> {
+        let break_len = self.line_break().chars().count() as isize;
+        if up && lines.start > 0 {
+            let above = self.line_texts(&(lines.start - 1..lines.start))[0].chars().count() as isize;
+            self.transform_lines(lines.start - 1..lines.end, |mut texts| {
+                texts.rotate_left(1);
+                texts
+            })?;
+            Ok(Some(-(above + break_len)))
+        } else if !up && lines.end < self.text_lines() {
+            let below = self.line_texts(&(lines.end..lines.end + 1))[0].chars().count() as isize;
+            self.transform_lines(lines.start..lines.end + 1, |mut texts| {
+                texts.rotate_right(1);
+                texts
+            })?;
+            Ok(Some(below + break_len))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Inserts a copy of `lines` below them, returning the number of
+    /// characters the copy added.
+    pub fn duplicate_lines(&mut self, lines: Range<usize>) -> Result<usize> {
+        let text = self.line_texts(&lines).join(self.line_break()) + self.line_break();
+        self.insert(self.content.line_to_char(lines.start), &text)?;
+        Ok(text.chars().count())
+    }
+    
+    /// Removes `lines` with their line breaks, returning the start of the line
+    /// that takes their place.
+    pub fn delete_lines(&mut self, lines: Range<usize>) -> Result<usize> {
+        let mut start = self.content.line_to_char(lines.start);
+        let end = if lines.end < self.len_lines() {
+            self.content.line_to_char(lines.end)
+        } else {
+            self.len_chars()
+        };
+        // The last line takes the line break before it along
+        if lines.end >= self.len_lines() && lines.start > 0 {
+            start = self.lines_range(&(lines.start - 1..lines.start)).end;
+        }
+        self.remove(start, end)?;
+        let line = lines.start.min(self.len_lines() - 1);
+        Ok(self.content.line_to_char(line))
+    }
+    
+    /// Joins `lines` into one, or a single line with the next, in a single
+    /// undo step. Each line break and the indentation after it become one
+    /// space. Returns where the first join was made, or `None` on the last line.
+    pub fn join_lines(&mut self, lines: Range<usize>) -> Result<Option<usize>> {
+        let lines = if lines.len() < 2 { lines.start..lines.start + 2 } else { lines };
+        if lines.end > self.text_lines() {
+            return Ok(None);
+        }
+        let texts = self.line_texts(&lines);
+        let joins: Vec<(Range<usize>, &str)> = (lines.start..lines.end - 1)
+            .zip(texts.windows(2))
+            .map(|(line, pair)| {
+                let end = self.lines_range(&(line..line + 1)).end;
+                let next = self.content.line_to_char(line + 1);
+                let indent = indent::leading_whitespace(&pair[1]).chars().count();
+                let bare = pair[0].ends_with([' ', '\t']) || pair[0].is_empty() || pair[1].trim().is_empty();
+                (end..next + indent, if bare { "" } else { " " })
+            })
+            .collect();
+        let first = joins[0].0.start;
+        self.edit_group(|buffer| {
+            for (range, text) in joins.iter().rev() {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(Some(first))
+        })
+    }
+    
+    /// Starts an empty line above or below `line`, indented like it. Returns
+    /// where the new line's indentation ends.
+    pub fn insert_line(&mut self, line: usize, below: bool) -> Result<usize> {
+        let text = self.line_texts(&(line..line + 1)).remove(0);
+        let indent = indent::leading_whitespace(&text).to_string();
+        let line_break = self.line_break();
+        if below {
+            let end = self.lines_range(&(line..line + 1)).end;
+            self.insert(end, &format!("{}{}", line_break, indent))?;
+            Ok(end + line_break.chars().count() + indent.chars().count())
+        } else {
+            let start = self.content.line_to_char(line);
+            self.insert(start, &format!("{}{}", indent, line_break))?;
+            Ok(start + indent.chars().count())
+        }
+    }
+
+    /// Returns the range of the word of letters, digits and underscores around
+    /// `pos`, which is empty when there is none.
+    pub fn word_range(&self, pos: usize) -> Range<usize> {
+        let pos = pos.min(self.len_chars());
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let before = self.content.chars_at(pos).reversed().take_while(|&c| is_word(c)).count();
+        let after = self.content.chars_at(pos).take_while(|&c| is_word(c)).count();
+        pos - before..pos + after
+    }
+
+    /// Rewrites the text of each of `ranges` with `transform` in a single undo
+    /// step, returning the ranges the new texts cover in order of position.
+    ///
+    /// Nothing changes when any of the texts can't be transformed.
+    pub fn transform_ranges(
+        &mut self,
+        ranges: &[Range<usize>],
+        transform: impl Fn(&str) -> Result<String>,
+    ) -> Result<Vec<Range<usize>>> {
+        let mut ranges = ranges.to_vec();
+        ranges.sort_by_key(|range| range.start);
+        let texts = ranges
+            .iter()
+            .map(|range| transform(&self.slice(range.start, range.end)?))
+            .collect::<Result<Vec<String>>>()?;
+
+        self.edit_group(|buffer| {
+            for (range, text) in ranges.iter().zip(&texts).rev() {
+                buffer.replace(range.clone(), text)?;
+            }
+            Ok(())
+        })?;
+
+        let mut shift = 0isize;
+        Ok(ranges
+            .iter()
+            .zip(&texts)
+            .map(|(range, text)| {
+                let start = (range.start as isize + shift) as usize;
+                let len = text.chars().count();
+                shift += len as isize - range.len() as isize;
+                start..start + len
+            })
+            .collect())
+    }
+
+    /// Updates the buffer content from a string.
+    ///
+    /// Only the part that differs from the current content is replaced, so the
+    /// change is recorded as a small edit and bookmarks elsewhere stay put.
+    pub fn update_content(&mut self, new_content: &str) -> Result<()> {
+        match diff(&self.content.to_string(), new_content) {
+            Some((range, text)) => self.replace(range, &text),
+            None => Ok(()),
+        }
+    }
+    
+    /// Starts tracking `position` as the buffer is edited.
+    ///
+    /// The anchor lives until it is removed with [`TextBuffer::remove_anchor`].
+    pub fn create_anchor(&mut self, position: usize, gravity: Gravity) -> Result<AnchorId> {
+        if position <= self.content.len_chars() {
+            Ok(self.anchors.create(position, gravity))
+        } else {
+            Err(anyhow::anyhow!("Character index out of bounds"))
+        }
+    }
+    
+    /// Returns the current position of an anchor, or `None` if it was removed.
+    pub fn anchor_position(&self, anchor: AnchorId) -> Option<usize> {
+        self.anchors.position(anchor)
+    }
+    
+    /// Stops tracking an anchor.
+    pub fn remove_anchor(&mut self, anchor: AnchorId) {
+        self.anchors.remove(anchor);
+    }
+    
+    /// Returns the line an anchor is on.
+    fn anchor_line(&self, anchor: AnchorId) -> usize {
+        let position = self.anchors.position(anchor).unwrap_or(0);
+        self.content.char_to_line(position.min(self.content.len_chars()))
+    }
+    
+    /// Returns the line a bookmark is on.
+    fn bookmark_line(&self, bookmark: &Bookmark) -> usize {
+        self.anchor_line(bookmark.anchor)
+    }
+    
+    /// Returns the bookmarked lines in order, with their names.
+    pub fn bookmarks(&self) -> Vec<(usize, Option<&str>)> {
+        let mut lines: Vec<(usize, Option<&str>)> = self
+            .bookmarks
+            .iter()
+            .map(|bookmark| (self.bookmark_line(bookmark), bookmark.name.as_deref()))
+            .collect();
+        lines.sort_by_key(|(line, _)| *line);
+        lines.dedup_by_key(|(line, _)| *line);
+        lines
+    }
+    
+    /// Returns whether `line` has a bookmark.
+    pub fn is_bookmarked(&self, line: usize) -> bool {
+        self.bookmarks
+            .iter()
+            .any(|bookmark| self.bookmark_line(bookmark) == line)
+    }
+    
+    /// Bookmarks `line`, or renames its bookmark if it already has one.
+    pub fn set_bookmark(&mut self, line: usize, name: Option<String>) -> Result<()> {
+        if line >= self.content.len_lines() {
+            return Err(anyhow::anyhow!("Line index out of bounds"));
         }
         
-        // Update the content
-        self.content = Rope::from_str(new_content);
-        self.modified = true;
-        
+        self.remove_bookmark(line);
+        let anchor = self.anchors.create(self.content.line_to_char(line), Gravity::Right);
+        self.bookmarks.push(Bookmark {
+            anchor,
+            name: name.filter(|name| !name.trim().is_empty()),
+        });
         Ok(())
     }
+    
+    /// Removes the bookmark from `line`, if any.
+    pub fn remove_bookmark(&mut self, line: usize) {
+        let (removed, kept): (Vec<Bookmark>, Vec<Bookmark>) = std::mem::take(&mut self.bookmarks)
+            .into_iter()
+            .partition(|bookmark| self.bookmark_line(bookmark) == line);
+        for bookmark in removed {
+            self.anchors.remove(bookmark.anchor);
+        }
+        self.bookmarks = kept;
+    }
+    
+    /// Adds an anonymous bookmark to `line` or removes the one it has.
+    ///
+    /// Returns whether the line is bookmarked afterwards.
+    pub fn toggle_bookmark(&mut self, line: usize) -> Result<bool> {
+        if self.is_bookmarked(line) {
+            self.remove_bookmark(line);
+            Ok(false)
+        } else {
+            self.set_bookmark(line, None)?;
+            Ok(true)
+        }
+    }
+    
+    /// Removes all bookmarks.
+    pub fn clear_bookmarks(&mut self) {
+        for bookmark in std::mem::take(&mut self.bookmarks) {
+            self.anchors.remove(bookmark.anchor);
+        }
+    }
+    
+    /// Returns the next bookmarked line after `line`, or the previous one before
+    /// it when `forward` is false, wrapping around the ends of the buffer.
+    pub fn next_bookmark(&self, line: usize, forward: bool) -> Option<usize> {
+        let lines: Vec<usize> = self.bookmarks().into_iter().map(|(line, _)| line).collect();
+        if forward {
+            lines.iter().find(|&&l| l > line).or(lines.first()).copied()
+        } else {
+            lines.iter().rev().find(|&&l| l < line).or(lines.last()).copied()
+        }
+    }
+    
+    /// Returns the regions of the buffer that can be folded.
+    pub fn fold_regions(&self) -> Vec<FoldRegion> {
+        fold::regions(&self.content)
+    }
+    
+    /// Returns the first lines of the folded regions, in order.
+    pub fn folded_lines(&self) -> Vec<usize> {
+        let mut lines: Vec<usize> = self.folds.iter().map(|&anchor| self.anchor_line(anchor)).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    }
+    
+    /// Returns whether the region starting on `line` is folded.
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.folds.iter().any(|&anchor| self.anchor_line(anchor) == line)
+    }
+    
+    /// Folds the region starting on `line`.
+    ///
+    /// The fold stays on the line as the buffer is edited, and takes effect
+    /// whenever a region starts there.
+    pub fn fold(&mut self, line: usize) -> Result<()> {
+        if line >= self.content.len_lines() {
+            return Err(anyhow::anyhow!("Line index out of bounds"));
+        }
+        if !self.is_folded(line) {
+            let anchor = self.anchors.create(self.content.line_to_char(line), Gravity::Right);
+            self.folds.push(anchor);
+        }
+        Ok(())
+    }
+    
+    /// Unfolds the region starting on `line`, returning whether it was folded.
+    pub fn unfold(&mut self, line: usize) -> bool {
+        let (removed, kept): (Vec<AnchorId>, Vec<AnchorId>) = std::mem::take(&mut self.folds)
+            .into_iter()
+            .partition(|&anchor| self.anchor_line(anchor) == line);
+        for &anchor in &removed {
+            self.anchors.remove(anchor);
+        }
+        self.folds = kept;
+        !removed.is_empty()
+    }
+    
+    /// Unfolds every region.
+    pub fn unfold_all(&mut self) {
+        for anchor in std::mem::take(&mut self.folds) {
+            self.anchors.remove(anchor);
+        }
+    }
+    
+    /// Returns the map to the text shown with the folded ones among `regions`
+    /// collapsed.
+    pub fn fold_map(&self, regions: &[FoldRegion]) -> FoldMap {
+        let folded_lines = self.folded_lines();
+        let folded: Vec<FoldRegion> = regions
+            .iter()
+            .filter(|region| folded_lines.binary_search(&region.start_line).is_ok())
+            .copied()
+            .collect();
+        FoldMap::new(&self.content, &folded)
+    }
+}
+
+/// Finds the smallest edit that turns `old` into `new`.
+///
+/// Returns the character range of `old` to replace and the text replacing it, or
+/// `None` if the strings are equal.
+pub fn diff(old: &str, new: &str) -> Option<(Range<usize>, String)> {
+    if old == new {
+        return None;
+    }
+    
+    // Find the changed range by skipping the common prefix and suffix
+    let prefix = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_len = old.chars().count();
+    let new_len = new.chars().count();
+    let suffix = old
+        .chars()
+        .rev()
+        .zip(new.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(old_len - prefix)
+        .min(new_len - prefix);
+    
+    let inserted = new.chars().skip(prefix).take(new_len - suffix - prefix).collect();
+    Some((prefix..old_len - suffix, inserted))
 }
 
 impl Default for TextBuffer {
@@ -323,6 +1149,38 @@ impl Default for TextBuffer {
     }
 }
 
+/// Writes `content` to a temporary file beside `path`, flushes it to disk and
+/// moves it over `path`, keeping the permissions of the file it replaces.
+///
+/// A new file gets the permissions the umask allows, as if it were created
+/// directly. A symlink at `path` keeps pointing at the file it did, which is
+/// the one written.
+fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let existing = std::fs::metadata(&target).ok();
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".zing-save");
+    // Temporary files are only readable by their owner unless asked otherwise,
+    // and the mode asked for here is narrowed by the umask
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+    temp.write_all(content)?;
+    temp.as_file().sync_all()?;
+    if let Some(metadata) = existing {
+        temp.as_file().set_permissions(metadata.permissions())?;
+    }
+    temp.persist(&target)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,6 +1244,421 @@ mod tests {
         assert_eq!(buffer.content.to_string(), "");
     }
 
+    #[test]
+    fn test_edit_group() {
+        let mut buffer = TextBuffer::from_str("a\nb\n");
+        let before = buffer.revision();
+        buffer.edit_group(|buffer| {
+            buffer.insert(2, "  ")?;
+            buffer.edit_group(|buffer| buffer.insert(0, "  "))
+        }).unwrap();
+        assert_eq!(buffer.content.to_string(), "  a\n  b\n");
+        
+        // The whole group is undone and redone in one step
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "a\nb\n");
+        assert_eq!(buffer.revision(), before);
+        buffer.redo().unwrap();
+        assert_eq!(buffer.content.to_string(), "  a\n  b\n");
+        
+        // Edits after the group are separate steps again
+        buffer.insert(0, "x").unwrap();
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "  a\n  b\n");
+    }
+
+    #[test]
+    fn test_indent_lines() {
+        let style = IndentStyle::new(true, 2);
+        let mut buffer = TextBuffer::from_str("a\n\n\tb\n");
+        buffer.indent_lines(0..3, style).unwrap();
+        assert_eq!(buffer.content.to_string(), "  a\n\n  \tb\n");
+        buffer.outdent_lines(0..3, style).unwrap();
+        buffer.outdent_lines(0..3, style).unwrap();
+        assert_eq!(buffer.content.to_string(), "a\n\nb\n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "a\n\n\tb\n");
+        
+        assert_eq!(buffer.convert_indentation(IndentStyle::new(false, 2), style).unwrap(), 1);
+        assert_eq!(buffer.content.to_string(), "a\n\n  b\n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "a\n\n\tb\n");
+    }
+
+    #[test]
+    fn test_toggle_comments() {
+        let mut buffer = TextBuffer::from_str("a\n  b\n");
+        buffer.toggle_line_comments(0..2, "//").unwrap();
+        assert_eq!(buffer.content.to_string(), "// a\n//   b\n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "a\n  b\n");
+        
+        let range = buffer.toggle_block_comment(4..5, "/*", "*/").unwrap();
+        assert_eq!(buffer.content.to_string(), "a\n  /* b */\n");
+        assert_eq!(range, 4..11);
+        assert_eq!(buffer.toggle_block_comment(range, "/*", "*/").unwrap(), 4..5);
+        assert_eq!(buffer.content.to_string(), "a\n  b\n");
+    }
+
+    #[test]
+    fn test_line_editing() {
+        let mut buffer = TextBuffer::from_str("a\nb\nc\n");
+        assert_eq!(buffer.text_lines(), 3);
+        assert_eq!(buffer.move_lines(0..1, true).unwrap(), None);
+        assert_eq!(buffer.move_lines(1..2, true).unwrap(), Some(-2));
+        assert_eq!(buffer.content.to_string(), "b\na\nc\n");
+        assert_eq!(buffer.move_lines(0..2, false).unwrap(), Some(2));
+        assert_eq!(buffer.content.to_string(), "c\nb\na\n");
+        assert_eq!(buffer.move_lines(2..3, false).unwrap(), None);
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "b\na\nc\n");
+        
+        assert_eq!(buffer.duplicate_lines(1..3).unwrap(), 4);
+        assert_eq!(buffer.content.to_string(), "b\na\nc\na\nc\n");
+        assert_eq!(buffer.delete_lines(3..5).unwrap(), 6);
+        assert_eq!(buffer.content.to_string(), "b\na\nc\n");
+        
+        let mut buffer = TextBuffer::from_str("x\ny");
+        assert_eq!(buffer.delete_lines(1..2).unwrap(), 0);
+        assert_eq!(buffer.content.to_string(), "x");
+    }
+
+    #[test]
+    fn test_join_and_insert_lines() {
+        let mut buffer = TextBuffer::from_str("fn a(\n    b,\n    c\n)\n");
+        assert_eq!(buffer.join_lines(0..3).unwrap(), Some(5));
+        assert_eq!(buffer.content.to_string(), "fn a( b, c\n)\n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.join_lines(3..3).unwrap(), None);
+        
+        assert_eq!(buffer.insert_line(1, true).unwrap(), 17);
+        assert_eq!(buffer.content.to_string(), "fn a(\n    b,\n    \n    c\n)\n");
+        assert_eq!(buffer.insert_line(0, false).unwrap(), 0);
+        assert_eq!(buffer.content.to_string(), "\nfn a(\n    b,\n    \n    c\n)\n");
+        
+        let mut buffer = TextBuffer::from_str("b\n\na\nb\n");
+        let range = buffer.transform_lines(0..4, lines::remove_blank).unwrap();
+        assert_eq!(buffer.content.to_string(), "b\na\nb\n");
+        assert_eq!(range, 0..5);
+        buffer.transform_lines(0..3, |texts| lines::sort(texts, lines::SortOptions::default())).unwrap();
+        assert_eq!(buffer.content.to_string(), "a\nb\nb\n");
+    }
+
+    #[test]
+    fn test_type_char() {
+        let pairs = [('(', ')'), ('"', '"')];
+        let mut buffer = TextBuffer::from_str("f\n");
+        assert_eq!(buffer.type_char(1..1, '(', &pairs).unwrap(), 2..2);
+        assert_eq!(buffer.type_char(2..2, 'x', &pairs).unwrap(), 3..3);
+        assert_eq!(buffer.type_char(3..3, ')', &pairs).unwrap(), 4..4);
+        assert_eq!(buffer.content.to_string(), "f(x)\n");
+        assert_eq!(buffer.type_char(2..3, '"', &pairs).unwrap(), 3..4);
+        assert_eq!(buffer.content.to_string(), "f(\"x\")\n");
+        
+        // Wrapping undoes in one step
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "f(x)\n");
+    }
+    
+    #[test]
+    fn test_transform_ranges() {
+        let mut buffer = TextBuffer::from_str("one two_three four\n");
+        assert_eq!(buffer.word_range(6), 4..13);
+        assert_eq!(buffer.word_range(3), 0..3);
+        assert_eq!(buffer.word_range(19), 19..19);
+        
+        let upper = |text: &str| transform::Transform::Upper.apply(text);
+        let ranges = buffer.transform_ranges(&[14..18, 4..13], |text| transform::Transform::Pascal.apply(text)).unwrap();
+        assert_eq!(ranges, vec![4..12, 13..17]);
+        assert_eq!(buffer.content.to_string(), "one TwoThree Four\n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "one two_three four\n");
+        
+        // A text that can't be transformed leaves every range alone
+        assert!(buffer.transform_ranges(&[0..3, 4..7], |text| {
+            if text == "two" { anyhow::bail!("no") } else { upper(text) }
+        }).is_err());
+        assert_eq!(buffer.content.to_string(), "one two_three four\n");
+    }
+    
+    #[tokio::test]
+    async fn test_save_applies_format() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut buffer = TextBuffer::from_str("a  \nb\t");
+        buffer.format = FileFormat {
+            line_ending: Some(LineEnding::Crlf),
+            trim_trailing_whitespace: Some(true),
+            ..FileFormat::default()
+        };
+        let defaults = FileFormat {
+            insert_final_newline: Some(true),
+            trim_trailing_whitespace: Some(false),
+            ..FileFormat::default()
+        };
+        buffer.prepare_save(&defaults, None).unwrap();
+        buffer.save_to(file.path()).await.unwrap();
+        assert_eq!(std::fs::read(file.path()).unwrap(), b"a\r\nb\r\n");
+        assert_eq!(buffer.content.to_string(), "a\r\nb\r\n");
+        assert!(!buffer.is_modified());
+        
+        // The clean-up undoes in one step
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "a  \nb\t");
+    }
+    
+    #[tokio::test]
+    async fn test_save_replaces_file_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "old text").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        }
+        
+        let mut buffer = TextBuffer::from_file(&path, None).await.unwrap();
+        buffer.update_content("new").unwrap();
+        buffer.save().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        
+        // The temporary file is gone and the permissions are kept
+        let names: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, vec!["notes.txt"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o640);
+        }
+        
+        // A new file gets the permissions the umask allows, not owner-only ones
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let fresh = dir.path().join("fresh.txt");
+            let mut created = TextBuffer::from_str("fresh");
+            created.save_to(&fresh).await.unwrap();
+            let probe = dir.path().join("probe.txt");
+            std::fs::write(&probe, "").unwrap();
+            let expected = std::fs::metadata(&probe).unwrap().permissions().mode() & 0o777;
+            std::fs::remove_file(&probe).unwrap();
+            assert_eq!(std::fs::metadata(&fresh).unwrap().permissions().mode() & 0o777, expected);
+            std::fs::remove_file(&fresh).unwrap();
+        }
+        
+        // Saving through a symlink writes the file it points at
+        #[cfg(unix)]
+        {
+            let link = dir.path().join("link.txt");
+            std::os::unix::fs::symlink(&path, &link).unwrap();
+            let mut linked = TextBuffer::from_str("linked");
+            linked.save_to(&link).await.unwrap();
+            assert!(std::fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "linked");
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_save_keeps_changes_made_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "first").unwrap();
+        let mut buffer = TextBuffer::from_file(&path, None).await.unwrap();
+        buffer.insert(5, "!").unwrap();
+        buffer.check_disk().unwrap();
+        
+        // Another program rewrites the file after it was opened
+        std::fs::write(&path, "rewritten elsewhere").unwrap();
+        assert!(buffer.check_disk().unwrap_err().downcast_ref::<ChangedOnDisk>().is_some());
+        let err = buffer.save().await.unwrap_err();
+        assert!(err.downcast_ref::<ChangedOnDisk>().is_some());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "rewritten elsewhere");
+        assert!(buffer.is_modified());
+        
+        // Overwriting on purpose saves, after which saving works as usual
+        buffer.ignore_disk_changes();
+        buffer.save().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first!");
+        buffer.insert(0, "> ").unwrap();
+        buffer.save().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "> first!");
+    }
+    
+    #[test]
+    fn test_prepare_save() {
+        let mut buffer = TextBuffer::from_str("fn main() {  \n}  \n\n\n");
+        buffer.insert(0, " ").unwrap();
+        buffer.undo().unwrap();
+        buffer.insert(11, "x").unwrap();
+        let format = FileFormat {
+            trim_trailing_whitespace: Some(true),
+            trim_modified_lines_only: Some(true),
+            insert_final_newline: Some(true),
+            trim_final_newlines: Some(true),
+            ..FileFormat::default()
+        };
+        
+        // Only the edited line is trimmed, and the formatter's text goes first
+        buffer.prepare_save(&format, Some("fn main() {x  \n}  \n\n\n")).unwrap();
+        assert_eq!(buffer.content.to_string(), "fn main() {x\n}  \n");
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "fn main() {x  \n}  \n\n\n");
+    }
+
+    #[test]
+    fn test_update_content_records_minimal_edit() {
+        let mut buffer = TextBuffer::from_str("let x = 1;\nlet y = 2;\n");
+        buffer.update_content("let x = 10;\nlet y = 2;\n").unwrap();
+        assert_eq!(buffer.content.to_string(), "let x = 10;\nlet y = 2;\n");
+        
+        // Undo only reverts the typed character instead of duplicating the text
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "let x = 1;\nlet y = 2;\n");
+    }
+    
+    #[test]
+    fn test_bookmarks_follow_edits() {
+        let mut buffer = TextBuffer::from_str("one\ntwo\nthree\n");
+        assert!(buffer.toggle_bookmark(1).unwrap());
+        buffer.set_bookmark(2, Some("end".to_string())).unwrap();
+        
+        // Lines inserted above push the bookmarks down
+        buffer.insert(0, "zero\n").unwrap();
+        assert_eq!(buffer.bookmarks(), vec![(2, None), (3, Some("end"))]);
+        
+        // Typing at the start of a bookmarked line keeps it on that line
+        buffer.update_content("zero\none\n  two\nthree\n").unwrap();
+        assert!(buffer.is_bookmarked(2));
+        
+        // Removing the lines above pulls them back up
+        buffer.remove(0, 9).unwrap();
+        assert_eq!(buffer.bookmarks(), vec![(0, None), (1, Some("end"))]);
+        
+        assert_eq!(buffer.next_bookmark(0, true), Some(1));
+        assert_eq!(buffer.next_bookmark(1, true), Some(0));
+        assert_eq!(buffer.next_bookmark(0, false), Some(1));
+        
+        assert!(!buffer.toggle_bookmark(0).unwrap());
+        assert_eq!(buffer.bookmarks(), vec![(1, Some("end"))]);
+    }
+
+    #[test]
+    fn test_anchors_follow_undo_redo() {
+        let mut buffer = TextBuffer::from_str("hello world");
+        let word = buffer.create_anchor(6, Gravity::Left).unwrap();
+        let end = buffer.create_anchor(11, Gravity::Right).unwrap();
+        
+        buffer.insert(0, ">> ").unwrap();
+        buffer.insert(14, "!").unwrap();
+        assert_eq!(buffer.anchor_position(word), Some(9));
+        assert_eq!(buffer.anchor_position(end), Some(15));
+        
+        buffer.undo().unwrap();
+        buffer.undo().unwrap();
+        assert_eq!(buffer.anchor_position(word), Some(6));
+        assert_eq!(buffer.anchor_position(end), Some(11));
+        
+        buffer.redo().unwrap();
+        assert_eq!(buffer.anchor_position(word), Some(9));
+        
+        buffer.remove_anchor(word);
+        assert_eq!(buffer.anchor_position(word), None);
+        assert!(buffer.create_anchor(100, Gravity::Left).is_err());
+    }
+
+    #[test]
+    fn test_modified_follows_revisions() {
+        let mut buffer = TextBuffer::from_str("saved");
+        assert!(!buffer.is_modified());
+        
+        buffer.insert(5, "!").unwrap();
+        assert!(buffer.is_modified());
+        
+        // Undoing back to the saved content is not a modification
+        buffer.undo().unwrap();
+        assert!(!buffer.is_modified());
+        buffer.redo().unwrap();
+        assert!(buffer.is_modified());
+        
+        buffer.mark_saved();
+        buffer.undo().unwrap();
+        assert!(buffer.is_modified());
+        
+        // A different edit gets a new revision even with the same length
+        buffer.insert(5, "?").unwrap();
+        assert!(buffer.is_modified());
+    }
+
+    #[test]
+    fn test_changed_lines() {
+        let mut buffer = TextBuffer::from_str("one\ntwo\nthree\nfour\n");
+        buffer.insert(4, "2\n").unwrap();
+        assert_eq!(buffer.changed_lines().collect::<Vec<_>>(), vec![1, 2]);
+        
+        // Lines after an edit move with it, and removed lines are dropped
+        buffer.remove(0, 4).unwrap();
+        assert_eq!(buffer.changed_lines().collect::<Vec<_>>(), vec![0, 1]);
+        buffer.insert(buffer.len_chars(), "five\n").unwrap();
+        assert_eq!(buffer.changed_lines().collect::<Vec<_>>(), vec![0, 1, 4, 5]);
+        
+        buffer.mark_saved();
+        assert_eq!(buffer.changed_lines().count(), 0);
+        buffer.insert(0, "x").unwrap();
+        buffer.undo().unwrap();
+        assert_eq!(buffer.changed_lines().count(), 0);
+    }
+
+    #[test]
+    fn test_change_events() {
+        let mut buffer = TextBuffer::from_str("hello world");
+        let events = buffer.subscribe();
+        
+        buffer.update_content("hello there").unwrap();
+        let removed = events.try_recv().unwrap();
+        let inserted = events.try_recv().unwrap();
+        assert_eq!((removed.start, removed.old_text.as_str(), removed.new_text.as_str()), (6, "world", ""));
+        assert_eq!((inserted.start, inserted.old_text.as_str(), inserted.new_text.as_str()), (6, "", "there"));
+        assert_eq!(inserted.new_end(), 11);
+        // Both halves of the replacement are one edit
+        assert_eq!(inserted.revision, removed.revision);
+        assert_eq!(inserted.revision, buffer.revision());
+        
+        // A single undo restores the whole replaced text
+        buffer.undo().unwrap();
+        assert_eq!(buffer.content.to_string(), "hello world");
+        let undone = events.try_recv().unwrap();
+        let restored = events.try_recv().unwrap();
+        assert_eq!(undone.old_text, "there");
+        assert_eq!(restored.new_text, "world");
+        assert!(undone.revision < removed.revision);
+        assert_eq!(restored.revision, buffer.revision());
+        assert!(events.try_recv().is_err());
+        
+        // Dropped receivers stop being notified
+        drop(events);
+        buffer.insert(0, ">").unwrap();
+        assert!(buffer.subscribers.0.is_empty());
+    }
+
+    #[test]
+    fn test_folds_follow_edits() {
+        let mut buffer = TextBuffer::from_str("fn a() {\n    1\n}\nfn b() {\n    2\n}\n");
+        let regions = buffer.fold_regions();
+        assert_eq!(regions.iter().map(|r| r.start_line).collect::<Vec<_>>(), vec![0, 3]);
+        
+        buffer.fold(3).unwrap();
+        assert_eq!(buffer.fold_map(&regions).display_text(&buffer.content), "fn a() {\n    1\n}\nfn b() { ⋯\n}\n");
+        
+        // The fold moves with its line
+        buffer.insert(0, "// top\n").unwrap();
+        assert_eq!(buffer.folded_lines(), vec![4]);
+        assert!(buffer.is_folded(4));
+        
+        assert!(buffer.unfold(4));
+        assert!(!buffer.unfold(4));
+        assert!(buffer.folded_lines().is_empty());
+    }
+
     #[test]
     fn test_line_operations() {
         let text = "Line 1\nLine 2\nLine 3";
@@ -0,0 +1,293 @@
+//! Transformations of selected text: case changes, encodings and hashing.
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+/// A rewrite of selected text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// `UPPER CASE`
+    Upper,
+    /// `lower case`
+    Lower,
+    /// `Title Case`
+    Title,
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+    /// `PascalCase`
+    Pascal,
+    /// Upper case letters become lower case and the other way around
+    SwapCase,
+    /// Base64 of the UTF-8 bytes
+    Base64Encode,
+    /// Text from its Base64
+    Base64Decode,
+    /// Percent-encoding of everything but unreserved URL characters
+    UrlEncode,
+    /// Text from its percent-encoding
+    UrlDecode,
+    /// The contents of a JSON string holding the text
+    JsonEscape,
+    /// Text from the contents of a JSON string, with or without its quotes
+    JsonUnescape,
+}
+
+impl Transform {
+    /// Every transformation, in the order menus list them.
+    pub const ALL: [Transform; 14] = [
+        Transform::Upper,
+        Transform::Lower,
+        Transform::Title,
+        Transform::Camel,
+        Transform::Snake,
+        Transform::Kebab,
+        Transform::Pascal,
+        Transform::SwapCase,
+        Transform::Base64Encode,
+        Transform::Base64Decode,
+        Transform::UrlEncode,
+        Transform::UrlDecode,
+        Transform::JsonEscape,
+        Transform::JsonUnescape,
+    ];
+
+    /// Returns `text` rewritten, or why it can't be.
+    ///
+    /// Identifier cases apply to each line on its own, keeping the whitespace
+    /// around it.
+    pub fn apply(self, text: &str) -> Result<String> {
+        Ok(match self {
+            Transform::Upper => text.to_uppercase(),
+            Transform::Lower => text.to_lowercase(),
+            Transform::Title => title_case(text),
+            Transform::Camel => per_line(text, |words| join_capitalized(words, false)),
+            Transform::Snake => per_line(text, |words| join_lower(words, "_")),
+            Transform::Kebab => per_line(text, |words| join_lower(words, "-")),
+            Transform::Pascal => per_line(text, |words| join_capitalized(words, true)),
+            Transform::SwapCase => swap_case(text),
+            Transform::Base64Encode => STANDARD.encode(text),
+            Transform::Base64Decode => {
+                String::from_utf8(base64_decode(text)?).context("Decoded Base64 is not UTF-8 text")?
+            }
+            Transform::UrlEncode => url_encode(text),
+            Transform::UrlDecode => url_decode(text)?,
+            Transform::JsonEscape => {
+                let quoted = serde_json::to_string(text)?;
+                quoted[1..quoted.len() - 1].to_string()
+            }
+            Transform::JsonUnescape => json_unescape(text)?,
+        })
+    }
+}
+
+/// Splits an identifier or phrase into words at separators, at changes from
+/// lower to upper case and before the last capital of an acronym.
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if let Some(&prev) = word.chars().last().as_ref() {
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            let boundary = c.is_uppercase()
+                && ((prev.is_lowercase() || prev.is_ascii_digit()) || (prev.is_uppercase() && next_lower));
+            if boundary {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Rewrites the words of each line of `text` with `join`, keeping the
+/// indentation, trailing whitespace and line breaks.
+fn per_line(text: &str, join: impl Fn(Vec<String>) -> String) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let body = line.trim_end_matches(['\n', '\r']);
+            let trimmed = body.trim();
+            if trimmed.is_empty() {
+                return line.to_string();
+            }
+            let start = body.len() - body.trim_start().len();
+            let end = start + trimmed.len();
+            format!("{}{}{}", &line[..start], join(words(trimmed)), &line[end..])
+        })
+        .collect()
+}
+
+/// Returns `word` with its first letter upper case and the rest lower case.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// Joins `words` capitalized, with the first one lower case unless `pascal`.
+fn join_capitalized(words: Vec<String>, pascal: bool) -> String {
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if i == 0 && !pascal { word.to_lowercase() } else { capitalize(word) })
+        .collect()
+}
+
+/// Joins `words` in lower case with `separator` between them.
+fn join_lower(words: Vec<String>, separator: &str) -> String {
+    words.iter().map(|word| word.to_lowercase()).collect::<Vec<_>>().join(separator)
+}
+
+/// Capitalizes every word of `text`, leaving everything between words alone.
+fn title_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    for c in text.chars() {
+        // An apostrophe inside a word, as in "don't", doesn't start a new one
+        let starts_word = !prev.is_some_and(|prev| prev.is_alphanumeric() || prev == '\'');
+        if starts_word {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+    result
+}
+
+/// Turns upper case letters lower case and lower case letters upper case.
+fn swap_case(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_uppercase() {
+            result.extend(c.to_lowercase());
+        } else if c.is_lowercase() {
+            result.extend(c.to_uppercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Decodes standard or URL-safe Base64, ignoring whitespace and missing padding.
+fn base64_decode(text: &str) -> Result<Vec<u8>> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let data = digits.trim_end_matches('=');
+    let engine = if data.contains(['-', '_']) { URL_SAFE_NO_PAD } else { STANDARD_NO_PAD };
+    engine.decode(data).context("Not valid Base64")
+}
+
+/// Percent-encodes every byte of `text` except letters, digits and `-_.~`.
+fn url_encode(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    result
+}
+
+/// Decodes the `%XX` escapes in `text`.
+fn url_decode(text: &str) -> Result<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).and_then(|hex| std::str::from_utf8(hex).ok());
+            let value = hex
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| anyhow!("Invalid percent escape"))?;
+            bytes.push(value);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).context("Decoded URL text is not UTF-8")
+}
+
+/// Reads the contents of a JSON string, which may keep its quotes.
+fn json_unescape(text: &str) -> Result<String> {
+    let quoted = text.len() >= 2 && text.starts_with('"') && text.ends_with('"');
+    let json = if quoted { text.to_string() } else { format!("\"{}\"", text) };
+    serde_json::from_str(&json).context("Not a valid JSON string")
+}
+
+/// Returns the SHA-256 digest of the UTF-8 bytes of `text` in lower case hex.
+pub fn sha256_hex(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(transform: Transform, text: &str) -> String {
+        transform.apply(text).unwrap()
+    }
+
+    #[test]
+    fn test_case_transforms() {
+        assert_eq!(apply(Transform::Upper, "straße"), "STRASSE");
+        assert_eq!(apply(Transform::Lower, "Hello World"), "hello world");
+        assert_eq!(apply(Transform::Title, "don't stop-me now"), "Don't Stop-Me Now");
+        assert_eq!(apply(Transform::SwapCase, "Hello, World"), "hELLO, wORLD");
+
+        let text = "  parseHTTPResponse2Json\nuser_id value\n";
+        assert_eq!(apply(Transform::Snake, text), "  parse_http_response2_json\nuser_id_value\n");
+        assert_eq!(apply(Transform::Kebab, text), "  parse-http-response2-json\nuser-id-value\n");
+        assert_eq!(apply(Transform::Camel, "user_id value"), "userIdValue");
+        assert_eq!(apply(Transform::Pascal, "  xml-http request \r\n"), "  XmlHttpRequest \r\n");
+    }
+
+    #[test]
+    fn test_encodings() {
+        for text in ["", "f", "fo", "foo", "foob", "héllo wörld"] {
+            assert_eq!(apply(Transform::Base64Decode, &apply(Transform::Base64Encode, text)), text);
+        }
+        assert_eq!(apply(Transform::Base64Encode, "foob"), "Zm9vYg==");
+        assert_eq!(apply(Transform::Base64Decode, "Zm9v\nYg"), "foob");
+        assert_eq!(apply(Transform::Base64Encode, "?>?"), "Pz4/");
+        assert_eq!(apply(Transform::Base64Decode, "Pz4_"), "?>?");
+        assert!(Transform::Base64Decode.apply("Zm9v!").is_err());
+
+        assert_eq!(apply(Transform::UrlEncode, "a b&c=é~"), "a%20b%26c%3D%C3%A9~");
+        assert_eq!(apply(Transform::UrlDecode, "a%20b%26c%3D%C3%A9~"), "a b&c=é~");
+        assert!(Transform::UrlDecode.apply("100%").is_err());
+
+        assert_eq!(apply(Transform::JsonEscape, "say \"hi\"\n\t\\"), r#"say \"hi\"\n\t\\"#);
+        assert_eq!(apply(Transform::JsonUnescape, r#"say \"hi\"\n"#), "say \"hi\"\n");
+        assert_eq!(apply(Transform::JsonUnescape, r#""é""#), "é");
+        assert!(Transform::JsonUnescape.apply(r#"bad \q"#).is_err());
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let long = "a".repeat(1000);
+        assert_eq!(sha256_hex(&long), "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+    }
+}
@@ -0,0 +1,178 @@
+//! Clipboard module for Zing text editor.
+//!
+//! This module provides access to the system clipboard, a history of recently
+//! copied text and helpers for pasting with indentation and copying as HTML.
+
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+
+use crate::config::Theme;
+use crate::syntax;
+
+/// Default number of entries kept in the clipboard history.
+const DEFAULT_CAPACITY: usize = 20;
+
+/// A piece of text that was copied or cut in the editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardEntry {
+    /// The copied text
+    pub text: String,
+    /// Whether whole lines were copied because nothing was selected
+    pub line_mode: bool,
+}
+
+/// A bounded history of copied text, most recent first.
+#[derive(Debug, Clone)]
+pub struct ClipboardRing {
+    entries: VecDeque<ClipboardEntry>,
+    capacity: usize,
+}
+
+impl ClipboardRing {
+    /// Creates an empty history with the default capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Creates an empty history holding at most `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Records a copy, moving an identical earlier entry to the front.
+    pub fn push(&mut self, text: String, line_mode: bool) {
+        if text.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry.text != text);
+        self.entries.push_front(ClipboardEntry { text, line_mode });
+        self.entries.truncate(self.capacity);
+    }
+
+    /// Returns the entry at `index`, where 0 is the most recent.
+    pub fn get(&self, index: usize) -> Option<&ClipboardEntry> {
+        self.entries.get(index)
+    }
+
+    /// Iterates over the entries, most recent first.
+    pub fn iter(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns whether the history is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns whether `text` was last copied as whole lines.
+    pub fn is_line_mode(&self, text: &str) -> bool {
+        self.entries
+            .front()
+            .is_some_and(|entry| entry.line_mode && entry.text == text)
+    }
+}
+
+impl Default for ClipboardRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads text from the system clipboard.
+pub fn get_text() -> Result<String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .context("Failed to read from the clipboard")
+}
+
+/// Puts `html` on the system clipboard, with `text` as the plain text fallback.
+pub fn set_html(html: &str, text: &str) -> Result<()> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_html(html, Some(text)))
+        .context("Failed to write to the clipboard")
+}
+
+/// Returns the leading whitespace of `line`.
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Re-indents `text` so it fits at a position whose line is indented by `indent`.
+///
+/// The common indentation of the pasted lines is removed, the first line is
+/// inserted as-is at the cursor and the following lines get `indent` prepended.
+pub fn reindent(text: &str, indent: &str) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    if lines.len() < 2 {
+        return text.trim_start_matches([' ', '\t']).to_string();
+    }
+
+    // The first line usually starts mid-line, so only the others decide the common indent
+    let common = lines[1..]
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| leading_whitespace(line).len())
+        .min()
+        .unwrap_or(0);
+
+    let mut result = String::with_capacity(text.len() + indent.len() * lines.len());
+    result.push_str(lines[0].trim_start_matches([' ', '\t']));
+    for line in &lines[1..] {
+        result.push('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        result.push_str(indent);
+        result.push_str(&line[common.min(leading_whitespace(line).len())..]);
+    }
+
+    result
+}
+
+/// Renders `text` as syntax highlighted HTML.
+///
+/// The syntax is picked from the file `extension`, falling back to plain text.
+pub fn to_highlighted_html(text: &str, extension: Option<&str>, theme: Theme) -> Result<String> {
+    let definition = syntax::find_syntax(extension);
+    syntect::html::highlighted_html_for_string(text, syntax::syntax_set(), definition, syntax::highlighting_theme(theme))
+        .context("Failed to highlight text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_order_and_capacity() {
+        let mut ring = ClipboardRing::with_capacity(2);
+        ring.push("one".to_string(), false);
+        ring.push("two".to_string(), false);
+        ring.push("one".to_string(), true);
+        assert_eq!(ring.iter().count(), 2);
+        assert_eq!(ring.get(0).unwrap().text, "one");
+        assert!(ring.is_line_mode("one"));
+
+        ring.push("three".to_string(), false);
+        let texts: Vec<&str> = ring.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, vec!["three", "one"]);
+        assert!(!ring.is_line_mode("one"));
+    }
+
+    #[test]
+    fn test_reindent() {
+        let pasted = "if x {\n        y();\n    }";
+        assert_eq!(reindent(pasted, "\t"), "if x {\n\t    y();\n\t}");
+        assert_eq!(reindent("  single", "    "), "single");
+        assert_eq!(reindent("a\n\n  b", "  "), "a\n\n  b");
+    }
+
+    #[test]
+    fn test_highlighted_html() {
+        let html = to_highlighted_html("fn main() {}", Some("rs"), Theme::Light).unwrap();
+        assert!(html.starts_with("<pre"));
+        assert!(html.contains("main"));
+    }
+}
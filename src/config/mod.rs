@@ -3,8 +3,13 @@
 //! This module provides functionality for managing editor settings and themes.
 
 use egui::{Color32, Stroke, Style, Visuals};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use crate::buffer::format::LineEnding;
+use crate::buffer::{FileFormat, IndentOverride};
+use crate::language::Language;
+
 /// Theme options for the editor.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Theme {
@@ -20,6 +25,95 @@ impl Default for Theme {
     }
 }
 
+/// When modified files are saved without being asked to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutoSave {
+    /// Files are only saved when asked to
+    #[default]
+    Off,
+    /// Files are saved once no edits were made for a while
+    AfterDelay,
+    /// Files are saved when the window loses focus
+    OnFocusLoss,
+    /// Files are saved when switching to another tab
+    OnTabSwitch,
+}
+
+impl AutoSave {
+    /// Every autosave mode, in the order they are offered.
+    pub const ALL: [AutoSave; 4] = [AutoSave::Off, AutoSave::AfterDelay, AutoSave::OnFocusLoss, AutoSave::OnTabSwitch];
+
+    /// Returns the name of the mode shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            AutoSave::Off => "Off",
+            AutoSave::AfterDelay => "After Delay",
+            AutoSave::OnFocusLoss => "On Focus Change",
+            AutoSave::OnTabSwitch => "On Tab Switch",
+        }
+    }
+}
+
+/// Which lines have whitespace at their end removed when saving.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrimWhitespace {
+    /// Trailing whitespace is kept
+    #[default]
+    Off,
+    /// Only lines edited since the last save are trimmed
+    ModifiedLines,
+    /// Every line is trimmed
+    AllLines,
+}
+
+impl TrimWhitespace {
+    /// Every trimming mode, in the order they are offered.
+    pub const ALL: [TrimWhitespace; 3] = [TrimWhitespace::Off, TrimWhitespace::ModifiedLines, TrimWhitespace::AllLines];
+
+    /// Returns the name of the mode shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            TrimWhitespace::Off => "Keep Trailing Whitespace",
+            TrimWhitespace::ModifiedLines => "Trim Modified Lines",
+            TrimWhitespace::AllLines => "Trim All Lines",
+        }
+    }
+}
+
+/// Which whitespace is drawn as faint symbols in the editor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShowWhitespace {
+    /// Whitespace stays invisible
+    #[default]
+    None,
+    /// Whitespace inside the selection is drawn
+    Selection,
+    /// Spaces and tabs at the end of lines are drawn
+    Trailing,
+    /// All spaces, tabs and line endings are drawn
+    All,
+}
+
+impl ShowWhitespace {
+    /// Every mode, in the order they are offered.
+    pub const ALL: [ShowWhitespace; 4] = [
+        ShowWhitespace::None,
+        ShowWhitespace::Selection,
+        ShowWhitespace::Trailing,
+        ShowWhitespace::All,
+    ];
+
+    /// Returns the name of the mode shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            ShowWhitespace::None => "None",
+            ShowWhitespace::Selection => "In Selection",
+            ShowWhitespace::Trailing => "Trailing",
+            ShowWhitespace::All => "All",
+        }
+    }
+}
+
 /// Editor configuration settings.
 #[derive(Debug, Clone)]
 pub struct EditorConfig {
@@ -37,6 +131,65 @@ pub struct EditorConfig {
     pub tab_size: usize,
     /// Whether to use spaces for tabs
     pub use_spaces: bool,
+    /// Whether to show the minimap at the right of the editor
+    pub show_minimap: bool,
+    /// Which whitespace is drawn as faint symbols
+    pub show_whitespace: ShowWhitespace,
+    /// Whether to draw lines along indentation levels
+    pub show_indent_guides: bool,
+    /// Whether to draw guide lines at the ruler columns
+    pub show_rulers: bool,
+    /// Columns to draw guide lines at, for languages without their own
+    pub rulers: Vec<usize>,
+    /// When modified files are saved automatically
+    pub autosave: AutoSave,
+    /// Seconds without edits before saving in [`AutoSave::AfterDelay`] mode
+    pub autosave_delay: f32,
+    /// Which lines have trailing whitespace removed when saving
+    pub trim_trailing_whitespace: TrimWhitespace,
+    /// Whether saved files end with exactly one line break
+    pub final_newline: bool,
+    /// Line ending every line is changed to when saving, if any
+    pub line_ending: Option<LineEnding>,
+    /// Whether to run the formatter for the file's language when saving
+    pub format_on_save: bool,
+    /// Whether typing an opening bracket or quote also types its closing one
+    pub auto_close_pairs: bool,
+    /// Settings for files in a language, by language id
+    pub languages: BTreeMap<String, LanguageSettings>,
+}
+
+/// Settings for the files of one language, taking the place of the general
+/// ones where they are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageSettings {
+    /// Tab size in spaces
+    pub tab_size: Option<usize>,
+    /// Whether to use spaces for tabs
+    pub use_spaces: Option<bool>,
+    /// Whether to enable word wrapping
+    pub word_wrap: Option<bool>,
+    /// Columns to draw guide lines at
+    pub rulers: Option<Vec<usize>>,
+    /// Token starting a comment that runs to the end of the line
+    pub line_comment: Option<String>,
+    /// Tokens opening and closing a block comment
+    pub block_comment: Option<(String, String)>,
+    /// Whether typing an opening bracket or quote also types its closing one
+    pub auto_close_pairs: Option<bool>,
+    /// Formatter command reading the text on standard input and writing the
+    /// formatted text to standard output. `{file}` in the command stands for
+    /// the path of the file.
+    pub formatter: Option<String>,
+}
+
+/// The comment tokens of a language after its settings are applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentTokens {
+    /// Token starting a comment that runs to the end of the line
+    pub line: Option<String>,
+    /// Tokens opening and closing a block comment
+    pub block: Option<(String, String)>,
 }
 
 impl Default for EditorConfig {
@@ -49,6 +202,25 @@ impl Default for EditorConfig {
             word_wrap: true,
             tab_size: 4,
             use_spaces: true,
+            show_minimap: true,
+            show_whitespace: ShowWhitespace::default(),
+            show_indent_guides: true,
+            show_rulers: false,
+            rulers: vec![80, 100],
+            autosave: AutoSave::default(),
+            autosave_delay: 1.0,
+            trim_trailing_whitespace: TrimWhitespace::default(),
+            final_newline: false,
+            line_ending: None,
+            format_on_save: false,
+            auto_close_pairs: true,
+            languages: BTreeMap::from([(
+                "rust".to_string(),
+                LanguageSettings {
+                    formatter: Some("rustfmt --emit stdout".to_string()),
+                    ..LanguageSettings::default()
+                },
+            )]),
         }
     }
 }
@@ -94,6 +266,78 @@ impl EditorConfig {
         ctx.set_style(style);
     }
 
+    /// Returns the changes made to files when saving, for files whose own
+    /// settings leave them unset.
+    pub fn save_format(&self) -> FileFormat {
+        FileFormat {
+            line_ending: self.line_ending,
+            charset: None,
+            trim_trailing_whitespace: Some(self.trim_trailing_whitespace != TrimWhitespace::Off),
+            trim_modified_lines_only: Some(self.trim_trailing_whitespace == TrimWhitespace::ModifiedLines),
+            insert_final_newline: Some(self.final_newline),
+            trim_final_newlines: Some(self.final_newline),
+            max_line_length: None,
+        }
+    }
+
+    /// Returns the settings for files in `language`, if it has any.
+    pub fn language(&self, language: &str) -> Option<&LanguageSettings> {
+        self.languages.get(language)
+    }
+
+    /// Returns the indentation settings `language` has in place of the
+    /// general ones.
+    pub fn language_indent(&self, language: &str) -> IndentOverride {
+        self.language(language).map_or_else(IndentOverride::default, |settings| IndentOverride {
+            use_spaces: settings.use_spaces,
+            tab_size: settings.tab_size,
+        })
+    }
+
+    /// Returns whether files in `language` wrap long lines.
+    pub fn word_wrap_for(&self, language: &str) -> bool {
+        self.language(language).and_then(|settings| settings.word_wrap).unwrap_or(self.word_wrap)
+    }
+
+    /// Returns the columns guide lines are drawn at in files in `language`,
+    /// which are none while rulers are hidden.
+    pub fn rulers_for(&self, language: &str) -> &[usize] {
+        if !self.show_rulers {
+            return &[];
+        }
+        self.language(language).and_then(|settings| settings.rulers.as_deref()).unwrap_or(&self.rulers)
+    }
+
+    /// Returns the comment tokens of `language`, as its settings change them.
+    pub fn comments_for(&self, language: &Language) -> CommentTokens {
+        let settings = self.language(language.id);
+        CommentTokens {
+            line: settings
+                .and_then(|settings| settings.line_comment.clone())
+                .or(language.line_comment.map(str::to_string)),
+            block: settings
+                .and_then(|settings| settings.block_comment.clone())
+                .or(language.block_comment.map(|(open, close)| (open.to_string(), close.to_string()))),
+        }
+    }
+
+    /// Returns whether typing an opening bracket or quote in files in
+    /// `language` also types its closing one.
+    pub fn auto_close_for(&self, language: &str) -> bool {
+        self.language(language)
+            .and_then(|settings| settings.auto_close_pairs)
+            .unwrap_or(self.auto_close_pairs)
+    }
+
+    /// Returns the formatter command to run on files in `language` when
+    /// saving, if any.
+    pub fn formatter_for(&self, language: &str) -> Option<&str> {
+        if !self.format_on_save {
+            return None;
+        }
+        self.language(language)?.formatter.as_deref()
+    }
+
     /// Toggles between light and dark themes.
     pub fn toggle_theme(&mut self) {
         self.theme = match self.theme {
@@ -121,6 +365,21 @@ impl EditorConfig {
     pub fn toggle_word_wrap(&mut self) {
         self.word_wrap = !self.word_wrap;
     }
+
+    /// Toggles the minimap.
+    pub fn toggle_minimap(&mut self) {
+        self.show_minimap = !self.show_minimap;
+    }
+
+    /// Toggles the indentation guides.
+    pub fn toggle_indent_guides(&mut self) {
+        self.show_indent_guides = !self.show_indent_guides;
+    }
+
+    /// Toggles the rulers.
+    pub fn toggle_rulers(&mut self) {
+        self.show_rulers = !self.show_rulers;
+    }
 }
 
 #[cfg(test)]
@@ -137,6 +396,64 @@ mod tests {
         assert!(config.word_wrap);
         assert_eq!(config.tab_size, 4);
         assert!(config.use_spaces);
+        assert!(config.show_minimap);
+        assert_eq!(config.autosave, AutoSave::Off);
+        assert_eq!(config.autosave_delay, 1.0);
+        assert_eq!(config.trim_trailing_whitespace, TrimWhitespace::Off);
+        assert!(!config.final_newline);
+        assert_eq!(config.line_ending, None);
+        assert!(!config.format_on_save);
+        assert!(config.auto_close_pairs);
+        assert_eq!(config.show_whitespace, ShowWhitespace::None);
+        assert!(config.show_indent_guides);
+        assert!(!config.show_rulers);
+        assert_eq!(config.rulers, vec![80, 100]);
+    }
+
+    #[test]
+    fn test_save_actions() {
+        let mut config = EditorConfig::default();
+        assert_eq!(config.formatter_for("rust"), None);
+        config.format_on_save = true;
+        assert_eq!(config.formatter_for("rust"), Some("rustfmt --emit stdout"));
+        assert_eq!(config.formatter_for("markdown"), None);
+        
+        config.trim_trailing_whitespace = TrimWhitespace::ModifiedLines;
+        let format = config.save_format();
+        assert_eq!(format.trim_trailing_whitespace, Some(true));
+        assert_eq!(format.trim_modified_lines_only, Some(true));
+    }
+
+    #[test]
+    fn test_language_settings() {
+        let mut config = EditorConfig::default();
+        config.languages.insert(
+            "python".to_string(),
+            LanguageSettings {
+                tab_size: Some(2),
+                word_wrap: Some(false),
+                rulers: Some(vec![79]),
+                line_comment: Some("##".to_string()),
+                auto_close_pairs: Some(false),
+                ..LanguageSettings::default()
+            },
+        );
+        assert_eq!(config.language_indent("python").tab_size, Some(2));
+        assert_eq!(config.language_indent("python").use_spaces, None);
+        assert!(!config.word_wrap_for("python"));
+        assert!(config.word_wrap_for("rust"));
+        assert!(config.rulers_for("python").is_empty());
+        config.toggle_rulers();
+        assert_eq!(config.rulers_for("python"), &[79]);
+        assert_eq!(config.rulers_for("rust"), &[80, 100]);
+        assert!(!config.auto_close_for("python"));
+        assert!(config.auto_close_for("rust"));
+        
+        let python = crate::language::find("python").unwrap();
+        assert_eq!(config.comments_for(python).line.as_deref(), Some("##"));
+        let rust = config.comments_for(crate::language::find("rust").unwrap());
+        assert_eq!(rust.line.as_deref(), Some("//"));
+        assert_eq!(rust.block, Some(("/*".to_string(), "*/".to_string())));
     }
 
     #[test]
@@ -0,0 +1,369 @@
+//! EditorConfig module for Zing text editor.
+//!
+//! Reads the `.editorconfig` files above a file, from its folder up to the one
+//! marked `root = true`, and applies the properties of the sections matching
+//! the file to its buffer. Properties of files closer to the file, and of later
+//! sections, take precedence.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::buffer::format::{Charset, LineEnding};
+use crate::buffer::{FileFormat, TextBuffer};
+use crate::workspace::ignore::glob_match;
+
+/// Name of the files EditorConfig properties are read from.
+const FILE_NAME: &str = ".editorconfig";
+/// Largest numeric range `{a..b}` in a section name that is expanded.
+const MAX_RANGE: i64 = 1000;
+
+/// A section of an `.editorconfig` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Section {
+    /// The glob the section applies to
+    pattern: String,
+    /// Property names and values, in lower case
+    properties: Vec<(String, String)>,
+}
+
+/// The contents of an `.editorconfig` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ConfigFile {
+    /// Whether files further up are ignored
+    root: bool,
+    /// The sections in the order they appear
+    sections: Vec<Section>,
+}
+
+/// The properties that apply to a file. Unset properties leave the editor's
+/// own settings in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Properties {
+    /// `indent_style`, whether to indent with spaces instead of tabs
+    pub use_spaces: Option<bool>,
+    /// `indent_size`, the columns in one level of indentation
+    pub indent_size: Option<usize>,
+    /// `tab_width`, the columns a tab takes
+    pub tab_width: Option<usize>,
+    /// `end_of_line`
+    pub end_of_line: Option<LineEnding>,
+    /// `charset`
+    pub charset: Option<Charset>,
+    /// `trim_trailing_whitespace`
+    pub trim_trailing_whitespace: Option<bool>,
+    /// `insert_final_newline`
+    pub insert_final_newline: Option<bool>,
+    /// `max_line_length`
+    pub max_line_length: Option<usize>,
+}
+
+impl Properties {
+    /// Interprets property values collected from the matching sections.
+    fn from_values(values: &BTreeMap<String, String>) -> Self {
+        let value = |key: &str| values.get(key).map(String::as_str);
+        let number = |key: &str| value(key).and_then(|v| v.parse::<usize>().ok()).filter(|&n| n > 0);
+        let flag = |key: &str| match value(key) {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+
+        let tab_width = number("tab_width");
+        let indent_size = match value("indent_size") {
+            Some("tab") => tab_width,
+            _ => number("indent_size"),
+        };
+        Self {
+            use_spaces: match value("indent_style") {
+                Some("space") => Some(true),
+                Some("tab") => Some(false),
+                _ => None,
+            },
+            indent_size,
+            // The tab width follows the indent size unless it is given
+            tab_width: tab_width.or(indent_size),
+            end_of_line: match value("end_of_line") {
+                Some("lf") => Some(LineEnding::Lf),
+                Some("crlf") => Some(LineEnding::Crlf),
+                Some("cr") => Some(LineEnding::Cr),
+                _ => None,
+            },
+            charset: match value("charset") {
+                Some("utf-8") => Some(Charset::Utf8),
+                Some("utf-8-bom") => Some(Charset::Utf8Bom),
+                Some("latin1") => Some(Charset::Latin1),
+                Some("utf-16be") => Some(Charset::Utf16Be),
+                Some("utf-16le") => Some(Charset::Utf16Le),
+                _ => None,
+            },
+            trim_trailing_whitespace: flag("trim_trailing_whitespace"),
+            insert_final_newline: flag("insert_final_newline"),
+            max_line_length: number("max_line_length"),
+        }
+    }
+
+    /// Sets the indentation and file format of `buffer` from the properties.
+    ///
+    /// Indentation detected from the content is kept where no property is set.
+    pub fn apply_to(&self, buffer: &mut TextBuffer) {
+        if let Some(use_spaces) = self.use_spaces {
+            buffer.indent.use_spaces = Some(use_spaces);
+        }
+        // Zing has one width for both tabs and indentation levels, so take the
+        // one that matters for how the file is indented
+        let tab_size = match buffer.indent.use_spaces {
+            Some(false) => self.tab_width.or(self.indent_size),
+            _ => self.indent_size.or(self.tab_width),
+        };
+        if let Some(tab_size) = tab_size {
+            buffer.indent.tab_size = Some(tab_size);
+        }
+        buffer.format = FileFormat {
+            line_ending: self.end_of_line,
+            // Keep the charset the file was read in unless one is asked for
+            charset: self.charset.or(buffer.format.charset),
+            trim_trailing_whitespace: self.trim_trailing_whitespace,
+            insert_final_newline: self.insert_final_newline,
+            max_line_length: self.max_line_length,
+            ..FileFormat::default()
+        };
+    }
+}
+
+/// Parses the text of an `.editorconfig` file.
+fn parse(text: &str) -> ConfigFile {
+    let mut file = ConfigFile::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            file.sections.push(Section {
+                pattern: pattern.to_string(),
+                properties: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once(['=', ':']) else { continue };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_lowercase();
+        match file.sections.last_mut() {
+            Some(section) => section.properties.push((key, value)),
+            None if key == "root" => file.root = value == "true",
+            None => {}
+        }
+    }
+    file
+}
+
+/// Returns the index of the `}` closing the `{` at the start of `pattern`.
+fn closing_brace(pattern: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, &c) in pattern.iter().enumerate() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits the inside of braces at the commas that are not nested deeper.
+fn alternatives(inner: &[char]) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut depth = 0;
+    let mut escaped = false;
+    for &c in inner {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        parts.last_mut().unwrap().push(c);
+    }
+    parts
+}
+
+/// Expands `{a,b}` alternatives and `{1..3}` numeric ranges in a section name
+/// into the plain globs it stands for.
+fn expand(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut escaped = false;
+    for (start, &c) in chars.iter().enumerate() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => {
+                let Some(end) = closing_brace(&chars[start..]).map(|offset| start + offset) else { break };
+                let prefix: String = chars[..start].iter().collect();
+                let inner: String = chars[start + 1..end].iter().collect();
+                let suffix: String = chars[end + 1..].iter().collect();
+
+                let choices = match inner.split_once("..").map(|(a, b)| (a.parse::<i64>(), b.parse::<i64>())) {
+                    Some((Ok(a), Ok(b))) if (b - a).abs() <= MAX_RANGE => {
+                        (a.min(b)..=a.max(b)).map(|n| n.to_string()).collect()
+                    }
+                    _ if inner.contains(',') => alternatives(&chars[start + 1..end]),
+                    // Braces without a choice are matched literally
+                    _ => vec![format!("\\{{{}\\}}", inner)],
+                };
+                return choices
+                    .iter()
+                    .flat_map(|choice| expand(&format!("{}{}{}", prefix, choice, suffix)))
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    vec![pattern.to_string()]
+}
+
+/// Returns whether a section named `pattern` applies to `relative`, the path
+/// of a file relative to the folder of the `.editorconfig` file.
+fn section_matches(pattern: &str, relative: &str) -> bool {
+    // Names without a slash match files in any folder, others are anchored
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.contains('/') => pattern.to_string(),
+        None => format!("**/{}", pattern),
+    };
+    let relative: Vec<char> = relative.chars().collect();
+    expand(&pattern)
+        .iter()
+        .any(|glob| glob_match(&glob.chars().collect::<Vec<_>>(), &relative))
+}
+
+/// Returns the EditorConfig properties of the file at `path`.
+pub fn resolve(path: &Path) -> Properties {
+    let mut files = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        if let Ok(text) = std::fs::read_to_string(dir.join(FILE_NAME)) {
+            let file = parse(&text);
+            let root = file.root;
+            files.push((dir, file));
+            if root {
+                break;
+            }
+        }
+    }
+
+    // Apply the outermost file first so closer ones override it
+    let mut values = BTreeMap::new();
+    for (dir, file) in files.iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else { continue };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        for section in file.sections.iter().filter(|section| section_matches(&section.pattern, &relative)) {
+            for (key, value) in &section.properties {
+                if value == "unset" {
+                    values.remove(key);
+                } else {
+                    values.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+    Properties::from_values(&values)
+}
+
+/// Applies the EditorConfig properties of the buffer's file to it, if it has one.
+pub fn apply(buffer: &mut TextBuffer) {
+    if let Some(path) = buffer.file_path.clone() {
+        resolve(&path).apply_to(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse() {
+        let file = parse("root = true\n# comment\n[*.{rs,toml}]\nIndent_Style = Space\nindent_size: 4\n\n[Makefile]\nindent_style=tab\n");
+        assert!(file.root);
+        assert_eq!(file.sections.len(), 2);
+        assert_eq!(file.sections[0].pattern, "*.{rs,toml}");
+        assert_eq!(
+            file.sections[0].properties,
+            vec![("indent_style".to_string(), "space".to_string()), ("indent_size".to_string(), "4".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_section_matches() {
+        assert!(section_matches("*", "src/main.rs"));
+        assert!(section_matches("*.{rs,toml}", "Cargo.toml"));
+        assert!(section_matches("*.{rs,toml}", "src/deep/lib.rs"));
+        assert!(!section_matches("*.{rs,toml}", "README.md"));
+        assert!(section_matches("src/*.rs", "src/main.rs"));
+        assert!(!section_matches("src/*.rs", "other/src/main.rs"));
+        assert!(section_matches("/docs/**/*.md", "docs/a/b.md"));
+        assert!(!section_matches("/docs/*.md", "src/docs/a.md"));
+        assert!(section_matches("file{1..3}.txt", "file2.txt"));
+        assert!(!section_matches("file{1..3}.txt", "file4.txt"));
+        assert!(section_matches("{a,{b,c}}.js", "c.js"));
+        assert!(section_matches("{x}.js", "{x}.js"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("project/src")).unwrap();
+        fs::write(root.join(FILE_NAME), "[*]\nmax_line_length = 80\n").unwrap();
+        fs::write(
+            root.join("project").join(FILE_NAME),
+            "root = true\n[*]\nindent_style = space\nindent_size = 2\nend_of_line = crlf\ninsert_final_newline = true\n[*.rs]\nindent_size = 4\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("project/src").join(FILE_NAME),
+            "[*.rs]\ninsert_final_newline = unset\ntrim_trailing_whitespace = true\n",
+        )
+        .unwrap();
+
+        let properties = resolve(&root.join("project/src/main.rs"));
+        assert_eq!(properties.use_spaces, Some(true));
+        assert_eq!(properties.indent_size, Some(4));
+        assert_eq!(properties.tab_width, Some(4));
+        assert_eq!(properties.end_of_line, Some(LineEnding::Crlf));
+        assert_eq!(properties.insert_final_newline, None);
+        assert_eq!(properties.trim_trailing_whitespace, Some(true));
+        // The file above the root is not read
+        assert_eq!(properties.max_line_length, None);
+
+        let mut buffer = TextBuffer::from_str("\tx\n");
+        buffer.file_path = Some(root.join("project/notes.txt"));
+        buffer.indent.use_spaces = Some(false);
+        apply(&mut buffer);
+        assert_eq!(buffer.indent.use_spaces, Some(true));
+        assert_eq!(buffer.indent.tab_size, Some(2));
+        assert_eq!(buffer.format.insert_final_newline, Some(true));
+    }
+
+    #[test]
+    fn test_indent_size_tab() {
+        let values: BTreeMap<String, String> = [("indent_style", "tab"), ("indent_size", "tab"), ("tab_width", "8")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let properties = Properties::from_values(&values);
+        assert_eq!((properties.use_spaces, properties.indent_size, properties.tab_width), (Some(false), Some(8), Some(8)));
+    }
+}
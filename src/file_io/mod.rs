@@ -5,9 +5,18 @@
 use anyhow::{Context, Result};
 use rfd::FileDialog;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::fs;
 
 use crate::buffer::TextBuffer;
+use crate::config::EditorConfig;
+use crate::editorconfig;
+use crate::language;
+
+/// How long a formatter may run before it is stopped and the file is saved
+/// unformatted.
+pub const FORMATTER_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Opens a file dialog for selecting a file to open.
 pub fn open_file_dialog() -> Option<PathBuf> {
@@ -18,6 +27,13 @@ pub fn open_file_dialog() -> Option<PathBuf> {
         .pick_file()
 }
 
+/// Opens a file dialog for selecting a workspace folder.
+pub fn open_folder_dialog() -> Option<PathBuf> {
+    FileDialog::new()
+        .set_title("Open Folder")
+        .pick_folder()
+}
+
 /// Opens a file dialog for saving a file.
 pub fn save_file_dialog() -> Option<PathBuf> {
     FileDialog::new()
@@ -27,15 +43,30 @@ pub fn save_file_dialog() -> Option<PathBuf> {
         .save_file()
 }
 
-/// Loads a file into a text buffer.
+/// Loads a file into a text buffer, with the `.editorconfig` settings that
+/// apply to it, reading it in the charset they ask for.
 pub async fn load_file<P: AsRef<Path>>(path: P) -> Result<TextBuffer> {
-    TextBuffer::from_file(path).await
+    let charset = editorconfig::resolve(path.as_ref()).charset;
+    let mut buffer = TextBuffer::from_file(path, charset).await?;
+    editorconfig::apply(&mut buffer);
+    Ok(buffer)
+}
+
+/// Associates `buffer` with the file at `path`, taking on the language and
+/// `.editorconfig` settings of the new path.
+pub fn set_path(buffer: &mut TextBuffer, path: PathBuf) {
+    buffer.language = language::detect(Some(&path), &buffer.content);
+    buffer.file_path = Some(path);
+    editorconfig::apply(buffer);
 }
 
 /// Saves a text buffer to a file.
 pub async fn save_buffer_to_file(buffer: &mut TextBuffer, path: Option<PathBuf>) -> Result<()> {
     match path {
-        Some(path) => buffer.save_to(path).await,
+        Some(path) => {
+            set_path(buffer, path.clone());
+            buffer.save_to(path).await
+        }
         None => {
             if buffer.file_path.is_some() {
                 buffer.save().await
@@ -46,6 +77,98 @@ pub async fn save_buffer_to_file(buffer: &mut TextBuffer, path: Option<PathBuf>)
     }
 }
 
+/// Runs the formatter `command` on `text` and returns the formatted text.
+///
+/// The command is split at whitespace and run without a shell, in the folder
+/// of `path`, with `{file}` in its arguments replaced by `path`. The text is
+/// given on standard input and the formatted text read from standard output.
+/// A formatter still running after `timeout` is killed.
+pub fn run_formatter(command: &str, text: &str, path: &Path, timeout: Duration) -> Result<String> {
+    use std::io::{Read, Write};
+    use std::process::{Command, Stdio};
+    
+    let file = path.to_string_lossy();
+    let mut words = command.split_whitespace().map(|word| word.replace("{file}", &file));
+    let program = words.next().context("The formatter command is empty")?;
+    let mut command = Command::new(&program);
+    command
+        .args(words)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = path.parent().filter(|dir| dir.is_dir()) {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run formatter {}", program))?;
+    
+    // Pipes are served on other threads so a formatter filling one cannot block us
+    let mut stdin = child.stdin.take().context("Failed to open the formatter's input")?;
+    let input = text.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let read_all = |mut pipe: Box<dyn Read + Send>| {
+        std::thread::spawn(move || {
+            let mut bytes = Vec::new();
+            pipe.read_to_end(&mut bytes).map(|_| bytes)
+        })
+    };
+    let stdout = read_all(Box::new(child.stdout.take().context("Failed to read the formatter's output")?));
+    let stderr = read_all(Box::new(child.stderr.take().context("Failed to read the formatter's output")?));
+    
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().with_context(|| format!("Failed to run formatter {}", program))? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("Formatter {} did not finish within {} seconds", program, timeout.as_secs_f32());
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    let _ = writer.join();
+    let stdout = stdout.join().ok().and_then(|bytes| bytes.ok()).unwrap_or_default();
+    let stderr = stderr.join().ok().and_then(|bytes| bytes.ok()).unwrap_or_default();
+    
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let reason = stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or("no error output");
+        anyhow::bail!("Formatter {} failed: {}", program, reason.trim());
+    }
+    String::from_utf8(stdout).with_context(|| format!("Formatter {} did not output UTF-8 text", program))
+}
+
+/// Makes the changes `config` and the buffer's own settings ask for before
+/// saving, as one undo step.
+///
+/// The formatter runs without holding the buffer's lock, so this belongs on a
+/// worker thread. Its output is only used if the text did not change in the
+/// meantime. A formatter that fails or is too slow leaves the text unformatted
+/// and its error is returned, but the other changes are still made so the file
+/// can be saved.
+pub fn prepare_save(buffer: &Arc<Mutex<TextBuffer>>, config: &EditorConfig) -> Result<()> {
+    let job = {
+        let buffer = buffer.lock().unwrap();
+        let command = buffer.file_path.clone().and_then(|path| Some((config.formatter_for(buffer.language.id)?.to_string(), path)));
+        command.map(|(command, path)| (command, path, buffer.content.to_string(), buffer.revision()))
+    };
+    let formatted = job.map(|(command, path, text, revision)| {
+        run_formatter(&command, &text, &path, FORMATTER_TIMEOUT).map(|formatted| (formatted, revision))
+    });
+    
+    let mut buffer = buffer.lock().unwrap();
+    let (formatted, failure) = match formatted {
+        Some(Ok((text, revision))) if revision == buffer.revision() => (Some(text), None),
+        Some(Ok(_)) => (None, Some(anyhow::anyhow!("the text changed while the formatter ran"))),
+        Some(Err(err)) => (None, Some(err)),
+        None => (None, None),
+    };
+    buffer.prepare_save(&config.save_format(), formatted.as_deref())?;
+    failure.map_or(Ok(()), Err)
+}
+
 /// Prints the content of a text buffer.
 ///
 /// This implementation saves the buffer to a temporary file and opens it with the system's
@@ -105,6 +228,7 @@ pub fn print_buffer(buffer: &TextBuffer) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::LanguageSettings;
     use std::io::Write;
     use tempfile::NamedTempFile;
     
@@ -121,7 +245,32 @@ mod tests {
         // Check that the buffer contains the expected content
         assert_eq!(buffer.content.to_string(), content);
         assert_eq!(buffer.file_path, Some(temp_file.path().to_path_buf()));
-        assert!(!buffer.modified);
+        assert!(!buffer.is_modified());
+        
+        Ok(())
+    }
+    
+    #[tokio::test]
+    async fn test_load_and_save_charsets() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        
+        // Files that aren't UTF-8 open and save back in their own charset
+        let latin1 = dir.path().join("latin1.txt");
+        fs::write(&latin1, [b'c', b'a', b'f', 0xE9]).await?;
+        let mut buffer = load_file(&latin1).await?;
+        assert_eq!(buffer.content.to_string(), "café");
+        buffer.save().await?;
+        assert_eq!(fs::read(&latin1).await?, [b'c', b'a', b'f', 0xE9]);
+        
+        let utf16 = dir.path().join("utf16.txt");
+        fs::write(&utf16, [0xFF, 0xFE, b'h', 0, b'i', 0]).await?;
+        assert_eq!(load_file(&utf16).await?.content.to_string(), "hi");
+        
+        // Text the charset can't hold is not saved
+        buffer.insert(4, " €")?;
+        assert!(buffer.save().await.is_err());
+        assert_eq!(fs::read(&latin1).await?, [b'c', b'a', b'f', 0xE9]);
+        assert!(buffer.is_modified());
         
         Ok(())
     }
@@ -144,7 +293,43 @@ mod tests {
         
         // Check that the buffer's file path was updated
         assert_eq!(buffer.file_path, Some(path));
-        assert!(!buffer.modified);
+        assert!(!buffer.is_modified());
+        
+        Ok(())
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_prepare_save_with_formatter() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut config = EditorConfig {
+            format_on_save: true,
+            final_newline: true,
+            ..EditorConfig::default()
+        };
+        let formatter = |command: &str| LanguageSettings {
+            formatter: Some(command.to_string()),
+            ..LanguageSettings::default()
+        };
+        config.languages.insert("text".to_string(), formatter("tr a-z A-Z"));
+        config.languages.insert("markdown".to_string(), formatter("false"));
+        
+        let buffer = Arc::new(Mutex::new(TextBuffer::from_str("hello")));
+        set_path(&mut buffer.lock().unwrap(), dir.path().join("notes.txt"));
+        prepare_save(&buffer, &config)?;
+        assert_eq!(buffer.lock().unwrap().content.to_string(), "HELLO\n");
+        buffer.lock().unwrap().undo()?;
+        assert_eq!(buffer.lock().unwrap().content.to_string(), "hello");
+        
+        // A failing formatter is reported, but the other changes are still made
+        set_path(&mut buffer.lock().unwrap(), dir.path().join("notes.md"));
+        assert!(prepare_save(&buffer, &config).is_err());
+        assert_eq!(buffer.lock().unwrap().content.to_string(), "hello\n");
+        
+        // A formatter that hangs is stopped
+        let started = Instant::now();
+        assert!(run_formatter("sleep 10", "hello", &dir.path().join("notes.txt"), Duration::from_millis(200)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
         
         Ok(())
     }
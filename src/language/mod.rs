@@ -0,0 +1,223 @@
+//! Language module for Zing text editor.
+//!
+//! This module knows the languages files are written in and tells which one a
+//! file uses from a modeline, its file name, its extension or its `#!` line.
+
+use ropey::Rope;
+use std::path::Path;
+
+/// A language files can be written in.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// Identifier used in settings, like `rust`
+    pub id: &'static str,
+    /// Name shown in the status bar
+    pub name: &'static str,
+    /// Other names modelines may use for the language
+    pub aliases: &'static [&'static str],
+    /// File extensions, without the dot
+    pub extensions: &'static [&'static str],
+    /// Whole file names, like `Makefile`
+    pub file_names: &'static [&'static str],
+    /// Programs named on a `#!` line that run files in the language
+    pub interpreters: &'static [&'static str],
+    /// Token starting a comment that runs to the end of the line
+    pub line_comment: Option<&'static str>,
+    /// Tokens opening and closing a block comment
+    pub block_comment: Option<(&'static str, &'static str)>,
+    /// Extension the highlighting definition is found by
+    pub syntax: Option<&'static str>,
+    /// Pairs typed together, like brackets and quotes. Pairs of different
+    /// characters are brackets that nest and can be matched.
+    pub pairs: &'static [(char, char)],
+}
+
+impl Language {
+    /// Returns whether `name` names the language, ignoring case.
+    fn is_named(&self, name: &str) -> bool {
+        self.id.eq_ignore_ascii_case(name)
+            || self.name.eq_ignore_ascii_case(name)
+            || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A language with nothing set but its names.
+const fn language(id: &'static str, name: &'static str) -> Language {
+    Language {
+        id,
+        name,
+        aliases: &[],
+        extensions: &[],
+        file_names: &[],
+        interpreters: &[],
+        line_comment: None,
+        block_comment: None,
+        syntax: None,
+        pairs: PAIRS,
+    }
+}
+
+/// Pairs typed together in most languages.
+const PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')];
+/// Pairs of languages that also quote with single quotes.
+const QUOTED_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')];
+/// Pairs of languages that also quote with backticks.
+const TEMPLATE_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\''), ('`', '`')];
+
+/// Comment tokens shared by the languages that borrow them from C.
+const C_LINE: Option<&str> = Some("//");
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+/// Every known language, plain text first.
+pub static LANGUAGES: &[Language] = &[
+    Language { aliases: &["plain", "txt"], extensions: &["txt", "text"], syntax: Some("txt"), ..language("text", "Plain Text") },
+    Language { extensions: &["sh", "bash", "zsh"], file_names: &[".bashrc", ".bash_profile", ".profile", ".zshrc"], interpreters: &["sh", "bash", "zsh", "dash", "ksh"], aliases: &["sh", "bash", "zsh"], line_comment: Some("#"), syntax: Some("sh"), pairs: TEMPLATE_PAIRS, ..language("shell", "Shell Script") },
+    Language { extensions: &["c", "h"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("c"), pairs: QUOTED_PAIRS, ..language("c", "C") },
+    Language { extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx"], aliases: &["c++"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("cpp"), pairs: QUOTED_PAIRS, ..language("cpp", "C++") },
+    Language { extensions: &["cs"], aliases: &["c#"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("cs"), pairs: QUOTED_PAIRS, ..language("csharp", "C#") },
+    Language { extensions: &["css"], block_comment: C_BLOCK, syntax: Some("css"), pairs: QUOTED_PAIRS, ..language("css", "CSS") },
+    Language { file_names: &["Dockerfile", "Containerfile"], extensions: &["dockerfile"], aliases: &["docker"], line_comment: Some("#"), pairs: QUOTED_PAIRS, ..language("dockerfile", "Dockerfile") },
+    Language { extensions: &["go"], aliases: &["golang"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("go"), pairs: TEMPLATE_PAIRS, ..language("go", "Go") },
+    Language { extensions: &["html", "htm", "xhtml"], block_comment: Some(("<!--", "-->")), syntax: Some("html"), pairs: QUOTED_PAIRS, ..language("html", "HTML") },
+    Language { extensions: &["java"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("java"), pairs: QUOTED_PAIRS, ..language("java", "Java") },
+    Language { extensions: &["js", "mjs", "cjs", "jsx"], interpreters: &["node"], aliases: &["js"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("js"), pairs: TEMPLATE_PAIRS, ..language("javascript", "JavaScript") },
+    Language { extensions: &["json"], file_names: &[".prettierrc", ".eslintrc"], syntax: Some("json"), ..language("json", "JSON") },
+    Language { extensions: &["lua"], interpreters: &["lua"], line_comment: Some("--"), block_comment: Some(("--[[", "]]")), syntax: Some("lua"), pairs: QUOTED_PAIRS, ..language("lua", "Lua") },
+    Language { file_names: &["Makefile", "makefile", "GNUmakefile"], extensions: &["mk", "mak"], aliases: &["make"], interpreters: &["make"], line_comment: Some("#"), syntax: Some("make"), pairs: QUOTED_PAIRS, ..language("makefile", "Makefile") },
+    Language { extensions: &["md", "markdown"], aliases: &["md"], block_comment: Some(("<!--", "-->")), syntax: Some("md"), pairs: &[('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('`', '`')], ..language("markdown", "Markdown") },
+    Language { extensions: &["php"], interpreters: &["php"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("php"), pairs: QUOTED_PAIRS, ..language("php", "PHP") },
+    Language { extensions: &["py", "pyw", "pyi"], file_names: &["SConstruct", "SConscript"], interpreters: &["python"], aliases: &["py"], line_comment: Some("#"), syntax: Some("py"), pairs: QUOTED_PAIRS, ..language("python", "Python") },
+    Language { extensions: &["rb", "rake", "gemspec"], file_names: &["Gemfile", "Rakefile", "Vagrantfile"], interpreters: &["ruby"], aliases: &["rb"], line_comment: Some("#"), block_comment: Some(("=begin", "=end")), syntax: Some("rb"), pairs: QUOTED_PAIRS, ..language("ruby", "Ruby") },
+    Language { extensions: &["rs"], aliases: &["rs"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("rs"), ..language("rust", "Rust") },
+    Language { extensions: &["sql"], line_comment: Some("--"), block_comment: C_BLOCK, syntax: Some("sql"), pairs: QUOTED_PAIRS, ..language("sql", "SQL") },
+    Language { extensions: &["toml"], file_names: &["Cargo.lock", "Pipfile"], line_comment: Some("#"), pairs: QUOTED_PAIRS, ..language("toml", "TOML") },
+    Language { extensions: &["ts", "tsx", "mts", "cts"], aliases: &["ts"], interpreters: &["deno", "ts-node"], line_comment: C_LINE, block_comment: C_BLOCK, syntax: Some("js"), pairs: TEMPLATE_PAIRS, ..language("typescript", "TypeScript") },
+    Language { extensions: &["xml", "svg", "xsd", "xsl", "plist"], block_comment: Some(("<!--", "-->")), syntax: Some("xml"), pairs: QUOTED_PAIRS, ..language("xml", "XML") },
+    Language { extensions: &["yaml", "yml"], file_names: &[".clang-format"], aliases: &["yml"], line_comment: Some("#"), syntax: Some("yaml"), pairs: QUOTED_PAIRS, ..language("yaml", "YAML") },
+];
+
+/// Number of lines at the start and end of a file searched for a modeline.
+const MODELINE_LINES: usize = 5;
+
+/// Returns the language of files nothing else is known about.
+pub fn plain_text() -> &'static Language {
+    &LANGUAGES[0]
+}
+
+/// Returns the language with the id, name or alias `name`.
+pub fn find(name: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|language| language.is_named(name))
+}
+
+/// Returns the language of the file at `path` holding `content`.
+///
+/// A modeline naming a known language wins, then the file name, then the
+/// extension and last the interpreter on a `#!` line.
+pub fn detect(path: Option<&Path>, content: &Rope) -> &'static Language {
+    from_modeline(content)
+        .or_else(|| path.and_then(from_path))
+        .or_else(|| from_shebang(&content.line(0).to_string()))
+        .unwrap_or_else(plain_text)
+}
+
+/// Returns the language the name or extension of `path` belongs to.
+fn from_path(path: &Path) -> Option<&'static Language> {
+    let file_name = path.file_name()?.to_str()?;
+    if let Some(language) = LANGUAGES.iter().find(|language| language.file_names.contains(&file_name)) {
+        return Some(language);
+    }
+    let extension = path.extension()?.to_str()?;
+    LANGUAGES
+        .iter()
+        .find(|language| language.extensions.iter().any(|ext| ext.eq_ignore_ascii_case(extension)))
+}
+
+/// Returns the language run by the interpreter on a `#!` line.
+fn from_shebang(line: &str) -> Option<&'static Language> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    // Versioned interpreters like `python3.12` run the same language
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    LANGUAGES.iter().find(|language| language.interpreters.contains(&program))
+}
+
+/// Returns the language named by a Vim or Emacs modeline near the start or end
+/// of `content`.
+fn from_modeline(content: &Rope) -> Option<&'static Language> {
+    let lines = content.len_lines();
+    let head = 0..lines.min(MODELINE_LINES);
+    let tail = lines.saturating_sub(MODELINE_LINES).max(head.end)..lines;
+    head.chain(tail)
+        .find_map(|line| modeline_language(&content.line(line).to_string()).and_then(find))
+}
+
+/// Returns the language name a modeline on `line` gives, if it has one.
+///
+/// Understands Emacs' `-*- mode: rust -*-` and `-*- rust -*-`, and Vim's
+/// `vim: set ft=rust:` and `vim: filetype=rust`.
+pub fn modeline_language(line: &str) -> Option<&str> {
+    if let Some((_, rest)) = line.split_once("-*-") {
+        let (inner, _) = rest.split_once("-*-")?;
+        if !inner.contains(':') {
+            return Some(inner.trim()).filter(|name| !name.is_empty());
+        }
+        return inner.split(';').find_map(|field| {
+            let (key, value) = field.split_once(':')?;
+            key.trim().eq_ignore_ascii_case("mode").then_some(value.trim())
+        });
+    }
+
+    let start = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+        let at = line.find(marker)?;
+        let starts_word = at == 0 || line[..at].ends_with(char::is_whitespace);
+        starts_word.then_some(at + marker.len())
+    })?;
+    line[start..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            matches!(key, "ft" | "filetype" | "syntax" | "syn").then_some(value)
+        })
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let empty = Rope::new();
+        let id = |path: &str, content: &Rope| detect(Some(Path::new(path)), content).id;
+        assert_eq!(id("src/main.rs", &empty), "rust");
+        assert_eq!(id("Cargo.toml", &empty), "toml");
+        assert_eq!(id("build/Makefile", &empty), "makefile");
+        assert_eq!(id("Dockerfile", &empty), "dockerfile");
+        assert_eq!(id("README.MD", &empty), "markdown");
+        assert_eq!(id("notes", &empty), "text");
+        assert_eq!(detect(None, &empty).id, "text");
+
+        let script = Rope::from_str("#!/usr/bin/env python3.12\nprint()\n");
+        assert_eq!(id("run", &script), "python");
+        let shell = Rope::from_str("#!/bin/bash -e\n");
+        assert_eq!(detect(None, &shell).id, "shell");
+
+        // Modelines win over the file name
+        let modeline = Rope::from_str("a\nb\nc\nd\ne\nf\ng\n# vim: set ts=4 ft=ruby:\n");
+        assert_eq!(id("notes.txt", &modeline), "ruby");
+    }
+
+    #[test]
+    fn test_modeline_language() {
+        assert_eq!(modeline_language("// -*- mode: rust; tab-width: 4 -*-"), Some("rust"));
+        assert_eq!(modeline_language("# -*- python -*-"), Some("python"));
+        assert_eq!(modeline_language("/* vim: set ft=c ts=8: */"), Some("c"));
+        assert_eq!(modeline_language("# vi: filetype=sh"), Some("sh"));
+        assert_eq!(modeline_language("# vim: ts=2"), None);
+        assert_eq!(modeline_language("let envim: ft=c"), None);
+        assert_eq!(find("C++").map(|language| language.id), Some("cpp"));
+    }
+}
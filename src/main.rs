@@ -4,9 +4,15 @@
 //! a sleek, modern interface.
 
 mod buffer;
+mod clipboard;
 mod config;
+mod editorconfig;
 mod file_io;
+mod language;
+mod session;
+mod syntax;
 mod ui;
+mod workspace;
 
 use eframe::{egui, NativeOptions};
 use env_logger::Env;
@@ -27,6 +33,7 @@ fn main() -> Result<(), eframe::Error> {
             .with_position([100.0, 100.0])
             .with_decorations(true)
             .with_transparent(false)
+            .with_drag_and_drop(true)
             .with_icon(load_icon()),
         ..Default::default()
     };
@@ -75,10 +82,20 @@ impl ZingApp {
         }
         
         // Open: Cmd+O or Ctrl+O
-        if cmd_or_ctrl && ctx.input(|i| i.key_pressed(egui::Key::O)) {
+        if cmd_or_ctrl && !modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::O)) {
             ui::editor::open_file(&mut self.ui_state);
         }
         
+        // Open Folder: Cmd+Shift+O or Ctrl+Shift+O
+        if cmd_or_ctrl && modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::O)) {
+            ui::editor::open_folder(&mut self.ui_state);
+        }
+        
+        // Reveal Active File: Cmd+Shift+E or Ctrl+Shift+E
+        if cmd_or_ctrl && modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::E)) {
+            ui::file_tree::reveal_active_file(&mut self.ui_state);
+        }
+        
         // New Tab: Cmd+T or Ctrl+T
         if cmd_or_ctrl && ctx.input(|i| i.key_pressed(egui::Key::T)) {
             self.ui_state.tabs.new_tab();
@@ -96,11 +113,188 @@ impl ZingApp {
             }
         }
         
-        // Print: Cmd+P or Ctrl+P
-        if cmd_or_ctrl && ctx.input(|i| i.key_pressed(egui::Key::P)) {
+        // Print: Cmd+Alt+P or Ctrl+Alt+P
+        if cmd_or_ctrl && modifiers.alt && ctx.input(|i| i.key_pressed(egui::Key::P)) {
             ui::editor::print_file(&mut self.ui_state);
         }
         
+        // Quick Open: Cmd+P or Ctrl+P
+        if cmd_or_ctrl && !modifiers.alt && !modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::P)) {
+            ui::quick_open::show(&mut self.ui_state);
+        }
+        
+        // Command Palette: Cmd+Shift+P or Ctrl+Shift+P
+        if cmd_or_ctrl && !modifiers.alt && modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::P)) {
+            ui::command_palette::show(&mut self.ui_state);
+        }
+        
+        // Paste from History: Cmd+Shift+V or Ctrl+Shift+V
+        if cmd_or_ctrl && modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::V)) {
+            ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::PasteFromHistory);
+        }
+        
+        // Comments: Cmd+7 or Ctrl+7 toggles line comments and with Shift block comments.
+        // egui has no key for the slash and drops the text it types while Cmd or Ctrl is
+        // held, so the 7 key stands in for it, as on keyboards that type / with Shift+7
+        let (block_comment, line_comment) = ctx.input_mut(|i| {
+            let block = i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Num7);
+            (block, i.consume_key(egui::Modifiers::COMMAND, egui::Key::Num7))
+        });
+        if line_comment {
+            ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::ToggleLineComment);
+        }
+        if block_comment {
+            ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::ToggleBlockComment);
+        }
+        
+        // Line editing: Alt+Up/Down move lines, Cmd+Shift+D or Ctrl+Shift+D duplicates,
+        // Cmd+Shift+K or Ctrl+Shift+K deletes lines, Cmd+J or Ctrl+J joins them, and
+        // Cmd+Enter or Ctrl+Enter starts a line below (with Shift, above)
+        if !ui::file_tree::has_focus(ctx) {
+            let command_shift = egui::Modifiers::COMMAND | egui::Modifiers::SHIFT;
+            let line_shortcuts = [
+                (egui::Modifiers::ALT, egui::Key::ArrowUp, ui::commands::Command::MoveLinesUp),
+                (egui::Modifiers::ALT, egui::Key::ArrowDown, ui::commands::Command::MoveLinesDown),
+                (command_shift, egui::Key::D, ui::commands::Command::Duplicate),
+                (command_shift, egui::Key::K, ui::commands::Command::DeleteLines),
+                (egui::Modifiers::COMMAND, egui::Key::J, ui::commands::Command::JoinLines),
+                (egui::Modifiers::COMMAND, egui::Key::Enter, ui::commands::Command::InsertLineBelow),
+                (command_shift, egui::Key::Enter, ui::commands::Command::InsertLineAbove),
+            ];
+            for (modifiers, key, command) in line_shortcuts {
+                if ctx.input_mut(|i| i.consume_key(modifiers, key)) {
+                    ui::commands::execute(&mut self.ui_state, ctx, command);
+                }
+            }
+        }
+        
+        // Brackets: Ctrl+M jumps to the matching bracket and Ctrl+Shift+M selects to it
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL | egui::Modifiers::SHIFT, egui::Key::M)) {
+            ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::SelectToBracket);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::CTRL, egui::Key::M)) {
+            ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::JumpToBracket);
+        }
+        
+        // Go to Line: Ctrl+G
+        if modifiers.ctrl && !modifiers.shift && ctx.input(|i| i.key_pressed(egui::Key::G)) {
+            ui::go_to_line::show(&mut self.ui_state);
+        }
+        
+        // Recent Tabs: Ctrl+Tab and Ctrl+Shift+Tab, switching when Ctrl is released
+        let (next_tab, previous_tab) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::CTRL, egui::Key::Tab),
+                i.consume_key(egui::Modifiers::CTRL | egui::Modifiers::SHIFT, egui::Key::Tab),
+            )
+        });
+        if next_tab {
+            ui::tab_switcher::cycle(&mut self.ui_state, true);
+        }
+        if previous_tab {
+            ui::tab_switcher::cycle(&mut self.ui_state, false);
+        }
+        
+        // Back/Forward: Alt+Left/Right, or Ctrl+-/Ctrl+Shift+- on macOS where Alt+arrows move by word
+        let (back, forward) = ctx.input_mut(|i| {
+            let (back_modifiers, back_key) = ui::navigation::BACK_SHORTCUT;
+            let (forward_modifiers, forward_key) = ui::navigation::FORWARD_SHORTCUT;
+            (
+                i.consume_key(back_modifiers, back_key),
+                i.consume_key(forward_modifiers, forward_key),
+            )
+        });
+        if back {
+            ui::navigation::go_back(&mut self.ui_state);
+        }
+        if forward {
+            ui::navigation::go_forward(&mut self.ui_state);
+        }
+        
+        // Last Edit Location: Ctrl+Shift+Backspace or Cmd+Shift+Backspace
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Backspace)) {
+            ui::navigation::go_to_last_edit(&mut self.ui_state);
+        }
+        
+        // Bookmarks: Ctrl+F2 toggles, Ctrl+Shift+F2 names, F2/Shift+F2 jump between them
+        if !ui::file_tree::has_focus(ctx) {
+            let (toggle, name, next, previous) = ctx.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::COMMAND, egui::Key::F2),
+                    i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::F2),
+                    i.consume_key(egui::Modifiers::NONE, egui::Key::F2),
+                    i.consume_key(egui::Modifiers::SHIFT, egui::Key::F2),
+                )
+            });
+            if toggle {
+                ui::bookmarks::toggle(&mut self.ui_state);
+            }
+            if name {
+                ui::bookmarks::show_name_prompt(&mut self.ui_state);
+            }
+            if next {
+                ui::bookmarks::jump(&mut self.ui_state, true);
+            }
+            if previous {
+                ui::bookmarks::jump(&mut self.ui_state, false);
+            }
+        }
+        
+        // Folding: Ctrl+Alt+- folds and Ctrl+Alt+= unfolds at the cursor, with Shift for
+        // every region, and Ctrl+Alt+1..9 folds a nesting level (Cmd instead of Ctrl on macOS)
+        let command_alt = egui::Modifiers::COMMAND | egui::Modifiers::ALT;
+        let (fold, unfold, fold_all, unfold_all) = ctx.input_mut(|i| {
+            (
+                i.consume_key(command_alt, egui::Key::Minus),
+                i.consume_key(command_alt, egui::Key::PlusEquals),
+                i.consume_key(command_alt | egui::Modifiers::SHIFT, egui::Key::Minus),
+                i.consume_key(command_alt | egui::Modifiers::SHIFT, egui::Key::PlusEquals),
+            )
+        });
+        if fold {
+            ui::folding::fold(&mut self.ui_state, ctx);
+        }
+        if unfold {
+            ui::folding::unfold(&mut self.ui_state, ctx);
+        }
+        if fold_all {
+            ui::folding::fold_all(&mut self.ui_state, ctx);
+        }
+        if unfold_all {
+            ui::folding::unfold_all(&mut self.ui_state, ctx);
+        }
+        let level_keys = [
+            egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+            egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+            egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+        ];
+        if let Some(index) = ctx.input_mut(|i| level_keys.iter().position(|&key| i.consume_key(command_alt, key))) {
+            ui::folding::fold_level(&mut self.ui_state, ctx, index + 1);
+        }
+        
+        // Panes: Ctrl+Alt+Arrow moves the focus to the next pane that way, and with Shift
+        // splits the focused pane that way (Cmd instead of Ctrl on macOS)
+        let directions = [
+            (egui::Key::ArrowLeft, ui::panes::Direction::Left),
+            (egui::Key::ArrowRight, ui::panes::Direction::Right),
+            (egui::Key::ArrowUp, ui::panes::Direction::Up),
+            (egui::Key::ArrowDown, ui::panes::Direction::Down),
+        ];
+        for (key, direction) in directions {
+            let (focus, split) = ctx.input_mut(|i| {
+                (
+                    i.consume_key(command_alt, key),
+                    i.consume_key(command_alt | egui::Modifiers::SHIFT, key),
+                )
+            });
+            if focus {
+                ui::panes::focus_direction(&mut self.ui_state, direction);
+            }
+            if split {
+                ui::panes::split(&mut self.ui_state, direction);
+            }
+        }
+        
         // Undo: Cmd+Z or Ctrl+Z
         if cmd_or_ctrl && ctx.input(|i| i.key_pressed(egui::Key::Z)) && !modifiers.shift {
             ui::editor::undo(&mut self.ui_state);
@@ -133,9 +327,22 @@ impl ZingApp {
                             ui::editor::open_file(&mut self.ui_state);
                             ui.close_menu();
                         }
+                        if ui.button("Open Folder... Shift+⌘O").clicked() {
+                            ui::editor::open_folder(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        if ui.button("Quick Open... ⌘P").clicked() {
+                            ui::quick_open::show(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        ui.menu_button("Open Recent", |ui| {
+                            ui::recent::menu(&mut self.ui_state, ui);
+                        });
                         
                         // Change the label based on whether it's the last tab
-                        let close_label = if self.ui_state.tabs.tabs.len() <= 1 {
+                        let close_label = if self.ui_state.tabs.tabs.len() <= 1 && self.ui_state.panes.count() > 1 {
+                            "Close Pane ⌘W"
+                        } else if self.ui_state.tabs.tabs.len() <= 1 {
                             "Quit ⌘W"
                         } else {
                             "Close Tab ⌘W"
@@ -161,8 +368,23 @@ impl ZingApp {
                             ui::editor::save_file(&mut self.ui_state, true);
                             ui.close_menu();
                         }
+                        ui.menu_button("Auto Save", |ui| {
+                            for mode in config::AutoSave::ALL {
+                                if ui.radio_value(&mut self.ui_state.config.autosave, mode, mode.label()).clicked() {
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                        ui.menu_button("On Save", |ui| {
+                            for mode in config::TrimWhitespace::ALL {
+                                ui.radio_value(&mut self.ui_state.config.trim_trailing_whitespace, mode, mode.label());
+                            }
+                            ui.separator();
+                            ui.checkbox(&mut self.ui_state.config.final_newline, "Ensure Final Newline");
+                            ui.checkbox(&mut self.ui_state.config.format_on_save, "Format on Save");
+                        });
                         ui.separator();
-                        if ui.button("Print... ⌘P").clicked() {
+                        if ui.button("Print... ⌥⌘P").clicked() {
                             ui::editor::print_file(&mut self.ui_state);
                             ui.close_menu();
                         }
@@ -179,22 +401,91 @@ impl ZingApp {
                             ui.close_menu();
                         }
                         ui.separator();
-                        if ui.button("Cut ⌘X").clicked() {
-                            // Implement cut functionality
-                            ui.close_menu();
+                        let clipboard_commands = [
+                            (ui::commands::Command::Cut, " ⌘X"),
+                            (ui::commands::Command::Copy, " ⌘C"),
+                            (ui::commands::Command::Paste, " ⌘V"),
+                            (ui::commands::Command::PasteAndIndent, " ⌥⌘V"),
+                            (ui::commands::Command::PasteFromHistory, " Shift+⌘V"),
+                            (ui::commands::Command::CopyAsHtml, ""),
+                        ];
+                        for (command, shortcut) in clipboard_commands {
+                            if ui.button(format!("{}{}", command.label(), shortcut)).clicked() {
+                                ui::commands::execute(&mut self.ui_state, ctx, command);
+                                ui.close_menu();
+                            }
                         }
-                        if ui.button("Copy ⌘C").clicked() {
-                            // Implement copy functionality
-                            ui.close_menu();
+                        ui.separator();
+                        let indentation_commands = [
+                            (ui::commands::Command::Indent, " Tab"),
+                            (ui::commands::Command::Outdent, " Shift+Tab"),
+                            (ui::commands::Command::ConvertIndentationToSpaces, ""),
+                            (ui::commands::Command::ConvertIndentationToTabs, ""),
+                        ];
+                        for (command, shortcut) in indentation_commands {
+                            if ui.button(format!("{}{}", command.label(), shortcut)).clicked() {
+                                ui::commands::execute(&mut self.ui_state, ctx, command);
+                                ui.close_menu();
+                            }
                         }
-                        if ui.button("Paste ⌘V").clicked() {
-                            // Implement paste functionality
-                            ui.close_menu();
+                        ui.menu_button("Lines", |ui| {
+                            let line_commands = [
+                                (ui::commands::Command::MoveLinesUp, " ⌥↑"),
+                                (ui::commands::Command::MoveLinesDown, " ⌥↓"),
+                                (ui::commands::Command::Duplicate, " Shift+⌘D"),
+                                (ui::commands::Command::DeleteLines, " Shift+⌘K"),
+                                (ui::commands::Command::JoinLines, " ⌘J"),
+                                (ui::commands::Command::InsertLineAbove, " Shift+⌘↩"),
+                                (ui::commands::Command::InsertLineBelow, " ⌘↩"),
+                            ];
+                            for (command, shortcut) in line_commands {
+                                if ui.button(format!("{}{}", command.label(), shortcut)).clicked() {
+                                    ui::commands::execute(&mut self.ui_state, ctx, command);
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            for command in ui::commands::Command::LINE_TRANSFORMS {
+                                if ui.button(command.label()).clicked() {
+                                    ui::commands::execute(&mut self.ui_state, ctx, command);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                        ui.menu_button("Transform", |ui| {
+                            for transform in buffer::transform::Transform::ALL {
+                                let command = ui::commands::Command::Transform(transform);
+                                if ui.button(command.label()).clicked() {
+                                    ui::commands::execute(&mut self.ui_state, ctx, command);
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button(ui::commands::Command::CopySha256.label()).clicked() {
+                                ui::commands::execute(&mut self.ui_state, ctx, ui::commands::Command::CopySha256);
+                                ui.close_menu();
+                            }
+                        });
+                        ui.separator();
+                        let comment_commands = [
+                            (ui::commands::Command::ToggleLineComment, " ⌘7"),
+                            (ui::commands::Command::ToggleBlockComment, " Shift+⌘7"),
+                        ];
+                        for (command, shortcut) in comment_commands {
+                            if ui.button(format!("{}{}", command.label(), shortcut)).clicked() {
+                                ui::commands::execute(&mut self.ui_state, ctx, command);
+                                ui.close_menu();
+                            }
                         }
                     });
                     
                     // View menu
                     egui::menu::menu_button(ui, "View", |ui| {
+                        if ui.button("Command Palette... Shift+⌘P").clicked() {
+                            ui::command_palette::show(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        ui.separator();
                         if ui.button(if self.ui_state.config.show_line_numbers { "Hide Line Numbers" } else { "Show Line Numbers" }).clicked() {
                             self.ui_state.config.toggle_line_numbers();
                             ui.close_menu();
@@ -203,6 +494,100 @@ impl ZingApp {
                             self.ui_state.config.toggle_word_wrap();
                             ui.close_menu();
                         }
+                        if ui.button(if self.ui_state.config.show_minimap { "Hide Minimap" } else { "Show Minimap" }).clicked() {
+                            self.ui_state.config.toggle_minimap();
+                            ui.close_menu();
+                        }
+                        if ui.button(if self.ui_state.config.show_indent_guides { "Hide Indentation Guides" } else { "Show Indentation Guides" }).clicked() {
+                            self.ui_state.config.toggle_indent_guides();
+                            ui.close_menu();
+                        }
+                        if ui.button(if self.ui_state.config.show_rulers { "Hide Rulers" } else { "Show Rulers" }).clicked() {
+                            self.ui_state.config.toggle_rulers();
+                            ui.close_menu();
+                        }
+                        ui.menu_button("Show Whitespace", |ui| {
+                            for mode in config::ShowWhitespace::ALL {
+                                if ui.radio_value(&mut self.ui_state.config.show_whitespace, mode, mode.label()).clicked() {
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                        ui.separator();
+                        if ui.button("Show Open Tabs").clicked() {
+                            self.ui_state.sidebar_section = ui::SidebarSection::Tabs;
+                            ui.close_menu();
+                        }
+                        if ui.button("Show File Tree").clicked() {
+                            self.ui_state.sidebar_section = ui::SidebarSection::Files;
+                            ui.close_menu();
+                        }
+                        if ui.button("Show Bookmarks").clicked() {
+                            self.ui_state.sidebar_section = ui::SidebarSection::Bookmarks;
+                            ui.close_menu();
+                        }
+                        if ui.button("Reveal Active File Shift+⌘E").clicked() {
+                            ui::file_tree::reveal_active_file(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        ui.menu_button("Split Editor", |ui| {
+                            let splits = [
+                                ("Split Right ⇧⌥⌘→", ui::panes::Direction::Right),
+                                ("Split Left ⇧⌥⌘←", ui::panes::Direction::Left),
+                                ("Split Down ⇧⌥⌘↓", ui::panes::Direction::Down),
+                                ("Split Up ⇧⌥⌘↑", ui::panes::Direction::Up),
+                            ];
+                            for (label, direction) in splits {
+                                if ui.button(label).clicked() {
+                                    ui::panes::split(&mut self.ui_state, direction);
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            let focuses = [
+                                ("Focus Left Pane ⌥⌘←", ui::panes::Direction::Left),
+                                ("Focus Right Pane ⌥⌘→", ui::panes::Direction::Right),
+                                ("Focus Pane Above ⌥⌘↑", ui::panes::Direction::Up),
+                                ("Focus Pane Below ⌥⌘↓", ui::panes::Direction::Down),
+                            ];
+                            for (label, direction) in focuses {
+                                if ui.button(label).clicked() {
+                                    ui::panes::focus_direction(&mut self.ui_state, direction);
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.add_enabled(self.ui_state.panes.count() > 1, egui::Button::new("Close Pane")).clicked() {
+                                ui::panes::close(&mut self.ui_state);
+                                ui.close_menu();
+                            }
+                        });
+                        ui.menu_button("Folding", |ui| {
+                            if ui.button("Fold ⌥⌘-").clicked() {
+                                ui::folding::fold(&mut self.ui_state, ctx);
+                                ui.close_menu();
+                            }
+                            if ui.button("Unfold ⌥⌘=").clicked() {
+                                ui::folding::unfold(&mut self.ui_state, ctx);
+                                ui.close_menu();
+                            }
+                            if ui.button("Fold All ⇧⌥⌘-").clicked() {
+                                ui::folding::fold_all(&mut self.ui_state, ctx);
+                                ui.close_menu();
+                            }
+                            if ui.button("Unfold All ⇧⌥⌘=").clicked() {
+                                ui::folding::unfold_all(&mut self.ui_state, ctx);
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                            for level in 1..=3 {
+                                if ui.button(format!("Fold Level {} ⌥⌘{}", level, level)).clicked() {
+                                    ui::folding::fold_level(&mut self.ui_state, ctx, level);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
                         ui.separator();
                         if ui.button(if matches!(self.ui_state.config.theme, crate::config::Theme::Dark) { "Light Theme" } else { "Dark Theme" }).clicked() {
                             self.ui_state.toggle_theme(ctx);
@@ -210,6 +595,54 @@ impl ZingApp {
                         }
                     });
                     
+                    // Go menu
+                    egui::menu::menu_button(ui, "Go", |ui| {
+                        if ui.button(format!("Back {}", ui::navigation::SHORTCUT_LABELS.0)).clicked() {
+                            ui::navigation::go_back(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        if ui.button(format!("Forward {}", ui::navigation::SHORTCUT_LABELS.1)).clicked() {
+                            ui::navigation::go_forward(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        if ui.button("Last Edit Location ⇧⌘⌫").clicked() {
+                            ui::navigation::go_to_last_edit(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        if ui.button("Go to Line... ⌃G").clicked() {
+                            ui::go_to_line::show(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        let bracket_commands = [
+                            (ui::commands::Command::JumpToBracket, " ⌃M"),
+                            (ui::commands::Command::SelectToBracket, " ⌃⇧M"),
+                        ];
+                        for (command, shortcut) in bracket_commands {
+                            if ui.button(format!("{}{}", command.label(), shortcut)).clicked() {
+                                ui::commands::execute(&mut self.ui_state, ctx, command);
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button("Toggle Bookmark ⌘F2").clicked() {
+                            ui::bookmarks::toggle(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        if ui.button("Name Bookmark... Shift+⌘F2").clicked() {
+                            ui::bookmarks::show_name_prompt(&mut self.ui_state);
+                            ui.close_menu();
+                        }
+                        if ui.button("Next Bookmark F2").clicked() {
+                            ui::bookmarks::jump(&mut self.ui_state, true);
+                            ui.close_menu();
+                        }
+                        if ui.button("Previous Bookmark Shift+F2").clicked() {
+                            ui::bookmarks::jump(&mut self.ui_state, false);
+                            ui.close_menu();
+                        }
+                    });
+                    
                     // Format menu
                     egui::menu::menu_button(ui, "Format", |ui| {
                         if ui.button("Decrease Font Size").clicked() {
@@ -249,6 +682,11 @@ impl eframe::App for ZingApp {
         // Render the UI
         ui::ui(&mut self.ui_state, ctx);
     }
+    
+    /// Called once before the application shuts down.
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.ui_state.save_session();
+    }
 }
 
 /// Loads the application icon.
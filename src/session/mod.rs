@@ -0,0 +1,285 @@
+//! Session module for Zing text editor.
+//!
+//! The session holds the state that is kept between runs of the editor, such as
+//! bookmarks, folded regions, the layout of the editor panes and the recently
+//! opened files and folders, and is stored as JSON in the user's configuration
+//! folder.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the session file inside the configuration folder.
+const SESSION_FILE: &str = "session.json";
+/// Number of recently opened files remembered.
+const MAX_RECENT_FILES: usize = 50;
+/// Number of recently opened folders remembered.
+const MAX_RECENT_FOLDERS: usize = 20;
+
+/// A bookmark saved with the session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedBookmark {
+    /// The bookmarked file
+    pub path: PathBuf,
+    /// The bookmarked line (0-indexed)
+    pub line: usize,
+    /// Optional name of the bookmark
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// A folded region saved with the session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFold {
+    /// The file the region is in
+    pub path: PathBuf,
+    /// The first line of the region (0-indexed)
+    pub line: usize,
+}
+
+/// The arrangement of the editor panes saved with the session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SavedLayout {
+    /// A pane and the files open in its tabs
+    Pane {
+        /// Files of the tabs, in order
+        files: Vec<PathBuf>,
+        /// Index of the active tab
+        active: usize,
+    },
+    /// Two layouts sharing the space
+    Split {
+        /// Whether the sides are next to each other rather than stacked
+        side_by_side: bool,
+        /// Share of the space taken by the first side
+        ratio: f32,
+        /// The left or top side
+        first: Box<SavedLayout>,
+        /// The right or bottom side
+        second: Box<SavedLayout>,
+    },
+}
+
+/// State restored when the editor starts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Bookmarks of all files, open or not
+    #[serde(default)]
+    pub bookmarks: Vec<SavedBookmark>,
+    /// Folded regions of all files, open or not
+    #[serde(default)]
+    pub folds: Vec<SavedFold>,
+    /// Layout of the editor panes when the editor was last closed
+    #[serde(default)]
+    pub layout: Option<SavedLayout>,
+    /// Recently opened files, the most recent first
+    #[serde(default)]
+    pub recent_files: Vec<PathBuf>,
+    /// Recently opened workspace folders, the most recent first
+    #[serde(default)]
+    pub recent_folders: Vec<PathBuf>,
+}
+
+/// Moves `path` to the front of `list`, keeping at most `max` entries.
+fn note_recent(list: &mut Vec<PathBuf>, path: PathBuf, max: usize) {
+    list.retain(|p| *p != path);
+    list.insert(0, path);
+    list.truncate(max);
+}
+
+impl Session {
+    /// Loads the session from the configuration folder, or returns an empty one.
+    pub fn load() -> Self {
+        let Some(path) = session_path() else {
+            return Self::default();
+        };
+        if !path.exists() {
+            return Self::default();
+        }
+
+        Self::load_from(&path).unwrap_or_else(|err| {
+            log::warn!("Ignoring unreadable session: {:#}", err);
+            Self::default()
+        })
+    }
+
+    /// Saves the session to the configuration folder.
+    pub fn save(&self) -> Result<()> {
+        let path = session_path().ok_or_else(|| anyhow::anyhow!("No configuration folder found"))?;
+        self.save_to(&path)
+    }
+
+    /// Loads a session from `path`.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session: {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse session: {}", path.display()))
+    }
+
+    /// Saves the session to `path`, creating its folder if needed.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create folder: {}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize session")?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write session: {}", path.display()))
+    }
+
+    /// Returns the saved bookmarks of `path` as lines and names.
+    pub fn bookmarks_for(&self, path: &Path) -> Vec<(usize, Option<String>)> {
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.path == path)
+            .map(|bookmark| (bookmark.line, bookmark.name.clone()))
+            .collect()
+    }
+
+    /// Replaces the saved bookmarks of `path`.
+    pub fn set_bookmarks_for(&mut self, path: &Path, bookmarks: Vec<(usize, Option<String>)>) {
+        self.bookmarks.retain(|bookmark| bookmark.path != path);
+        self.bookmarks.extend(bookmarks.into_iter().map(|(line, name)| SavedBookmark {
+            path: path.to_path_buf(),
+            line,
+            name,
+        }));
+    }
+
+    /// Moves `path` to the front of the recently opened files.
+    pub fn note_recent_file(&mut self, path: PathBuf) {
+        note_recent(&mut self.recent_files, path, MAX_RECENT_FILES);
+    }
+
+    /// Moves `path` to the front of the recently opened folders.
+    pub fn note_recent_folder(&mut self, path: PathBuf) {
+        note_recent(&mut self.recent_folders, path, MAX_RECENT_FOLDERS);
+    }
+
+    /// Removes `path` from the recently opened files and folders.
+    pub fn forget_recent(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+        self.recent_folders.retain(|p| p != path);
+    }
+
+    /// Removes recently opened files and folders that no longer exist.
+    pub fn prune_recent(&mut self) {
+        self.recent_files.retain(|path| path.is_file());
+        self.recent_folders.retain(|path| path.is_dir());
+    }
+
+    /// Forgets all recently opened files and folders.
+    pub fn clear_recent(&mut self) {
+        self.recent_files.clear();
+        self.recent_folders.clear();
+    }
+
+    /// Returns the first lines of the saved folded regions of `path`.
+    pub fn folds_for(&self, path: &Path) -> Vec<usize> {
+        self.folds
+            .iter()
+            .filter(|fold| fold.path == path)
+            .map(|fold| fold.line)
+            .collect()
+    }
+
+    /// Replaces the saved folded regions of `path`.
+    pub fn set_folds_for(&mut self, path: &Path, lines: Vec<usize>) {
+        self.folds.retain(|fold| fold.path != path);
+        self.folds.extend(lines.into_iter().map(|line| SavedFold {
+            path: path.to_path_buf(),
+            line,
+        }));
+    }
+}
+
+/// Returns the folder Zing keeps its settings and session in.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|base| base.join("zing"))
+}
+
+/// Returns the path of the session file.
+fn session_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(SESSION_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("zing/session.json");
+
+        let mut session = Session::default();
+        session.set_bookmarks_for(Path::new("/a.rs"), vec![(3, None), (10, Some("todo".to_string()))]);
+        session.set_bookmarks_for(Path::new("/b.rs"), vec![(0, None)]);
+        session.set_bookmarks_for(Path::new("/a.rs"), vec![(4, None)]);
+        session.set_folds_for(Path::new("/b.rs"), vec![2, 7]);
+        session.layout = Some(SavedLayout::Split {
+            side_by_side: true,
+            ratio: 0.25,
+            first: Box::new(SavedLayout::Pane { files: vec![PathBuf::from("/a.rs")], active: 0 }),
+            second: Box::new(SavedLayout::Pane { files: Vec::new(), active: 0 }),
+        });
+        session.save_to(&path)?;
+
+        let loaded = Session::load_from(&path)?;
+        assert_eq!(loaded, session);
+        assert_eq!(loaded.bookmarks_for(Path::new("/a.rs")), vec![(4, None)]);
+        assert_eq!(loaded.bookmarks_for(Path::new("/b.rs")), vec![(0, None)]);
+        assert_eq!(loaded.folds_for(Path::new("/b.rs")), vec![2, 7]);
+        assert!(loaded.folds_for(Path::new("/a.rs")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recent() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "")?;
+        let missing = dir.path().join("gone.rs");
+
+        let mut session = Session::default();
+        for i in 0..MAX_RECENT_FILES + 5 {
+            session.note_recent_file(dir.path().join(format!("{}.rs", i)));
+        }
+        assert_eq!(session.recent_files.len(), MAX_RECENT_FILES);
+        session.note_recent_file(missing.clone());
+        session.note_recent_file(file.clone());
+        session.note_recent_file(missing.clone());
+        assert_eq!(session.recent_files[..2], [missing.clone(), file.clone()]);
+        assert_eq!(session.recent_files.len(), MAX_RECENT_FILES);
+
+        session.note_recent_folder(dir.path().to_path_buf());
+        session.note_recent_folder(dir.path().join("gone"));
+        session.prune_recent();
+        assert_eq!(session.recent_files, vec![file.clone()]);
+        assert_eq!(session.recent_folders, vec![dir.path().to_path_buf()]);
+
+        session.forget_recent(&file);
+        assert!(session.recent_files.is_empty());
+        session.clear_recent();
+        assert!(session.recent_folders.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_fields_default() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("session.json");
+        std::fs::write(&path, "{}")?;
+        assert_eq!(Session::load_from(&path)?, Session::default());
+        Ok(())
+    }
+}
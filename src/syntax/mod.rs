@@ -0,0 +1,39 @@
+//! Syntax module for Zing text editor.
+//!
+//! This module loads the syntax definitions and themes used for highlighting.
+
+use std::sync::OnceLock;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+use crate::config::Theme;
+
+/// Returns the lazily loaded syntax definitions used for highlighting.
+pub fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Returns the lazily loaded highlighting themes.
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Returns the syntax for files with `extension`, falling back to plain text.
+/// Languages name the extension their syntax is found by.
+pub fn find_syntax(extension: Option<&str>) -> &'static SyntaxReference {
+    let syntaxes = syntax_set();
+    extension
+        .and_then(|ext| syntaxes.find_syntax_by_extension(ext))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
+
+/// Returns the highlighting theme matching the editor `theme`.
+pub fn highlighting_theme(theme: Theme) -> &'static syntect::highlighting::Theme {
+    let theme_name = match theme {
+        Theme::Dark => "base16-ocean.dark",
+        Theme::Light => "InspiredGitHub",
+    };
+    &theme_set().themes[theme_name]
+}
@@ -0,0 +1,145 @@
+//! Autosave for Zing text editor.
+//!
+//! Saves modified files without being asked, depending on the autosave mode of
+//! the configuration: once edits stop for a while, when the window loses focus
+//! or when switching tabs. Files that were never saved are left alone.
+
+use egui::Context;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::buffer::{ChangedOnDisk, TextBuffer};
+use crate::config::AutoSave;
+use crate::ui::{editor, ZingApp};
+
+/// How long the status bar shows that files were saved.
+const SAVED_INDICATOR_SECS: f32 = 2.0;
+
+/// State of autosave kept between frames.
+#[derive(Debug, Default)]
+pub struct AutoSaveState {
+    /// The active buffer and its revision in the last frame
+    seen: Option<(Weak<Mutex<TextBuffer>>, u64)>,
+    /// A buffer edited since it was saved and when it is due to be saved
+    due: Option<(Weak<Mutex<TextBuffer>>, Instant)>,
+    /// Whether the window had focus in the last frame
+    window_focused: bool,
+    /// When files were last saved automatically
+    last_saved: Option<Instant>,
+}
+
+impl AutoSaveState {
+    /// Creates the autosave state.
+    pub fn new() -> Self {
+        Self {
+            window_focused: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether files were saved automatically a moment ago.
+    pub fn recently_saved(&self) -> bool {
+        self.last_saved
+            .is_some_and(|saved| saved.elapsed().as_secs_f32() < SAVED_INDICATOR_SECS)
+    }
+}
+
+/// Saves those of `buffers` that are modified and have a file.
+///
+/// The on-save actions only run with `save_actions`, so saving after a pause
+/// in typing does not reformat the text being typed.
+fn save_all(app: &mut ZingApp, buffers: Vec<Arc<Mutex<TextBuffer>>>, save_actions: bool) {
+    for buffer in buffers {
+        let needs_saving = {
+            let buffer_lock = buffer.lock().unwrap();
+            buffer_lock.file_path.is_some() && buffer_lock.is_modified()
+        };
+        if needs_saving {
+            editor::save_buffer(app, buffer, save_actions, true);
+        }
+    }
+}
+
+/// Notes the time of a save for the status bar, or shows what failed.
+pub fn show_saved(app: &mut ZingApp, result: anyhow::Result<Option<String>>) {
+    match result {
+        Ok(None) => app.autosave.last_saved = Some(Instant::now()),
+        Ok(Some(problem)) => {
+            app.autosave.last_saved = Some(Instant::now());
+            app.set_status(format!("Saved file, but {}", problem), 5.0);
+        }
+        // Changes made by another program are never overwritten without asking
+        Err(err) if err.is::<ChangedOnDisk>() => {
+            log::warn!("Skipped autosave: {}", err);
+            app.set_status(format!("Not saved automatically: {}", err), 5.0);
+        }
+        Err(err) => {
+            log::error!("Failed to autosave: {}", err);
+            app.set_status(format!("Failed to save file: {}", err), 5.0);
+        }
+    }
+}
+
+/// Returns the buffers of the tabs in every pane, each only once.
+fn open_buffers(app: &ZingApp) -> Vec<Arc<Mutex<TextBuffer>>> {
+    let other_tabs = app.panes.other_tabs().flat_map(|tabs| tabs.tabs.iter());
+    let mut buffers: Vec<Arc<Mutex<TextBuffer>>> = Vec::new();
+    for tab in app.tabs.tabs.iter().chain(other_tabs) {
+        if !buffers.iter().any(|buffer| Arc::ptr_eq(buffer, &tab.buffer)) {
+            buffers.push(tab.buffer.clone());
+        }
+    }
+    buffers
+}
+
+/// Saves files as the autosave mode asks, called once per frame.
+pub fn update(app: &mut ZingApp, ctx: &Context) {
+    let active = app.buffer();
+    let revision = active.lock().unwrap().revision();
+    let window_focused = ctx.input(|i| i.focused);
+
+    let previous = app.autosave.seen.replace((Arc::downgrade(&active), revision));
+    let switched = previous.as_ref().is_some_and(|(buffer, _)| !Weak::ptr_eq(buffer, &Arc::downgrade(&active)));
+    let edited = previous.as_ref().is_some_and(|(_, seen)| !switched && *seen != revision);
+    let lost_focus = app.autosave.window_focused && !window_focused;
+    app.autosave.window_focused = window_focused;
+
+    match app.config.autosave {
+        AutoSave::Off => {
+            app.autosave.due = None;
+        }
+        AutoSave::AfterDelay => {
+            let delay = Duration::from_secs_f32(app.config.autosave_delay.max(0.0));
+            if edited {
+                // An edit elsewhere saves the buffer still waiting right away
+                if let Some((waiting, _)) = app.autosave.due.take() {
+                    if let Some(waiting) = waiting.upgrade().filter(|waiting| !Arc::ptr_eq(waiting, &active)) {
+                        save_all(app, vec![waiting], false);
+                    }
+                }
+                app.autosave.due = Some((Arc::downgrade(&active), Instant::now() + delay));
+            }
+            if let Some((buffer, at)) = app.autosave.due.clone() {
+                let now = Instant::now();
+                if now >= at {
+                    app.autosave.due = None;
+                    save_all(app, buffer.upgrade().into_iter().collect(), false);
+                } else {
+                    ctx.request_repaint_after(at - now);
+                }
+            }
+        }
+        AutoSave::OnFocusLoss => {
+            if lost_focus {
+                let buffers = open_buffers(app);
+                save_all(app, buffers, true);
+            }
+        }
+        AutoSave::OnTabSwitch => {
+            if switched {
+                let left = previous.and_then(|(buffer, _)| buffer.upgrade());
+                save_all(app, left.into_iter().collect(), true);
+            }
+        }
+    }
+}
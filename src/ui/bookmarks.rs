@@ -0,0 +1,248 @@
+//! Bookmarks for Zing text editor.
+//!
+//! Commands to toggle, name and jump between bookmarked lines, and the sidebar
+//! panel listing the bookmarks of all open files.
+
+use egui::{Align2, Color32, Context, Key, Modifiers, RichText, Sense, Ui, Vec2};
+
+use crate::config::Theme;
+use crate::ui::{editor, ZingApp};
+
+/// Maximum number of characters of a line shown in the bookmark list.
+const PREVIEW_CHARS: usize = 60;
+
+/// State of the prompt asking for a bookmark name.
+#[derive(Debug, Default)]
+pub struct BookmarkPrompt {
+    /// Whether the prompt is visible
+    pub open: bool,
+    /// The name typed by the user
+    name: String,
+    /// The line being bookmarked
+    line: usize,
+}
+
+impl BookmarkPrompt {
+    /// Creates a closed bookmark prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Toggles an anonymous bookmark on `line` of the active buffer.
+pub fn toggle_line(app: &mut ZingApp, line: usize) {
+    let buffer = app.buffer();
+    let result = buffer.lock().unwrap().toggle_bookmark(line);
+    match result {
+        Ok(true) => app.set_status(format!("Bookmarked line {}", line + 1), 2.0),
+        Ok(false) => app.set_status(format!("Removed bookmark from line {}", line + 1), 2.0),
+        Err(err) => {
+            log::error!("Failed to toggle bookmark: {}", err);
+            return;
+        }
+    }
+    app.save_session();
+}
+
+/// Toggles an anonymous bookmark on the cursor line.
+pub fn toggle(app: &mut ZingApp) {
+    toggle_line(app, app.cursor_line);
+}
+
+/// Moves the cursor to the next bookmark in the active buffer, or the previous
+/// one when `forward` is false.
+pub fn jump(app: &mut ZingApp, forward: bool) {
+    let buffer = app.buffer();
+    let target = buffer.lock().unwrap().next_bookmark(app.cursor_line, forward);
+    match target {
+        Some(line) => editor::go_to(app, line, 0),
+        None => app.set_status("No bookmarks in this file".to_string(), 2.0),
+    }
+}
+
+/// Asks for a name for the bookmark on the cursor line, creating it if needed.
+pub fn show_name_prompt(app: &mut ZingApp) {
+    let line = app.cursor_line;
+    let buffer = app.buffer();
+    let name = buffer
+        .lock()
+        .unwrap()
+        .bookmarks()
+        .into_iter()
+        .find(|(l, _)| *l == line)
+        .and_then(|(_, name)| name.map(str::to_string))
+        .unwrap_or_default();
+
+    app.bookmark_prompt = BookmarkPrompt { open: true, name, line };
+}
+
+/// Renders the bookmark name prompt if it is open.
+pub fn prompt_ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.bookmark_prompt.open {
+        return;
+    }
+
+    let (enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    if escape {
+        app.bookmark_prompt.open = false;
+        return;
+    }
+    if enter {
+        app.bookmark_prompt.open = false;
+        let line = app.bookmark_prompt.line;
+        let name = app.bookmark_prompt.name.trim().to_string();
+        let buffer = app.buffer();
+        let result = buffer.lock().unwrap().set_bookmark(line, Some(name));
+        if let Err(err) = result {
+            log::error!("Failed to set bookmark: {}", err);
+            return;
+        }
+        app.set_status(format!("Bookmarked line {}", line + 1), 2.0);
+        app.save_session();
+        return;
+    }
+
+    egui::Area::new("bookmark_prompt")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(320.0);
+                ui.label(format!("Bookmark line {}", app.bookmark_prompt.line + 1));
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut app.bookmark_prompt.name)
+                        .hint_text("Bookmark name")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+            });
+        });
+}
+
+/// An entry of the bookmark list.
+struct BookmarkRow {
+    /// Index of the tab the bookmark is in
+    tab: usize,
+    /// Bookmarked line
+    line: usize,
+    /// Name of the bookmark, or the text of its line
+    label: String,
+    /// Whether the bookmark has a name
+    named: bool,
+}
+
+/// Collects the bookmarks of all open tabs, grouped by tab.
+fn collect_rows(app: &ZingApp) -> Vec<(String, Vec<BookmarkRow>)> {
+    let titles = app.tabs.titles();
+    app.tabs
+        .tabs
+        .iter()
+        .enumerate()
+        .filter_map(|(tab_index, tab)| {
+            let buffer = tab.buffer.lock().unwrap();
+            let rows: Vec<BookmarkRow> = buffer
+                .bookmarks()
+                .into_iter()
+                .map(|(line, name)| {
+                    let (label, named) = match name {
+                        Some(name) => (name.to_string(), true),
+                        None => {
+                            let text = buffer.line(line).unwrap_or_default();
+                            (text.trim().chars().take(PREVIEW_CHARS).collect(), false)
+                        }
+                    };
+                    BookmarkRow { tab: tab_index, line, label, named }
+                })
+                .collect();
+            (!rows.is_empty()).then(|| (titles[tab_index].to_string(), rows))
+        })
+        .collect()
+}
+
+/// Renders the bookmark list in the sidebar.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (text_color, weak_color, marker_color) = if is_dark {
+        (Color32::from_rgb(200, 200, 215), Color32::from_gray(120), Color32::from_rgb(86, 156, 255))
+    } else {
+        (Color32::from_rgb(50, 50, 60), Color32::from_gray(130), Color32::from_rgb(0, 120, 215))
+    };
+
+    let groups = collect_rows(app);
+    let mut go_to = None;
+    let mut remove = None;
+
+    ui.horizontal(|ui| {
+        ui.add_space(8.0);
+        ui.label(RichText::new("BOOKMARKS").size(10.0).color(weak_color));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if !groups.is_empty() && ui.small_button("Clear").on_hover_text("Remove all bookmarks").clicked() {
+                for tab in &app.tabs.tabs {
+                    tab.buffer.lock().unwrap().clear_bookmarks();
+                }
+                app.save_session();
+            }
+        });
+    });
+
+    if groups.is_empty() {
+        ui.add_space(8.0);
+        ui.horizontal_wrapped(|ui| {
+            ui.add_space(8.0);
+            ui.label(RichText::new("No bookmarks. Press Ctrl+F2 to bookmark the current line.").size(11.0).color(weak_color));
+        });
+        return;
+    }
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for (title, rows) in &groups {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.add_space(8.0);
+                    ui.label(RichText::new(title).size(11.0).strong().color(text_color));
+                });
+
+                for row in rows {
+                    let response = ui
+                        .horizontal(|ui| {
+                            ui.add_space(12.0);
+                            ui.label(RichText::new("▍").size(11.0).color(marker_color));
+                            ui.label(RichText::new(format!("{:>4}", row.line + 1)).size(11.0).monospace().color(weak_color));
+                            let label = RichText::new(&row.label).size(11.0).color(text_color);
+                            ui.label(if row.named { label.strong() } else { label });
+                        })
+                        .response
+                        .interact(Sense::click())
+                        .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+                    if response.clicked() {
+                        go_to = Some((row.tab, row.line));
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button("Remove Bookmark").clicked() {
+                            remove = Some((row.tab, row.line));
+                            ui.close_menu();
+                        }
+                    });
+                }
+            }
+        });
+
+    if let Some((tab, line)) = remove {
+        if let Some(tab) = app.tabs.tabs.get(tab) {
+            tab.buffer.lock().unwrap().remove_bookmark(line);
+        }
+        app.save_session();
+    }
+    if let Some((tab, line)) = go_to {
+        app.tabs.active_tab = tab;
+        editor::go_to(app, line, 0);
+    }
+}
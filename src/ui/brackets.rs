@@ -0,0 +1,109 @@
+//! Bracket matching and auto-closing pairs for Zing text editor.
+//!
+//! The brackets and quotes come from the language of the active file. Typing
+//! them closes, wraps or types over pairs unless the language settings turn
+//! that off.
+
+use egui::text::CCursor;
+use egui::{Color32, Context, Rect, Stroke, Ui, Vec2};
+
+use crate::buffer::{brackets, FoldMap, TextBuffer};
+use crate::ui::{editor, indentation, ZingApp};
+
+/// Returns the pairs typing closes in the active buffer, which are none when
+/// its language settings turn auto-closing off.
+pub fn auto_close_pairs(app: &ZingApp) -> &'static [(char, char)] {
+    let language = app.buffer().lock().unwrap().language;
+    if app.config.auto_close_for(language.id) {
+        language.pairs
+    } else {
+        &[]
+    }
+}
+
+/// Returns whether the editor leaves typing `text` to this module.
+pub fn takes_text(text: &str, pairs: &[(char, char)]) -> bool {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => pairs.iter().any(|&(open, close)| c == open || c == close),
+        _ => false,
+    }
+}
+
+/// Types `c` over the selection, closing, wrapping in or typing over pairs.
+pub fn type_char(app: &mut ZingApp, ctx: &Context, c: char) {
+    let pairs = auto_close_pairs(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = indentation::selection(app, &buffer_lock);
+    let result = buffer_lock.type_char(selection, c, pairs);
+    drop(buffer_lock);
+    match result {
+        Ok(selection) => editor::set_selection(app, ctx, selection),
+        Err(err) => {
+            log::error!("Failed to type: {}", err);
+            app.set_status(format!("Failed to type: {}", err), 5.0);
+        }
+    }
+}
+
+/// Returns the bracket next to the cursor and its match, or else the
+/// brackets around the cursor, opening one first.
+fn target(app: &ZingApp, buffer: &TextBuffer) -> Option<(usize, usize)> {
+    let pairs = buffer.language.pairs;
+    let cursor = app.cursor_pos.min(buffer.len_chars());
+    brackets::matching_pair(&buffer.content, cursor, pairs)
+        .or_else(|| brackets::enclosing_pair(&buffer.content, cursor, pairs))
+}
+
+/// Moves the cursor to the bracket matching the one next to it, or to the
+/// closing bracket around it.
+pub fn jump_to_match(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let cursor = app.cursor_pos.min(buffer_lock.len_chars());
+    let pairs = buffer_lock.language.pairs;
+    let target = match brackets::matching_pair(&buffer_lock.content, cursor, pairs) {
+        Some((_, other)) => Some(other),
+        None => brackets::enclosing_pair(&buffer_lock.content, cursor, pairs).map(|(_, close)| close),
+    };
+    drop(buffer_lock);
+    if let Some(pos) = target {
+        editor::set_selection(app, ctx, pos..pos);
+    }
+}
+
+/// Selects the brackets next to or around the cursor and everything between.
+pub fn select_to_match(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let target = target(app, &buffer_lock);
+    drop(buffer_lock);
+    if let Some((a, b)) = target {
+        editor::set_selection(app, ctx, a.min(b)..a.max(b) + 1);
+    }
+}
+
+/// Outlines the bracket next to the cursor and the one matching it.
+pub fn paint_match(
+    ui: &Ui,
+    output: &egui::text_edit::TextEditOutput,
+    buffer: &TextBuffer,
+    fold_map: &FoldMap,
+    cursor: usize,
+    char_width: f32,
+    color: Color32,
+) {
+    let Some((a, b)) = brackets::matching_pair(&buffer.content, cursor, buffer.language.pairs) else {
+        return;
+    };
+    for pos in [a, b] {
+        if fold_map.hiding(pos).is_some() {
+            continue;
+        }
+        let cursor = output.galley.from_ccursor(CCursor::new(fold_map.to_display(pos)));
+        let rect = output.galley.pos_from_cursor(&cursor).translate(output.text_draw_pos.to_vec2());
+        let rect = Rect::from_min_size(rect.min, Vec2::new(char_width, rect.height()));
+        ui.painter().rect_stroke(rect, 2.0, Stroke::new(1.0, color));
+    }
+}
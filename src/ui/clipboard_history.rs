@@ -0,0 +1,160 @@
+//! Clipboard history picker for Zing text editor.
+//!
+//! Lists the text copied or cut earlier in the session so any of it can be
+//! pasted again, filtered by what the user types.
+
+use egui::{Align2, Context, Key, Modifiers, RichText, Vec2};
+
+use crate::ui::{commands, ZingApp};
+
+/// Maximum number of characters of an entry shown in the list.
+const PREVIEW_CHARS: usize = 80;
+
+/// State of the clipboard history picker.
+#[derive(Debug, Default)]
+pub struct ClipboardHistory {
+    /// Whether the picker is visible
+    pub open: bool,
+    /// Filter typed by the user
+    query: String,
+    /// Index of the highlighted entry among the filtered ones
+    selected: usize,
+}
+
+impl ClipboardHistory {
+    /// Creates a closed clipboard history picker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Shows the clipboard history picker.
+pub fn show(app: &mut ZingApp) {
+    if app.clipboard.is_empty() {
+        app.set_status("Clipboard history is empty".to_string(), 2.0);
+        return;
+    }
+
+    app.clipboard_history.open = true;
+    app.clipboard_history.query.clear();
+    app.clipboard_history.selected = 0;
+}
+
+/// Returns the indices of the history entries matching the filter.
+fn filtered(app: &ZingApp) -> Vec<usize> {
+    let query = app.clipboard_history.query.to_lowercase();
+    app.clipboard
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| query.is_empty() || entry.text.to_lowercase().contains(&query))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Returns a one line summary of `text` for the list.
+fn preview(text: &str) -> String {
+    let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default().trim();
+    let mut preview: String = first_line.chars().take(PREVIEW_CHARS).collect();
+    if first_line.chars().count() > PREVIEW_CHARS {
+        preview.push('…');
+    }
+
+    let lines = text.trim_end_matches('\n').lines().count();
+    if lines > 1 {
+        preview.push_str(&format!("  ({} lines)", lines));
+    }
+    preview
+}
+
+/// Pastes the history entry at `index` and closes the picker.
+fn accept(app: &mut ZingApp, ctx: &Context, index: usize) {
+    app.clipboard_history.open = false;
+    if let Some(entry) = app.clipboard.get(index).cloned() {
+        commands::paste_text(app, ctx, &entry.text, entry.line_mode, false);
+        app.clipboard.push(entry.text, entry.line_mode);
+    }
+}
+
+/// Renders the clipboard history picker if it is open.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.clipboard_history.open {
+        return;
+    }
+
+    let matches = filtered(app);
+
+    // Handle navigation keys before the text field sees them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::ArrowUp),
+            i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    let selected = &mut app.clipboard_history.selected;
+    *selected = (*selected).min(matches.len().saturating_sub(1));
+    if up && *selected > 0 {
+        *selected -= 1;
+    }
+    if down && *selected + 1 < matches.len() {
+        *selected += 1;
+    }
+    if escape {
+        app.clipboard_history.open = false;
+        return;
+    }
+    if enter {
+        if let Some(&index) = matches.get(app.clipboard_history.selected) {
+            accept(app, ctx, index);
+        } else {
+            app.clipboard_history.open = false;
+        }
+        return;
+    }
+
+    let mut clicked = None;
+    egui::Area::new("clipboard_history")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(520.0);
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut app.clipboard_history.query)
+                        .hint_text("Filter clipboard history")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    app.clipboard_history.selected = 0;
+                }
+
+                ui.separator();
+
+                if matches.is_empty() {
+                    ui.weak("No matching entries");
+                }
+
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (row, &index) in matches.iter().enumerate() {
+                        let Some(entry) = app.clipboard.get(index) else { continue };
+                        let is_selected = row == app.clipboard_history.selected;
+                        let label = RichText::new(preview(&entry.text)).monospace();
+                        let response = ui.selectable_label(is_selected, label);
+                        if is_selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            clicked = Some(index);
+                        }
+                    }
+                });
+            });
+        });
+
+    if let Some(index) = clicked {
+        accept(app, ctx, index);
+    }
+}
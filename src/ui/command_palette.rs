@@ -0,0 +1,143 @@
+//! Command palette for Zing text editor.
+//!
+//! Lists the editing commands by name and runs the one the user picks, fuzzy
+//! matching what they type against the names.
+
+use egui::{Align2, Color32, Context, Key, Modifiers, Vec2};
+
+use crate::config::Theme;
+use crate::ui::commands::{self, Command};
+use crate::ui::quick_open::match_label;
+use crate::ui::ZingApp;
+use crate::workspace::fuzzy::{fuzzy_match, FuzzyMatch};
+
+/// State of the command palette.
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    /// Whether the palette is visible
+    pub open: bool,
+    /// The text typed by the user
+    query: String,
+    /// Index of the highlighted command
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// Creates a closed command palette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Shows the command palette with an empty query.
+pub fn show(app: &mut ZingApp) {
+    app.command_palette.open = true;
+    app.command_palette.query.clear();
+    app.command_palette.selected = 0;
+}
+
+/// Returns the commands matching `query`, best first, with the matched
+/// characters of their labels.
+fn matches(query: &str) -> Vec<(Command, Vec<usize>)> {
+    let mut scored: Vec<(i64, usize, Command, Vec<usize>)> = Command::ALL
+        .iter()
+        .enumerate()
+        .filter_map(|(order, &command)| {
+            let FuzzyMatch { score, indices } = fuzzy_match(query, command.label())?;
+            Some((score, order, command, indices))
+        })
+        .collect();
+    scored.sort_by(|(a, a_order, ..), (b, b_order, ..)| b.cmp(a).then(a_order.cmp(b_order)));
+    scored.into_iter().map(|(_, _, command, indices)| (command, indices)).collect()
+}
+
+/// Renders the command palette if it is open.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.command_palette.open {
+        return;
+    }
+
+    let results = matches(&app.command_palette.query);
+
+    // Handle navigation keys before the text field sees them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::ArrowUp),
+            i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    let palette = &mut app.command_palette;
+    palette.selected = palette.selected.min(results.len().saturating_sub(1));
+    if up && palette.selected > 0 {
+        palette.selected -= 1;
+    }
+    if down && palette.selected + 1 < results.len() {
+        palette.selected += 1;
+    }
+    if escape {
+        palette.open = false;
+        return;
+    }
+    if enter {
+        palette.open = false;
+        if let Some(&(command, _)) = results.get(palette.selected) {
+            commands::execute(app, ctx, command);
+        }
+        return;
+    }
+
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (text_color, match_color) = if is_dark {
+        (Color32::from_rgb(200, 200, 215), Color32::from_rgb(86, 156, 255))
+    } else {
+        (Color32::from_rgb(50, 50, 60), Color32::from_rgb(0, 120, 215))
+    };
+
+    let mut clicked = None;
+    let palette = &mut app.command_palette;
+    egui::Area::new("command_palette")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(420.0);
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command name")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    palette.selected = 0;
+                }
+
+                ui.separator();
+
+                if results.is_empty() {
+                    ui.weak("No matching commands");
+                }
+
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (index, (command, indices)) in results.iter().enumerate() {
+                        let is_selected = index == palette.selected;
+                        let label = match_label(command.label(), indices, text_color, match_color);
+                        let response = ui.selectable_label(is_selected, label);
+                        if is_selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            clicked = Some(*command);
+                        }
+                    }
+                });
+            });
+        });
+
+    if let Some(command) = clicked {
+        app.command_palette.open = false;
+        commands::execute(app, ctx, command);
+    }
+}
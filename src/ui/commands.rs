@@ -0,0 +1,390 @@
+//! Editing commands for Zing text editor.
+//!
+//! Commands act on the selection of the active buffer and are shared by the
+//! menus, the toolbar and the keyboard shortcuts.
+
+use egui::Context;
+use std::ops::Range;
+
+use crate::buffer::lines::{self, SortOptions};
+use crate::buffer::transform::Transform;
+use crate::clipboard;
+use crate::ui::{brackets, clipboard_history, comments, editor, indentation, line_editing, transforms, ZingApp};
+
+/// A command that can be run against the active buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Cut the selection, or the current line when nothing is selected
+    Cut,
+    /// Copy the selection, or the current line when nothing is selected
+    Copy,
+    /// Paste the system clipboard over the selection
+    Paste,
+    /// Paste the system clipboard, adjusting its indentation to the current line
+    PasteAndIndent,
+    /// Pick an earlier copy to paste from the clipboard history
+    PasteFromHistory,
+    /// Copy the selection as syntax highlighted HTML
+    CopyAsHtml,
+    /// Indent the selected lines by one level
+    Indent,
+    /// Remove one level of indentation from the selected lines
+    Outdent,
+    /// Rewrite the indentation of the whole file with spaces
+    ConvertIndentationToSpaces,
+    /// Rewrite the indentation of the whole file with tabs
+    ConvertIndentationToTabs,
+    /// Comment out the selected lines, or uncomment them
+    ToggleLineComment,
+    /// Wrap the selection in a block comment, or remove the one around it
+    ToggleBlockComment,
+    /// Move the selected lines up past the line above
+    MoveLinesUp,
+    /// Move the selected lines down past the line below
+    MoveLinesDown,
+    /// Duplicate the selection, or the current line when nothing is selected
+    Duplicate,
+    /// Delete the selected lines
+    DeleteLines,
+    /// Join the selected lines, or the current line with the next
+    JoinLines,
+    /// Start a new line above the current one
+    InsertLineAbove,
+    /// Start a new line below the current one
+    InsertLineBelow,
+    /// Sort the lines in ascending order
+    SortLinesAscending,
+    /// Sort the lines in descending order
+    SortLinesDescending,
+    /// Sort the lines ignoring case
+    SortLinesCaseInsensitive,
+    /// Sort the lines by the numbers they start with
+    SortLinesNumerically,
+    /// Sort the lines and drop repeated ones
+    SortLinesUnique,
+    /// Reverse the order of the lines
+    ReverseLines,
+    /// Remove lines that repeat an earlier one
+    RemoveDuplicateLines,
+    /// Remove blank lines
+    RemoveEmptyLines,
+    /// Rewrite the selection, or the word at the cursor
+    Transform(Transform),
+    /// Copy the SHA-256 hash of the selection
+    CopySha256,
+    /// Move the cursor to the bracket matching the one next to it
+    JumpToBracket,
+    /// Select the brackets next to or around the cursor and their contents
+    SelectToBracket,
+    /// Show or hide the lines along indentation levels
+    ToggleIndentGuides,
+    /// Show or hide the rulers
+    ToggleRulers,
+}
+
+impl Command {
+    /// Commands that rewrite the selected lines, or every line, as a whole.
+    pub const LINE_TRANSFORMS: [Command; 8] = [
+        Command::SortLinesAscending,
+        Command::SortLinesDescending,
+        Command::SortLinesCaseInsensitive,
+        Command::SortLinesNumerically,
+        Command::SortLinesUnique,
+        Command::ReverseLines,
+        Command::RemoveDuplicateLines,
+        Command::RemoveEmptyLines,
+    ];
+
+    /// Every command, in the order the command palette lists them.
+    pub const ALL: [Command; 46] = [
+        Command::Cut,
+        Command::Copy,
+        Command::Paste,
+        Command::PasteAndIndent,
+        Command::PasteFromHistory,
+        Command::CopyAsHtml,
+        Command::Indent,
+        Command::Outdent,
+        Command::ConvertIndentationToSpaces,
+        Command::ConvertIndentationToTabs,
+        Command::ToggleLineComment,
+        Command::ToggleBlockComment,
+        Command::MoveLinesUp,
+        Command::MoveLinesDown,
+        Command::Duplicate,
+        Command::DeleteLines,
+        Command::JoinLines,
+        Command::InsertLineAbove,
+        Command::InsertLineBelow,
+        Command::SortLinesAscending,
+        Command::SortLinesDescending,
+        Command::SortLinesCaseInsensitive,
+        Command::SortLinesNumerically,
+        Command::SortLinesUnique,
+        Command::ReverseLines,
+        Command::RemoveDuplicateLines,
+        Command::RemoveEmptyLines,
+        Command::Transform(Transform::Upper),
+        Command::Transform(Transform::Lower),
+        Command::Transform(Transform::Title),
+        Command::Transform(Transform::Camel),
+        Command::Transform(Transform::Snake),
+        Command::Transform(Transform::Kebab),
+        Command::Transform(Transform::Pascal),
+        Command::Transform(Transform::SwapCase),
+        Command::Transform(Transform::Base64Encode),
+        Command::Transform(Transform::Base64Decode),
+        Command::Transform(Transform::UrlEncode),
+        Command::Transform(Transform::UrlDecode),
+        Command::Transform(Transform::JsonEscape),
+        Command::Transform(Transform::JsonUnescape),
+        Command::CopySha256,
+        Command::JumpToBracket,
+        Command::SelectToBracket,
+        Command::ToggleIndentGuides,
+        Command::ToggleRulers,
+    ];
+
+    /// Returns the name of the command as shown in menus.
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::Cut => "Cut",
+            Command::Copy => "Copy",
+            Command::Paste => "Paste",
+            Command::PasteAndIndent => "Paste and Indent",
+            Command::PasteFromHistory => "Paste from History...",
+            Command::CopyAsHtml => "Copy as HTML",
+            Command::Indent => "Indent",
+            Command::Outdent => "Outdent",
+            Command::ConvertIndentationToSpaces => "Convert Indentation to Spaces",
+            Command::ConvertIndentationToTabs => "Convert Indentation to Tabs",
+            Command::ToggleLineComment => "Toggle Line Comment",
+            Command::ToggleBlockComment => "Toggle Block Comment",
+            Command::MoveLinesUp => "Move Lines Up",
+            Command::MoveLinesDown => "Move Lines Down",
+            Command::Duplicate => "Duplicate Selection",
+            Command::DeleteLines => "Delete Lines",
+            Command::JoinLines => "Join Lines",
+            Command::InsertLineAbove => "Insert Line Above",
+            Command::InsertLineBelow => "Insert Line Below",
+            Command::SortLinesAscending => "Sort Lines Ascending",
+            Command::SortLinesDescending => "Sort Lines Descending",
+            Command::SortLinesCaseInsensitive => "Sort Lines Ignoring Case",
+            Command::SortLinesNumerically => "Sort Lines Numerically",
+            Command::SortLinesUnique => "Sort Lines and Remove Duplicates",
+            Command::ReverseLines => "Reverse Lines",
+            Command::RemoveDuplicateLines => "Remove Duplicate Lines",
+            Command::RemoveEmptyLines => "Remove Empty Lines",
+            Command::Transform(transform) => match transform {
+                Transform::Upper => "Transform to Uppercase",
+                Transform::Lower => "Transform to Lowercase",
+                Transform::Title => "Transform to Title Case",
+                Transform::Camel => "Transform to camelCase",
+                Transform::Snake => "Transform to snake_case",
+                Transform::Kebab => "Transform to kebab-case",
+                Transform::Pascal => "Transform to PascalCase",
+                Transform::SwapCase => "Swap Case",
+                Transform::Base64Encode => "Base64 Encode",
+                Transform::Base64Decode => "Base64 Decode",
+                Transform::UrlEncode => "URL Encode",
+                Transform::UrlDecode => "URL Decode",
+                Transform::JsonEscape => "Escape JSON String",
+                Transform::JsonUnescape => "Unescape JSON String",
+            },
+            Command::CopySha256 => "Copy SHA-256 Hash",
+            Command::JumpToBracket => "Go to Matching Bracket",
+            Command::SelectToBracket => "Select to Bracket",
+            Command::ToggleIndentGuides => "Toggle Indentation Guides",
+            Command::ToggleRulers => "Toggle Rulers",
+        }
+    }
+}
+
+/// Runs `command` against the active buffer.
+pub fn execute(app: &mut ZingApp, ctx: &Context, command: Command) {
+    match command {
+        Command::Cut => copy(app, ctx, true),
+        Command::Copy => copy(app, ctx, false),
+        Command::Paste | Command::PasteAndIndent => match clipboard::get_text() {
+            Ok(text) => {
+                let line_mode = app.clipboard.is_line_mode(&text);
+                paste_text(app, ctx, &text, line_mode, command == Command::PasteAndIndent);
+            }
+            Err(err) => {
+                log::error!("{:#}", err);
+                app.set_status(format!("Failed to paste: {:#}", err), 5.0);
+            }
+        },
+        Command::PasteFromHistory => clipboard_history::show(app),
+        Command::CopyAsHtml => copy_as_html(app),
+        Command::Indent => indentation::indent(app, ctx),
+        Command::Outdent => indentation::outdent(app, ctx),
+        Command::ConvertIndentationToSpaces => indentation::convert(app, ctx, true),
+        Command::ConvertIndentationToTabs => indentation::convert(app, ctx, false),
+        Command::ToggleLineComment => comments::toggle_line(app, ctx),
+        Command::ToggleBlockComment => comments::toggle_block(app, ctx),
+        Command::MoveLinesUp => line_editing::move_lines(app, ctx, true),
+        Command::MoveLinesDown => line_editing::move_lines(app, ctx, false),
+        Command::Duplicate => line_editing::duplicate(app, ctx),
+        Command::DeleteLines => line_editing::delete(app, ctx),
+        Command::JoinLines => line_editing::join(app, ctx),
+        Command::InsertLineAbove => line_editing::insert_line(app, ctx, false),
+        Command::InsertLineBelow => line_editing::insert_line(app, ctx, true),
+        Command::SortLinesAscending => sort_lines(app, ctx, SortOptions::default()),
+        Command::SortLinesDescending => sort_lines(app, ctx, SortOptions { descending: true, ..SortOptions::default() }),
+        Command::SortLinesCaseInsensitive => {
+            sort_lines(app, ctx, SortOptions { case_insensitive: true, ..SortOptions::default() })
+        }
+        Command::SortLinesNumerically => sort_lines(app, ctx, SortOptions { numeric: true, ..SortOptions::default() }),
+        Command::SortLinesUnique => sort_lines(app, ctx, SortOptions { unique: true, ..SortOptions::default() }),
+        Command::ReverseLines => line_editing::transform(app, ctx, |mut texts| {
+            texts.reverse();
+            texts
+        }),
+        Command::RemoveDuplicateLines => line_editing::transform(app, ctx, lines::remove_duplicates),
+        Command::RemoveEmptyLines => line_editing::transform(app, ctx, lines::remove_blank),
+        Command::Transform(transform) => transforms::apply(app, ctx, transform),
+        Command::CopySha256 => transforms::copy_sha256(app, ctx),
+        Command::JumpToBracket => brackets::jump_to_match(app, ctx),
+        Command::SelectToBracket => brackets::select_to_match(app, ctx),
+        Command::ToggleIndentGuides => app.config.toggle_indent_guides(),
+        Command::ToggleRulers => app.config.toggle_rulers(),
+    }
+}
+
+/// Sorts the selected lines, or every line, as `options` ask.
+fn sort_lines(app: &mut ZingApp, ctx: &Context, options: SortOptions) {
+    line_editing::transform(app, ctx, |texts| lines::sort(texts, options));
+}
+
+/// Returns the range a copy or cut acts on and whether it covers whole lines.
+///
+/// With an empty selection this is the line containing the cursor.
+fn copy_range(app: &ZingApp) -> (Range<usize>, bool) {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let len = buffer_lock.len_chars();
+    let selection = app.selection.start.min(len)..app.selection.end.min(len);
+
+    if !selection.is_empty() {
+        return (selection, false);
+    }
+
+    let line = buffer_lock.content.char_to_line(selection.start);
+    let start = buffer_lock.content.line_to_char(line);
+    let end = if line + 1 < buffer_lock.len_lines() {
+        buffer_lock.content.line_to_char(line + 1)
+    } else {
+        len
+    };
+    (start..end, true)
+}
+
+/// Copies the selection or current line, removing it from the buffer if `cut` is set.
+fn copy(app: &mut ZingApp, ctx: &Context, cut: bool) {
+    let (range, line_mode) = copy_range(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+
+    let mut text = match buffer_lock.slice(range.start, range.end) {
+        Ok(text) => text,
+        Err(err) => {
+            log::error!("Failed to copy: {}", err);
+            return;
+        }
+    };
+    if text.is_empty() {
+        return;
+    }
+    // A copied last line still pastes as a whole line
+    if line_mode && !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    if cut {
+        if let Err(err) = buffer_lock.remove(range.start, range.end) {
+            log::error!("Failed to cut: {}", err);
+            return;
+        }
+    }
+    drop(buffer_lock);
+
+    ctx.copy_text(text.clone());
+    app.clipboard.push(text, line_mode);
+
+    if cut {
+        editor::set_selection(app, ctx, range.start..range.start);
+    }
+}
+
+/// Inserts `text` over the selection of the active buffer.
+///
+/// Whole lines copied without a selection are inserted above the current line.
+/// With `indent` set, the pasted lines are re-indented to match the current line.
+pub fn paste_text(app: &mut ZingApp, ctx: &Context, text: &str, line_mode: bool, indent: bool) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let len = buffer_lock.len_chars();
+    let selection = app.selection.start.min(len)..app.selection.end.min(len);
+    let line_mode = line_mode && selection.is_empty();
+
+    let line = buffer_lock.content.char_to_line(selection.start);
+    let line_start = buffer_lock.content.line_to_char(line);
+    // Line pastes go in above the current line and others replace the selection
+    let range = if line_mode { line_start..line_start } else { selection.clone() };
+
+    let text = if indent {
+        let line_text = buffer_lock.content.line(line).to_string();
+        let current_indent = &line_text[..line_text.len() - line_text.trim_start_matches([' ', '\t']).len()];
+        if line_mode {
+            // Every pasted line starts a line of its own, so none of them is special
+            let reindented = clipboard::reindent(&format!("\n{}", text), current_indent);
+            reindented[1..].to_string()
+        } else {
+            clipboard::reindent(text, current_indent)
+        }
+    } else {
+        text.to_string()
+    };
+
+    let result = buffer_lock.replace(range.clone(), &text);
+    drop(buffer_lock);
+
+    if let Err(err) = result {
+        log::error!("Failed to paste: {}", err);
+        app.set_status(format!("Failed to paste: {}", err), 5.0);
+        return;
+    }
+
+    // The cursor stays put for line pastes and ends up after the text otherwise
+    let inserted = text.chars().count();
+    let cursor = if line_mode { selection.start + inserted } else { range.start + inserted };
+    editor::set_selection(app, ctx, cursor..cursor);
+}
+
+/// Copies the selection or current line as HTML highlighted like the active file.
+fn copy_as_html(app: &mut ZingApp) {
+    let (range, _) = copy_range(app);
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let text = buffer_lock.slice(range.start, range.end).unwrap_or_default();
+    let syntax = buffer_lock.language.syntax;
+    drop(buffer_lock);
+
+    if text.is_empty() {
+        return;
+    }
+
+    let result = clipboard::to_highlighted_html(&text, syntax, app.config.theme)
+        .and_then(|html| clipboard::set_html(&html, &text));
+    match result {
+        Ok(()) => {
+            app.clipboard.push(text, false);
+            app.set_status("Copied as HTML".to_string(), 2.0);
+        }
+        Err(err) => {
+            log::error!("Failed to copy as HTML: {:#}", err);
+            app.set_status(format!("Failed to copy as HTML: {:#}", err), 5.0);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Comment toggling for Zing text editor.
+//!
+//! Comments are made with the tokens of the active file's language, as its
+//! language settings change them.
+
+use egui::Context;
+
+use crate::buffer::indent;
+use crate::ui::{editor, indentation, ZingApp};
+
+/// Comments out the selected lines, or uncomments them when they all are.
+///
+/// Languages without line comments get a block comment around the lines.
+pub fn toggle_line(app: &mut ZingApp, ctx: &Context) {
+    let language = app.buffer().lock().unwrap().language;
+    let tokens = app.config.comments_for(language);
+    let Some(token) = tokens.line else {
+        if tokens.block.is_some() {
+            toggle_block(app, ctx);
+        } else {
+            report_missing(app, "line");
+        }
+        return;
+    };
+
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = indentation::selection(app, &buffer_lock);
+    let lines = indentation::selected_lines(&buffer_lock, &selection);
+    let line_start = buffer_lock.content.line_to_char(lines.start);
+    let indent = indent::leading_whitespace(&buffer_lock.content.line(lines.start).to_string()).chars().count();
+    let len = buffer_lock.len_chars();
+
+    let result = buffer_lock.toggle_line_comments(lines.clone(), &token);
+    let range = if lines.len() > 1 {
+        buffer_lock.lines_range(&lines)
+    } else {
+        // The cursor stays on the same text, moving with the marker before it
+        let growth = buffer_lock.len_chars() as isize - len as isize;
+        let marker = line_start + indent;
+        let shift = |pos: usize| if pos > marker { (pos as isize + growth).max(marker as isize) as usize } else { pos };
+        shift(selection.start)..shift(selection.end)
+    };
+    drop(buffer_lock);
+    report(app, result);
+    editor::set_selection(app, ctx, range);
+}
+
+/// Wraps the selection, or the current line, in a block comment, or removes
+/// the block comment around it.
+pub fn toggle_block(app: &mut ZingApp, ctx: &Context) {
+    let language = app.buffer().lock().unwrap().language;
+    let Some((open, close)) = app.config.comments_for(language).block else {
+        report_missing(app, "block");
+        return;
+    };
+
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = indentation::selection(app, &buffer_lock);
+    let result = buffer_lock.toggle_block_comment(selection, &open, &close);
+    drop(buffer_lock);
+    match result {
+        Ok(range) => editor::set_selection(app, ctx, range),
+        Err(err) => report(app, Err(err)),
+    }
+}
+
+/// Tells the user the active file's language has no comments of `kind`.
+fn report_missing(app: &mut ZingApp, kind: &str) {
+    let language = app.buffer().lock().unwrap().language;
+    app.set_status(format!("{} has no {} comments", language.name, kind), 3.0);
+}
+
+/// Shows a failed edit in the status bar.
+fn report(app: &mut ZingApp, result: anyhow::Result<()>) {
+    if let Err(err) = result {
+        log::error!("Failed to toggle comment: {}", err);
+        app.set_status(format!("Failed to toggle comment: {}", err), 5.0);
+    }
+}
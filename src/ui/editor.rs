@@ -4,10 +4,16 @@ use egui::{Color32, FontId, TextEdit, Ui, Vec2, Rounding, Stroke, TextStyle};
 use egui::text::{LayoutJob, TextFormat};
 use std::sync::mpsc::{self, Sender, Receiver};
 use std::sync::{Arc, Mutex, Once};
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
+use crate::buffer::transform::Transform;
+use crate::buffer::ChangedOnDisk;
 use crate::config::Theme;
-use crate::ui::ZingApp;
+use crate::ui::commands::{self, Command};
+use crate::ui::gutter::GutterClick;
+use crate::ui::panes::PaneId;
+use crate::ui::{autosave, bookmarks, brackets, folding, guides, gutter, indentation, navigation, panes, ZingApp};
 
 // Global channel for file operations
 static INIT: Once = Once::new();
@@ -16,8 +22,12 @@ static mut FILE_OP_RECEIVER: Option<Receiver<FileOperation>> = None;
 
 // File operation types
 enum FileOperation {
-    OpenComplete(Option<crate::buffer::TextBuffer>),
+    OpenComplete(Option<Box<crate::buffer::TextBuffer>>),
     SaveComplete(Option<PathBuf>, bool),
+    FolderOpened(PathBuf),
+    /// A buffer saved with [`save_buffer`], whether automatically, and how it went
+    Saved(Arc<Mutex<crate::buffer::TextBuffer>>, bool, anyhow::Result<Option<String>>),
+    Warning(String),
     ResetDialogFlag,
 }
 
@@ -25,9 +35,13 @@ enum FileOperation {
 #[derive(Debug)]
 pub struct EditorView;
 
-/// Renders the editor UI.
-pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
-    // Initialize the channel if not already done
+/// Id of the text edit widget of `pane`, used to read and write its cursor state.
+pub fn editor_id(pane: PaneId) -> egui::Id {
+    egui::Id::new("zing_editor").with(pane)
+}
+
+/// Initializes the channel for file operations if not already done.
+fn init_file_ops() {
     INIT.call_once(|| {
         let (sender, receiver) = mpsc::channel();
         unsafe {
@@ -35,23 +49,30 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
             FILE_OP_RECEIVER = Some(receiver);
         }
     });
+}
+
+/// Returns a sender for reporting file operations from a worker thread.
+fn file_op_sender() -> Sender<FileOperation> {
+    init_file_ops();
+    unsafe { FILE_OP_SENDER.clone().unwrap() }
+}
+
+/// Renders the editor UI.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    init_file_ops();
     
-    // Check for file operation results
+    // Check for file operation results, which belong to the focused pane
+    let focused = app.panes.is_current_focused();
     unsafe {
-        if let Some(receiver) = &FILE_OP_RECEIVER {
+        if let (true, Some(receiver)) = (focused, &FILE_OP_RECEIVER) {
             while let Ok(op) = receiver.try_recv() {
                 match op {
                     FileOperation::OpenComplete(Some(buffer)) => {
-                        app.set_buffer(buffer);
+                        app.set_buffer(*buffer);
                         app.set_status("File opened successfully".to_string(), 3.0);
                     },
                     FileOperation::SaveComplete(Some(path), _) => {
-                        // Update the tab title and file path in the main app
-                        if let Some(tab) = app.tabs.tabs.get_mut(app.tabs.active_tab) {
-                            tab.title = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                            tab.file_path = Some(path.clone());
-                            tab.is_modified = false;
-                        }
+                        app.note_recent_file(path.clone());
                         app.set_status(format!("File saved: {}", path.display()), 3.0);
                     },
                     FileOperation::SaveComplete(None, true) => {
@@ -60,6 +81,16 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
                     FileOperation::SaveComplete(None, false) => {
                         app.set_status("Failed to save file".to_string(), 5.0);
                     },
+                    FileOperation::FolderOpened(path) => {
+                        app.open_workspace(path.clone());
+                        app.set_status(format!("Opened folder: {}", path.display()), 3.0);
+                    },
+                    FileOperation::Saved(buffer, automatic, result) => {
+                        show_saved(app, buffer, automatic, result);
+                    },
+                    FileOperation::Warning(message) => {
+                        app.set_status(message, 5.0);
+                    },
                     FileOperation::ResetDialogFlag => {
                         app.file_dialog_open = false;
                     },
@@ -69,14 +100,81 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
         }
     }
 
+    let editor_id = editor_id(app.panes.current());
+    
+    // Take clipboard and indentation keys, and brackets and quotes, away from
+    // the text edit so they go through the commands
+    if ui.memory(|mem| mem.has_focus(editor_id)) {
+        let ctx = ui.ctx().clone();
+        let backspace_indents = indentation::backspace_range(app).is_some();
+        let pairs = brackets::auto_close_pairs(app);
+        let (events, modifiers) = ctx.input_mut(|i| {
+            let mut taken = Vec::new();
+            i.events.retain(|event| match event {
+                egui::Event::Copy | egui::Event::Cut | egui::Event::Paste(_) => {
+                    taken.push(event.clone());
+                    false
+                }
+                egui::Event::Key { key, pressed: true, modifiers, .. }
+                    if indentation::takes_key(*key, *modifiers, backspace_indents) =>
+                {
+                    taken.push(event.clone());
+                    false
+                }
+                egui::Event::Text(text) if brackets::takes_text(text, pairs) => {
+                    taken.push(event.clone());
+                    false
+                }
+                _ => true,
+            });
+            (taken, i.modifiers)
+        });
+        
+        for event in events {
+            match event {
+                egui::Event::Copy => commands::execute(app, &ctx, Command::Copy),
+                egui::Event::Cut => commands::execute(app, &ctx, Command::Cut),
+                // Shift+Paste opens the clipboard history instead
+                egui::Event::Paste(_) if modifiers.shift => {}
+                egui::Event::Paste(text) => {
+                    let line_mode = app.clipboard.is_line_mode(&text);
+                    commands::paste_text(app, &ctx, &text, line_mode, modifiers.alt);
+                }
+                egui::Event::Key { key, modifiers, .. } => indentation::press_key(app, &ctx, key, modifiers),
+                egui::Event::Text(text) => text.chars().for_each(|c| brackets::type_char(app, &ctx, c)),
+                _ => {}
+            }
+        }
+    }
+    
+    // Indentation of the file, for the indentation guides
+    let indent_style = indentation::style(app);
+    
     let buffer = app.buffer();
     let mut buffer_lock = buffer.lock().unwrap();
     
-    // Get the content as a string
-    let mut content_str = buffer_lock.content.to_string();
+    // Jumping into a folded region unfolds it
+    let jump_to = app.pending_jump.take().map(|pos| pos.min(buffer_lock.len_chars()));
+    let fold_regions = buffer_lock.fold_regions();
+    if let Some(pos) = jump_to {
+        while let Some(line) = buffer_lock.fold_map(&fold_regions).hiding(pos) {
+            buffer_lock.unfold(line);
+        }
+    }
+    let fold_map = buffer_lock.fold_map(&fold_regions);
+    
+    // Get the content as a string, with folded regions collapsed
+    let display_text = fold_map.display_text(&buffer_lock.content);
+    let mut content_str = display_text.clone();
     
     // Store the font size for the layouter
     let font_size = app.config.font_size;
+    let language = buffer_lock.language.id;
+    let word_wrap = app.config.word_wrap_for(language);
+    
+    // Columns to draw guides at, from the file's language and its settings
+    let mut rulers = app.config.rulers_for(language).to_vec();
+    rulers.extend(buffer_lock.format.max_line_length);
     
     // Get available size
     let available_size = ui.available_size();
@@ -135,8 +233,28 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
     ui.style_mut().spacing.item_spacing = Vec2::splat(0.0);
     ui.style_mut().spacing.window_margin = egui::Margin::same(0.0);
     
+    // Move the cursor if something asked us to jump to a position
+    if let Some(pos) = jump_to {
+        let mut state = TextEdit::load_state(ui.ctx(), editor_id).unwrap_or_default();
+        let ccursor = egui::text::CCursor::new(fold_map.to_display(pos));
+        state.set_ccursor_range(Some(egui::text::CCursorRange::one(ccursor)));
+        TextEdit::store_state(ui.ctx(), editor_id, state);
+        ui.memory_mut(|mem| mem.request_focus(editor_id));
+        app.cursor_pos = pos;
+    }
+    
+    // Command picked from the context menu, run once the buffer is unlocked
+    let mut context_command = None;
+    
+    // Gutter to the left of the text, with clicks toggling bookmarks and folds
+    let bookmarked: Vec<usize> = buffer_lock.bookmarks().into_iter().map(|(line, _)| line).collect();
+    let gutter_width = gutter::width(app, ui, buffer_lock.len_lines());
+    let mut gutter_click = None;
+    // Whether an edit unfolded a region instead of changing the text
+    let mut unfolded = false;
+    
     // Create a scrollable area for the editor content
-    egui::ScrollArea::vertical()
+    egui::ScrollArea::new([!word_wrap, true])
         .auto_shrink([false, false])
         .scroll_bar_visibility(egui::scroll_area::ScrollBarVisibility::AlwaysVisible)
         .show(ui, |ui| {
@@ -146,22 +264,153 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
             // Add padding at the top to ensure first line is visible
             ui.add_space(20.0);
             
+            // Lay out long lines to the window width, or let them run on
+            // past it when word wrap is off
+            let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+                let wrap_width = if word_wrap { wrap_width } else { f32::INFINITY };
+                let job = LayoutJob::simple(text.to_owned(), FontId::monospace(font_size), text_color, wrap_width);
+                ui.fonts(|fonts| fonts.layout_job(job))
+            };
+            
             // Create a text edit widget with explicit styling
             let text_edit = TextEdit::multiline(&mut content_str)
+                .layouter(&mut layouter)
+                .id(editor_id)
                 .font(FontId::monospace(font_size))
                 .desired_width(f32::INFINITY)
                 .desired_rows(50)  // Set a large number of visible rows to encourage scrolling
                 .interactive(true) // Ensure it's interactive
+                .lock_focus(true) // Keep focus on Tab, which indents instead
                 .text_color(text_color)
                 .frame(false); // Remove frame to maximize space
 
             // Show the text edit widget
-            let output = text_edit.show(ui);
-            let response = output.response;
+            let output = ui.horizontal_top(|ui| {
+                ui.add_space(gutter_width);
+                text_edit.show(ui)
+            }).inner;
+            let response = output.response.clone();
 
             // Update cursor position using TextEdit's output
             if let Some(cursor_range) = output.cursor_range {
-                app.cursor_pos = cursor_range.primary.ccursor.index;
+                app.cursor_pos = fold_map.to_buffer(cursor_range.primary.ccursor.index);
+                let [start, end] = cursor_range.sorted_cursors();
+                app.selection = fold_map.to_buffer(start.ccursor.index)..fold_map.to_buffer(end.ccursor.index);
+            }
+            
+            gutter_click = gutter::ui(app, ui, &output, gutter_width, &bookmarked, &fold_regions, &fold_map);
+            
+            // Draw guides at the columns the file's settings ask for, along the
+            // indentation and over the whitespace
+            let font_id = FontId::monospace(font_size);
+            let char_width = ui.fonts(|fonts| fonts.glyph_width(&font_id, ' '));
+            guides::paint_rulers(ui, &output, &rulers, char_width, text_color.linear_multiply(0.15));
+            if app.config.show_indent_guides {
+                // Tabs are laid out as wide as a fixed number of spaces
+                let step = if indent_style.use_spaces {
+                    char_width * indent_style.tab_size as f32
+                } else {
+                    ui.fonts(|fonts| fonts.glyph_width(&font_id, '\t'))
+                };
+                let cursor = fold_map.to_display(app.cursor_pos);
+                let (guide, active) = (text_color.linear_multiply(0.1), text_color.linear_multiply(0.35));
+                guides::paint_indent_guides(ui, &output, step, cursor, guide, active);
+            }
+            let selection = fold_map.to_display(app.selection.start)..fold_map.to_display(app.selection.end);
+            let whitespace_color = text_color.linear_multiply(0.3);
+            guides::paint_whitespace(ui, &output, app.config.show_whitespace, selection, font_id, whitespace_color);
+            
+            // Remember which lines are on screen, for the minimap
+            let clip = ui.clip_rect();
+            let row_lines = gutter::row_lines(&output.galley, &fold_map);
+            let mut visible = output.galley.rows.iter().zip(&row_lines).filter(|(row, _)| {
+                let top = output.text_draw_pos.y + row.rect.min.y;
+                let bottom = output.text_draw_pos.y + row.rect.max.y;
+                bottom >= clip.min.y && top <= clip.max.y
+            });
+            if let Some((_, &first)) = visible.next() {
+                let last = visible.next_back().map_or(first, |(_, &line)| line);
+                app.visible_lines = first..last + 1;
+            }
+            
+            // Scroll a line to the top of the view, as asked by the minimap
+            if let Some(line) = app.scroll_to_line.take() {
+                let line = line.min(buffer_lock.len_lines().saturating_sub(1));
+                let pos = fold_map.to_display(buffer_lock.content.line_to_char(line));
+                let cursor = output.galley.from_ccursor(egui::text::CCursor::new(pos));
+                let line_rect = output.galley.pos_from_cursor(&cursor)
+                    .translate(output.text_draw_pos.to_vec2());
+                ui.scroll_to_rect(line_rect, Some(egui::Align::TOP));
+            }
+            
+            // Clipboard and indentation commands on right click
+            let response = response.context_menu(|ui| {
+                for command in [
+                    Command::Cut,
+                    Command::Copy,
+                    Command::Paste,
+                    Command::PasteAndIndent,
+                    Command::PasteFromHistory,
+                    Command::CopyAsHtml,
+                ] {
+                    if ui.button(command.label()).clicked() {
+                        context_command = Some(command);
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                for command in [
+                    Command::Indent,
+                    Command::Outdent,
+                    Command::ConvertIndentationToSpaces,
+                    Command::ConvertIndentationToTabs,
+                ] {
+                    if ui.button(command.label()).clicked() {
+                        context_command = Some(command);
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                for command in [Command::ToggleLineComment, Command::ToggleBlockComment] {
+                    if ui.button(command.label()).clicked() {
+                        context_command = Some(command);
+                        ui.close_menu();
+                    }
+                }
+                ui.menu_button("Lines", |ui| {
+                    let line_commands = [
+                        Command::MoveLinesUp,
+                        Command::MoveLinesDown,
+                        Command::Duplicate,
+                        Command::DeleteLines,
+                        Command::JoinLines,
+                        Command::InsertLineAbove,
+                        Command::InsertLineBelow,
+                    ];
+                    for command in line_commands.into_iter().chain(Command::LINE_TRANSFORMS) {
+                        if ui.button(command.label()).clicked() {
+                            context_command = Some(command);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button("Transform", |ui| {
+                    let transforms = Transform::ALL.into_iter().map(Command::Transform);
+                    for command in transforms.chain([Command::CopySha256]) {
+                        if ui.button(command.label()).clicked() {
+                            context_command = Some(command);
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+            
+            // Bring the jump target into the middle of the view
+            if let Some(pos) = jump_to {
+                let cursor = output.galley.from_ccursor(egui::text::CCursor::new(fold_map.to_display(pos)));
+                let cursor_rect = output.galley.pos_from_cursor(&cursor)
+                    .translate(output.text_draw_pos.to_vec2());
+                ui.scroll_to_rect(cursor_rect, Some(egui::Align::Center));
             }
             
             // Add some space at the bottom
@@ -169,14 +418,27 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
             
             // Handle text changes and cursor position
             if response.changed() {
-                // If the text changed, update the buffer content
-                buffer_lock.update_content(&content_str).unwrap();
-                
-                // Mark the current tab as modified
-                if let Some(tab) = app.tabs.tabs.get_mut(app.tabs.active_tab) {
-                    tab.is_modified = true;
+                // If the text changed, apply the edit to the buffer content
+                if let Some((range, text)) = crate::buffer::diff(&display_text, &content_str) {
+                    match fold_map.to_buffer_range(range) {
+                        Ok(range) => {
+                            // The text is rebuilt from the buffer next frame, so
+                            // an edit that fails to apply is simply dropped
+                            if let Err(err) = buffer_lock.replace(range, &text) {
+                                log::error!("Failed to apply edit: {}", err);
+                                app.set_status(format!("Failed to apply edit: {}", err), 5.0);
+                                ui.ctx().request_repaint();
+                            }
+                        }
+                        // Edits cutting into a placeholder unfold its region instead
+                        Err(line) => unfolded = buffer_lock.unfold(line),
+                    }
                 }
             }
+            
+            // Outline the bracket at the cursor and the one matching it
+            let bracket_color = text_color.linear_multiply(0.5);
+            brackets::paint_match(ui, &output, &buffer_lock, &fold_map, app.cursor_pos, char_width, bracket_color);
         });
 
     // Get cursor position for status bar
@@ -195,7 +457,23 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
     // Release the lock before calling functions that might need it
     drop(buffer_lock);
     
+    match gutter_click {
+        Some(GutterClick::Bookmark(line)) => bookmarks::toggle_line(app, line),
+        Some(GutterClick::Fold(line)) => folding::toggle_line(app, &ui.ctx().clone(), line),
+        None => {}
+    }
+    if unfolded {
+        refresh_selection(app, &ui.ctx().clone());
+    }
+    
+    if let Some(command) = context_command {
+        commands::execute(app, &ui.ctx().clone(), command);
+    }
+    
     // Handle keyboard shortcuts
+    if !focused {
+        return;
+    }
     if ui.input(|i| i.key_pressed(egui::Key::S) && i.modifiers.ctrl) {
         // Ctrl+S: Save
         save_file(app, false);
@@ -205,9 +483,6 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
     } else if ui.input(|i| i.key_pressed(egui::Key::O) && i.modifiers.ctrl) {
         // Ctrl+O: Open
         open_file(app);
-    } else if ui.input(|i| i.key_pressed(egui::Key::P) && i.modifiers.ctrl) {
-        // Ctrl+P: Print
-        print_file(app);
     } else if ui.input(|i| i.key_pressed(egui::Key::Z) && i.modifiers.ctrl && !i.modifiers.shift) {
         // Ctrl+Z: Undo
         undo(app);
@@ -225,12 +500,10 @@ pub fn open_file(app: &mut ZingApp) {
     }
     
     app.file_dialog_open = true;
-    let tabs = Arc::new(Mutex::new(app.tabs.clone()));
     
     // Use a background thread for file dialog to avoid blocking the UI
     std::thread::spawn({
         let sender = unsafe { FILE_OP_SENDER.clone().unwrap() };
-        let tabs = Arc::clone(&tabs);
         
         move || {
             if let Some(path) = crate::file_io::open_file_dialog() {
@@ -242,15 +515,8 @@ pub fn open_file(app: &mut ZingApp) {
                 runtime.block_on(async {
                     match crate::file_io::load_file(&path).await {
                         Ok(new_buffer) => {
-                            // Create a new tab for the opened file
-                            sender.send(FileOperation::OpenComplete(Some(new_buffer))).ok();
-                            // Update the tab information
-                            let mut tabs = tabs.lock().unwrap();
-                            tabs.tabs.push(crate::ui::tabs::Tab::new(
-                                path.file_name().unwrap_or_default().to_string_lossy().to_string(),
-                                Some(path)
-                            ));
-                            tabs.active_tab = tabs.tabs.len() - 1;
+                            // The tab is created, or focused if the file is open, on the UI thread
+                            sender.send(FileOperation::OpenComplete(Some(Box::new(new_buffer)))).ok();
                         }
                         Err(err) => {
                             log::error!("Failed to load file: {}", err);
@@ -266,6 +532,107 @@ pub fn open_file(app: &mut ZingApp) {
     });
 }
 
+/// Opens a folder dialog and makes the chosen folder the workspace root.
+pub fn open_folder(app: &mut ZingApp) {
+    if app.file_dialog_open {
+        return;
+    }
+    
+    app.file_dialog_open = true;
+    
+    // Use a background thread for the dialog to avoid blocking the UI
+    std::thread::spawn({
+        let sender = unsafe { FILE_OP_SENDER.clone().unwrap() };
+        
+        move || {
+            if let Some(path) = crate::file_io::open_folder_dialog() {
+                sender.send(FileOperation::FolderOpened(path)).ok();
+            }
+            
+            // Reset the file dialog flag
+            sender.send(FileOperation::ResetDialogFlag).ok();
+        }
+    });
+}
+
+/// Opens the file at `path` in a new tab, or shows its tab if it is already
+/// open, returning whether it was loaded.
+pub fn open_path(app: &mut ZingApp, path: &Path) -> bool {
+    if app.focus_open_file(path) {
+        app.note_recent_file(path.to_path_buf());
+        return true;
+    }
+    
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    
+    match runtime.block_on(crate::file_io::load_file(path)) {
+        Ok(buffer) => {
+            app.set_buffer(buffer);
+            app.set_status(format!("Opened {}", path.display()), 3.0);
+            true
+        }
+        Err(err) => {
+            log::error!("Failed to load file: {}", err);
+            app.set_status(format!("Failed to open file: {}", err), 5.0);
+            false
+        }
+    }
+}
+
+/// Moves the cursor to a 0-indexed line and column in the current buffer.
+///
+/// Out of range positions are clamped to the end of the line or buffer.
+pub fn go_to(app: &mut ZingApp, line: usize, column: usize) {
+    navigation::record_jump(app);
+    
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    
+    let line = line.min(buffer_lock.len_lines().saturating_sub(1));
+    let line_start = buffer_lock.content.line_to_char(line);
+    let line_text = buffer_lock.content.line(line).to_string();
+    let line_len = line_text.trim_end_matches(['\n', '\r']).chars().count();
+    
+    app.pending_jump = Some(line_start + column.min(line_len));
+}
+
+/// Selects `range` in the editor and gives it keyboard focus.
+pub fn set_selection(app: &mut ZingApp, ctx: &egui::Context, range: Range<usize>) {
+    store_selection(app, ctx, range);
+    ctx.memory_mut(|mem| mem.request_focus(editor_id(app.panes.current())));
+}
+
+/// Puts the editor's cursor back on the selection after folds changed, moving
+/// it out of any region folded over it.
+pub fn refresh_selection(app: &mut ZingApp, ctx: &egui::Context) {
+    store_selection(app, ctx, app.selection.clone());
+}
+
+/// Stores `range` of the active buffer as the selection of the text edit.
+fn store_selection(app: &mut ZingApp, ctx: &egui::Context, range: Range<usize>) {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let fold_map = buffer_lock.fold_map(&buffer_lock.fold_regions());
+    drop(buffer_lock);
+    
+    let display = fold_map.to_display(range.start)..fold_map.to_display(range.end);
+    let editor_id = editor_id(app.panes.current());
+    let mut state = TextEdit::load_state(ctx, editor_id).unwrap_or_default();
+    state.set_ccursor_range(Some(egui::text::CCursorRange::two(
+        egui::text::CCursor::new(display.start),
+        egui::text::CCursor::new(display.end),
+    )));
+    TextEdit::store_state(ctx, editor_id, state);
+    
+    // Hidden positions moved to the end of the folded line
+    let range = fold_map.to_buffer(display.start)..fold_map.to_buffer(display.end);
+    app.cursor_pos = range.end;
+    app.selection = range;
+}
+
 /// Saves the current buffer to a file.
 pub fn save_file(app: &mut ZingApp, save_as: bool) {
     if app.file_dialog_open {
@@ -286,14 +653,12 @@ pub fn save_file(app: &mut ZingApp, save_as: bool) {
     
     if need_path {
         app.file_dialog_open = true;
-        let tabs = Arc::new(Mutex::new(app.tabs.clone()));
-        let active_tab = app.tabs.active_tab;
         
         // Use a background thread for file dialog to avoid blocking the UI
         std::thread::spawn({
             let buffer = app.buffer();
-            let sender = unsafe { FILE_OP_SENDER.clone().unwrap() };
-            let tabs = Arc::clone(&tabs);
+            let config = app.config.clone();
+            let sender = file_op_sender();
             
             move || {
                 if let Some(path) = crate::file_io::save_file_dialog() {
@@ -302,19 +667,19 @@ pub fn save_file(app: &mut ZingApp, save_as: bool) {
                         .build()
                         .unwrap();
                     
+                    // The on-save actions follow the settings for the new path
+                    crate::file_io::set_path(&mut buffer.lock().unwrap(), path.clone());
+                    let problem = crate::file_io::prepare_save(&buffer, &config).err();
                     runtime.block_on(async {
                         let mut buffer_lock = buffer.lock().unwrap();
-                        match buffer_lock.save_to(&path).await {
+                        match crate::file_io::save_buffer_to_file(&mut buffer_lock, None).await {
                             Ok(_) => {
                                 log::info!("File saved successfully: {}", path.display());
-                                // Update the tab information
-                                let mut tabs = tabs.lock().unwrap();
-                                if let Some(tab) = tabs.tabs.get_mut(active_tab) {
-                                    tab.title = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                                    tab.file_path = Some(path.clone());
-                                    tab.is_modified = false;
+                                sender.send(FileOperation::SaveComplete(Some(path.clone()), true)).ok();
+                                if let Some(problem) = problem {
+                                    log::warn!("Saved {} unformatted: {}", path.display(), problem);
+                                    sender.send(FileOperation::Warning(format!("Saved file, but {}", problem))).ok();
                                 }
-                                sender.send(FileOperation::SaveComplete(Some(path), true)).ok();
                             }
                             Err(err) => {
                                 log::error!("Failed to save file: {}", err);
@@ -331,36 +696,106 @@ pub fn save_file(app: &mut ZingApp, save_as: bool) {
                 sender.send(FileOperation::ResetDialogFlag).ok();
             }
         });
-    } else if let Some(path) = current_path {
+    } else if current_path.is_some() {
         // Save to the existing file path
-        let runtime = tokio::runtime::Builder::new_current_thread()
+        save_buffer(app, app.buffer(), true, false);
+    }
+}
+
+/// Asks whether to overwrite a file another program changed since it was
+/// opened or saved, saving it if so.
+pub fn overwrite_confirmation(app: &mut ZingApp, ctx: &egui::Context) {
+    let buffer = match app.pending_overwrite.clone() {
+        Some(buffer) => buffer,
+        None => return,
+    };
+    let path = buffer.lock().unwrap().file_path.clone().unwrap_or_default();
+    
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new("File Changed on Disk")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+        .show(ctx, |ui| {
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+            ui.label(format!("'{}' was changed by another program since it was opened.", name));
+            ui.label("Overwrite it with the text in the editor?");
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Overwrite").clicked();
+                cancelled = ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape));
+            });
+        });
+    
+    if confirmed {
+        app.pending_overwrite = None;
+        buffer.lock().unwrap().ignore_disk_changes();
+        save_buffer(app, buffer, true, false);
+    } else if cancelled {
+        app.pending_overwrite = None;
+        app.set_status("Save cancelled".to_string(), 3.0);
+    }
+}
+
+/// Saves `buffer` to its file, as Ctrl+S does for a buffer that has one.
+///
+/// With `save_actions` the on-save actions run first. The save happens on a
+/// worker thread, so a slow formatter does not hold up the editor, and how it
+/// went shows up once it is done. A formatter that failed does not stop the
+/// save; its error is shown instead.
+pub fn save_buffer(app: &mut ZingApp, buffer: Arc<Mutex<crate::buffer::TextBuffer>>, save_actions: bool, automatic: bool) {
+    // A save that will be refused must not make the on-save changes first
+    let checked = buffer.lock().unwrap().check_disk();
+    if let Err(err) = checked {
+        show_saved(app, buffer, automatic, Err(err));
+        return;
+    }
+    
+    let config = save_actions.then(|| app.config.clone());
+    let sender = file_op_sender();
+    std::thread::spawn(move || {
+        let problem = config.and_then(|config| crate::file_io::prepare_save(&buffer, &config).err());
+        let result = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
-            .unwrap();
-        
-        let buffer = app.buffer();
-        let path_clone = path.clone();
-        let tabs = Arc::new(Mutex::new(app.tabs.clone()));
-        let active_tab = app.tabs.active_tab;
-        
-        runtime.block_on(async {
-            let mut buffer_lock = buffer.lock().unwrap();
-            match buffer_lock.save().await {
-                Ok(_) => {
-                    log::info!("File saved successfully: {}", path_clone.display());
-                    // Update the tab information
-                    let mut tabs = tabs.lock().unwrap();
-                    if let Some(tab) = tabs.tabs.get_mut(active_tab) {
-                        tab.is_modified = false;
-                    }
-                    app.set_status(format!("Saved file: {}", path_clone.display()), 3.0);
-                }
-                Err(err) => {
-                    log::error!("Failed to save file: {}", err);
-                    app.set_status(format!("Failed to save file: {}", err), 5.0);
-                }
-            }
-        });
+            .map_err(anyhow::Error::from)
+            .and_then(|runtime| runtime.block_on(buffer.lock().unwrap().save()))
+            .map(|()| problem.map(|problem| problem.to_string()));
+        sender.send(FileOperation::Saved(buffer, automatic, result)).ok();
+    });
+}
+
+/// Shows how saving `buffer` with [`save_buffer`] went, `result` holding what
+/// went wrong with the on-save actions of a file that was saved anyway.
+fn show_saved(
+    app: &mut ZingApp,
+    buffer: Arc<Mutex<crate::buffer::TextBuffer>>,
+    automatic: bool,
+    result: anyhow::Result<Option<String>>,
+) {
+    if automatic {
+        autosave::show_saved(app, result);
+        return;
+    }
+    let path = buffer.lock().unwrap().file_path.clone().unwrap_or_default();
+    match result {
+        Ok(None) => {
+            log::info!("File saved successfully: {}", path.display());
+            app.set_status(format!("Saved file: {}", path.display()), 3.0);
+        }
+        Ok(Some(problem)) => {
+            log::warn!("Saved {} unformatted: {}", path.display(), problem);
+            app.set_status(format!("Saved file, but {}", problem), 5.0);
+        }
+        Err(err) if err.is::<ChangedOnDisk>() => {
+            log::warn!("Not saved: {}", err);
+            app.pending_overwrite = Some(buffer);
+        }
+        Err(err) => {
+            log::error!("Failed to save file: {}", err);
+            app.set_status(format!("Failed to save file: {}", err), 5.0);
+        }
     }
 }
 
@@ -383,7 +818,7 @@ pub fn print_file(app: &mut ZingApp) {
 /// Creates a new empty tab.
 pub fn new_tab(app: &mut ZingApp) {
     // Create a new tab with an empty buffer
-    let tab = crate::ui::tabs::Tab::new("Untitled".to_string(), None);
+    let tab = crate::ui::tabs::Tab::new();
     app.tabs.tabs.push(tab);
     app.tabs.active_tab = app.tabs.tabs.len() - 1;
     app.cursor_pos = 0;
@@ -424,6 +859,12 @@ pub fn redo(app: &mut ZingApp) {
 
 /// Closes the current tab.
 pub fn close_tab(app: &mut ZingApp) {
+    // Closing the last tab of a split pane closes the pane
+    if app.tabs.tabs.len() <= 1 && panes::close(app) {
+        app.set_status("Pane closed".to_string(), 2.0);
+        return;
+    }
+    
     // Check if this is the last tab
     if app.tabs.tabs.len() <= 1 {
         // This is the last tab, warn the user
@@ -0,0 +1,55 @@
+//! File drop handling for Zing text editor.
+//!
+//! Opens files dropped onto the window as tabs in the pane they were dropped
+//! on, and folders as the workspace root. While files are dragged over the
+//! window the pane that will receive them is highlighted.
+
+use egui::{Align2, Color32, Context, FontId, Id, LayerId, Order, Rounding, Stroke};
+use std::path::PathBuf;
+
+use crate::config::Theme;
+use crate::ui::{editor, panes, ZingApp};
+
+/// Highlights the drop target while files are dragged over the window and
+/// opens them once dropped.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    let (hovering, dropped, pointer) = ctx.input(|i| {
+        let dropped: Vec<PathBuf> = i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect();
+        (!i.raw.hovered_files.is_empty(), dropped, i.pointer.hover_pos())
+    });
+
+    // Not every platform reports the pointer while dragging files, in which
+    // case the files go to the focused pane
+    let target = panes::pane_at(app, pointer);
+
+    if hovering {
+        if let Some((_, rect)) = target {
+            let accent = match app.config.theme {
+                Theme::Dark => Color32::from_rgb(86, 156, 255),
+                Theme::Light => Color32::from_rgb(0, 120, 215),
+            };
+            let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("file_drop")));
+            let rect = rect.shrink(4.0);
+            painter.rect(rect, Rounding::same(6.0), accent.linear_multiply(0.12), Stroke::new(2.0, accent));
+            painter.text(rect.center(), Align2::CENTER_CENTER, "Drop to open", FontId::proportional(18.0), accent);
+        }
+        ctx.request_repaint();
+    }
+
+    if dropped.is_empty() {
+        return;
+    }
+    if let Some((id, _)) = target {
+        panes::focus(app, id);
+    }
+
+    let (folders, files): (Vec<PathBuf>, Vec<PathBuf>) = dropped.into_iter().partition(|path| path.is_dir());
+    if let Some(folder) = folders.into_iter().next() {
+        app.open_workspace(folder.clone());
+        app.set_status(format!("Opened folder: {}", folder.display()), 3.0);
+    }
+    let opened = files.iter().filter(|path| editor::open_path(app, path)).count();
+    if opened > 1 {
+        app.set_status(format!("Opened {} files", opened), 3.0);
+    }
+}
@@ -0,0 +1,733 @@
+//! File tree sidebar for Zing text editor.
+//!
+//! Shows the workspace folder as a collapsible tree. Directories are read lazily
+//! the first time they are expanded, and files hidden by `.gitignore` are skipped.
+
+use egui::{Color32, Context, FontId, Id, Key, Modifiers, Pos2, Rect, Sense, Stroke, Ui, Vec2};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::Theme;
+use crate::ui::{editor, ZingApp};
+use crate::workspace::{self, ignore::IgnoreRules};
+
+/// Height of a single row in the tree.
+const ROW_HEIGHT: f32 = 20.0;
+/// Horizontal indentation per nesting level.
+const INDENT: f32 = 12.0;
+
+/// A file or folder shown in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TreeEntry {
+    /// Absolute path of the entry
+    path: PathBuf,
+    /// Whether the entry is a directory
+    is_dir: bool,
+}
+
+/// An inline edit in progress in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TreeEdit {
+    /// Naming a new file inside a folder
+    NewFile(PathBuf),
+    /// Naming a new folder inside a folder
+    NewFolder(PathBuf),
+    /// Renaming an existing entry
+    Rename(PathBuf),
+}
+
+/// Something the user did in the tree, applied after drawing.
+enum TreeAction {
+    Open(PathBuf),
+    Toggle(PathBuf),
+    Select(PathBuf),
+    StartEdit(TreeEdit),
+    CommitEdit,
+    CancelEdit,
+    RequestDelete(PathBuf),
+    Move(PathBuf, PathBuf),
+}
+
+/// State of the file tree sidebar.
+#[derive(Debug, Default)]
+pub struct FileTree {
+    /// Folders that are currently expanded
+    expanded: HashSet<PathBuf>,
+    /// Cached directory listings
+    children: HashMap<PathBuf, Vec<TreeEntry>>,
+    /// Ignore rules from the `.gitignore` files seen so far
+    rules: IgnoreRules,
+    /// Folders whose `.gitignore` has already been loaded
+    rules_loaded: HashSet<PathBuf>,
+    /// The highlighted entry
+    pub selected: Option<PathBuf>,
+    /// Inline edit in progress and the name typed so far
+    edit: Option<(TreeEdit, String)>,
+    /// Entry waiting for delete confirmation
+    pending_delete: Option<PathBuf>,
+    /// Entry being dragged
+    dragging: Option<PathBuf>,
+    /// Whether to scroll the selected entry into view on the next frame
+    scroll_to_selected: bool,
+}
+
+impl FileTree {
+    /// Creates an empty file tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets all cached listings and expansion state, e.g. when the root changes.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Drops the cached listing of `dir` so it is read again when next shown.
+    pub fn refresh(&mut self, dir: &Path) {
+        self.children.remove(dir);
+    }
+
+    /// Drops every cached listing and ignore rule.
+    pub fn refresh_all(&mut self) {
+        self.children.clear();
+        self.rules = IgnoreRules::new();
+        self.rules_loaded.clear();
+    }
+
+    /// Expands the folders leading to `path` and selects it.
+    pub fn reveal(&mut self, root: &Path, path: &Path) {
+        if !path.starts_with(root) {
+            return;
+        }
+        for ancestor in path.ancestors().skip(1) {
+            self.expanded.insert(ancestor.to_path_buf());
+            if ancestor == root {
+                break;
+            }
+        }
+        self.selected = Some(path.to_path_buf());
+        self.scroll_to_selected = true;
+    }
+
+    /// Returns the children of `dir`, reading the directory if needed.
+    fn children(&mut self, dir: &Path) -> &[TreeEntry] {
+        if !self.children.contains_key(dir) {
+            let entries = self.read_dir(dir);
+            self.children.insert(dir.to_path_buf(), entries);
+        }
+        &self.children[dir]
+    }
+
+    /// Lists `dir`, skipping ignored entries, with folders first.
+    fn read_dir(&mut self, dir: &Path) -> Vec<TreeEntry> {
+        if self.rules_loaded.insert(dir.to_path_buf()) {
+            self.rules.add_dir(dir);
+        }
+
+        let mut entries: Vec<TreeEntry> = match std::fs::read_dir(dir) {
+            Ok(read_dir) => read_dir
+                .flatten()
+                .filter_map(|entry| {
+                    let is_dir = entry.file_type().ok()?.is_dir();
+                    let path = entry.path();
+                    (!self.rules.is_ignored(&path, is_dir)).then_some(TreeEntry { path, is_dir })
+                })
+                .collect(),
+            Err(err) => {
+                log::warn!("Failed to read directory {}: {}", dir.display(), err);
+                Vec::new()
+            }
+        };
+
+        entries.sort_by_key(|entry| (!entry.is_dir, file_name(&entry.path).to_lowercase()));
+        entries
+    }
+
+    /// Returns the entries currently visible, in display order, with their depth.
+    fn visible_rows(&mut self, root: &Path) -> Vec<(TreeEntry, usize)> {
+        let mut rows = Vec::new();
+        let mut stack: Vec<(TreeEntry, usize)> = self
+            .children(root)
+            .iter()
+            .rev()
+            .map(|entry| (entry.clone(), 0))
+            .collect();
+
+        while let Some((entry, depth)) = stack.pop() {
+            if entry.is_dir && self.expanded.contains(&entry.path) {
+                let children: Vec<TreeEntry> = self.children(&entry.path).to_vec();
+                stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+            }
+            rows.push((entry, depth));
+        }
+
+        rows
+    }
+}
+
+/// Returns the last component of `path` as a string.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Returns a short badge and colour identifying the type of a file.
+fn file_icon(path: &Path) -> (&'static str, Color32) {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "rs" => ("rs", Color32::from_rgb(222, 122, 72)),
+        "md" | "markdown" => ("md", Color32::from_rgb(86, 156, 255)),
+        "toml" => ("tm", Color32::from_rgb(156, 130, 100)),
+        "json" => ("{}", Color32::from_rgb(220, 190, 80)),
+        "yaml" | "yml" => ("ym", Color32::from_rgb(180, 110, 200)),
+        "js" | "mjs" | "ts" | "tsx" | "jsx" => ("js", Color32::from_rgb(230, 200, 60)),
+        "py" => ("py", Color32::from_rgb(80, 140, 200)),
+        "html" | "htm" | "xml" | "svg" => ("<>", Color32::from_rgb(228, 100, 70)),
+        "css" | "scss" => ("#", Color32::from_rgb(90, 160, 220)),
+        "sh" | "bash" | "zsh" => ("$", Color32::from_rgb(120, 190, 110)),
+        "c" | "h" | "cpp" | "hpp" | "cc" => ("c", Color32::from_rgb(100, 150, 210)),
+        "go" => ("go", Color32::from_rgb(90, 200, 220)),
+        "png" | "jpg" | "jpeg" | "gif" | "icns" | "ico" => ("im", Color32::from_rgb(160, 120, 200)),
+        "lock" => ("lk", Color32::from_rgb(140, 140, 150)),
+        _ => ("·", Color32::from_rgb(140, 140, 150)),
+    }
+}
+
+/// Expands the tree to the active file and selects it.
+pub fn reveal_active_file(app: &mut ZingApp) {
+    let root = match app.workspace.root.clone() {
+        Some(root) => root,
+        None => return,
+    };
+    let buffer = app.buffer();
+    let path = buffer.lock().unwrap().file_path.clone();
+
+    match path {
+        Some(path) if path.starts_with(&root) => {
+            app.file_tree.reveal(&root, &path);
+            app.sidebar_section = crate::ui::SidebarSection::Files;
+        }
+        _ => app.set_status("The active file is not in the open folder".to_string(), 3.0),
+    }
+}
+
+/// Starts naming a new file or folder next to the selection.
+fn start_create(app: &mut ZingApp, folder: bool) {
+    let root = match app.workspace.root.clone() {
+        Some(root) => root,
+        None => return,
+    };
+    let parent = match &app.file_tree.selected {
+        Some(path) if path.is_dir() => path.clone(),
+        Some(path) => path.parent().map(Path::to_path_buf).unwrap_or(root),
+        None => root,
+    };
+    app.file_tree.expanded.insert(parent.clone());
+    let edit = if folder { TreeEdit::NewFolder(parent) } else { TreeEdit::NewFile(parent) };
+    app.file_tree.edit = Some((edit, String::new()));
+}
+
+/// Applies the inline edit in progress.
+fn commit_edit(app: &mut ZingApp) {
+    let (edit, name) = match app.file_tree.edit.take() {
+        Some(edit) => edit,
+        None => return,
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return;
+    }
+
+    let result = match &edit {
+        TreeEdit::NewFile(parent) => {
+            let path = parent.join(name);
+            workspace::create_file(&path).map(|_| (parent.clone(), path))
+        }
+        TreeEdit::NewFolder(parent) => {
+            let path = parent.join(name);
+            workspace::create_folder(&path).map(|_| (parent.clone(), path))
+        }
+        TreeEdit::Rename(path) => {
+            let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            let target = parent.join(name);
+            workspace::rename_path(path, &target).map(|_| (parent, target))
+        }
+    };
+
+    match result {
+        Ok((parent, path)) => {
+            app.file_tree.refresh(&parent);
+            app.file_tree.selected = Some(path.clone());
+            app.workspace.reindex();
+            match edit {
+                TreeEdit::NewFile(_) => {
+                    editor::open_path(app, &path);
+                }
+                TreeEdit::Rename(old) => {
+                    app.file_tree.refresh(&old);
+                    app.retarget_paths(&old, &path);
+                    app.set_status(format!("Renamed to {}", file_name(&path)), 3.0);
+                }
+                TreeEdit::NewFolder(_) => {}
+            }
+        }
+        Err(err) => {
+            log::error!("{:#}", err);
+            app.set_status(format!("{:#}", err), 5.0);
+        }
+    }
+}
+
+/// Moves `path` into the folder `dir`.
+fn move_entry(app: &mut ZingApp, path: &Path, dir: &Path) {
+    if path.parent() == Some(dir) || path == dir {
+        return;
+    }
+    match workspace::move_path(path, dir) {
+        Ok(target) => {
+            if let Some(parent) = path.parent() {
+                app.file_tree.refresh(parent);
+            }
+            app.file_tree.refresh(dir);
+            app.file_tree.expanded.insert(dir.to_path_buf());
+            app.file_tree.selected = Some(target.clone());
+            app.workspace.reindex();
+            app.retarget_paths(path, &target);
+            app.set_status(format!("Moved {} to {}", file_name(path), dir.display()), 3.0);
+        }
+        Err(err) => {
+            log::error!("{:#}", err);
+            app.set_status(format!("{:#}", err), 5.0);
+        }
+    }
+}
+
+/// Returns whether the file tree has keyboard focus.
+pub fn has_focus(ctx: &Context) -> bool {
+    ctx.memory(|mem| mem.has_focus(Id::new("file_tree")))
+}
+
+/// Handles arrow keys, Enter, F2 and Delete while the tree has keyboard focus.
+fn handle_keys(app: &mut ZingApp, ui: &Ui, rows: &[(TreeEntry, usize)], actions: &mut Vec<TreeAction>) {
+    let pressed = |key: Key| ui.input_mut(|i| i.consume_key(Modifiers::NONE, key));
+    let index = app
+        .file_tree
+        .selected
+        .as_ref()
+        .and_then(|selected| rows.iter().position(|(entry, _)| entry.path == *selected));
+
+    let select = |actions: &mut Vec<TreeAction>, row: Option<&(TreeEntry, usize)>| {
+        if let Some((entry, _)) = row {
+            actions.push(TreeAction::Select(entry.path.clone()));
+        }
+    };
+
+    if pressed(Key::ArrowDown) {
+        select(actions, rows.get(index.map_or(0, |i| i + 1)));
+    }
+    if pressed(Key::ArrowUp) {
+        select(actions, index.and_then(|i| i.checked_sub(1)).and_then(|i| rows.get(i)));
+    }
+
+    let current = match index.map(|i| &rows[i]) {
+        Some(row) => row,
+        None => return,
+    };
+    let (entry, depth) = current;
+    let is_expanded = app.file_tree.expanded.contains(&entry.path);
+
+    if pressed(Key::ArrowRight) {
+        if entry.is_dir && !is_expanded {
+            actions.push(TreeAction::Toggle(entry.path.clone()));
+        } else if entry.is_dir {
+            select(actions, rows.get(index.unwrap() + 1).filter(|(_, d)| d > depth));
+        }
+    }
+    if pressed(Key::ArrowLeft) {
+        if entry.is_dir && is_expanded {
+            actions.push(TreeAction::Toggle(entry.path.clone()));
+        } else if let Some(parent) = entry.path.parent() {
+            if rows.iter().any(|(row, _)| row.path == parent) {
+                actions.push(TreeAction::Select(parent.to_path_buf()));
+            }
+        }
+    }
+    if pressed(Key::Enter) {
+        if entry.is_dir {
+            actions.push(TreeAction::Toggle(entry.path.clone()));
+        } else {
+            actions.push(TreeAction::Open(entry.path.clone()));
+        }
+    }
+    if pressed(Key::F2) {
+        actions.push(TreeAction::StartEdit(TreeEdit::Rename(entry.path.clone())));
+    }
+    if pressed(Key::Delete) {
+        actions.push(TreeAction::RequestDelete(entry.path.clone()));
+    }
+}
+
+/// Draws the disclosure triangle of a folder row.
+fn paint_chevron(ui: &Ui, center: Pos2, expanded: bool, color: Color32) {
+    let points = if expanded {
+        vec![
+            center + Vec2::new(-3.5, -2.0),
+            center + Vec2::new(3.5, -2.0),
+            center + Vec2::new(0.0, 2.5),
+        ]
+    } else {
+        vec![
+            center + Vec2::new(-2.0, -3.5),
+            center + Vec2::new(2.5, 0.0),
+            center + Vec2::new(-2.0, 3.5),
+        ]
+    };
+    ui.painter().add(egui::Shape::convex_polygon(points, color, Stroke::NONE));
+}
+
+/// Renders the inline name editor for a new or renamed entry.
+fn edit_row(app: &mut ZingApp, ui: &mut Ui, depth: usize, actions: &mut Vec<TreeAction>) {
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(ui.available_width(), ROW_HEIGHT), Sense::hover());
+    let edit_rect = Rect::from_min_max(
+        rect.min + Vec2::new(8.0 + depth as f32 * INDENT + 14.0, 1.0),
+        rect.max - Vec2::new(4.0, 1.0),
+    );
+
+    if let Some((_, name)) = &mut app.file_tree.edit {
+        let response = ui.put(
+            edit_rect,
+            egui::TextEdit::singleline(name).font(FontId::proportional(12.0)),
+        );
+        if !response.has_focus() && !response.lost_focus() {
+            response.request_focus();
+        }
+        if response.lost_focus() {
+            if ui.input(|i| i.key_pressed(Key::Enter)) {
+                actions.push(TreeAction::CommitEdit);
+            } else {
+                actions.push(TreeAction::CancelEdit);
+            }
+        }
+    }
+}
+
+/// Renders the file tree section of the sidebar.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (text_color, muted_color, selected_bg, hover_bg, accent) = if is_dark {
+        (
+            Color32::from_rgb(210, 210, 225),
+            Color32::from_rgb(140, 140, 160),
+            Color32::from_rgb(35, 35, 48),
+            Color32::from_rgb(28, 28, 38),
+            Color32::from_rgb(86, 156, 255),
+        )
+    } else {
+        (
+            Color32::from_rgb(40, 40, 60),
+            Color32::from_rgb(120, 120, 130),
+            Color32::from_rgb(250, 250, 255),
+            Color32::from_rgb(242, 242, 247),
+            Color32::from_rgb(0, 120, 215),
+        )
+    };
+
+    let root = match app.workspace.root.clone() {
+        Some(root) => root,
+        None => {
+            ui.add_space(12.0);
+            ui.vertical_centered(|ui| {
+                ui.label(egui::RichText::new("No folder open").color(muted_color).size(12.0));
+                ui.add_space(6.0);
+                if ui.button("Open Folder...").clicked() {
+                    editor::open_folder(app);
+                }
+            });
+            return;
+        }
+    };
+
+    let mut actions = Vec::new();
+
+    // Header with the folder name and a menu of tree actions
+    ui.horizontal(|ui| {
+        ui.add_space(8.0);
+        ui.label(
+            egui::RichText::new(file_name(&root).to_uppercase())
+                .color(muted_color)
+                .size(11.0)
+                .strong(),
+        )
+        .on_hover_text(root.display().to_string());
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            egui::menu::menu_button(ui, "...", |ui| {
+                if ui.button("New File").clicked() {
+                    start_create(app, false);
+                    ui.close_menu();
+                }
+                if ui.button("New Folder").clicked() {
+                    start_create(app, true);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("Reveal Active File").clicked() {
+                    reveal_active_file(app);
+                    ui.close_menu();
+                }
+                if ui.button("Refresh").clicked() {
+                    app.file_tree.refresh_all();
+                    app.workspace.reindex();
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("Open Folder...").clicked() {
+                    editor::open_folder(app);
+                    ui.close_menu();
+                }
+            });
+        });
+    });
+
+    let tree_id = Id::new("file_tree");
+    let rows = app.file_tree.visible_rows(&root);
+    let has_focus = ui.memory(|mem| mem.has_focus(tree_id));
+    if has_focus && app.file_tree.edit.is_none() {
+        handle_keys(app, ui, &rows, &mut actions);
+    }
+
+    let pointer_released = ui.input(|i| i.pointer.any_released());
+    let pointer_pos = ui.input(|i| i.pointer.interact_pos());
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            ui.spacing_mut().item_spacing = Vec2::ZERO;
+
+            // Background interaction keeps keyboard focus in the tree and accepts drops on the root
+            let background = ui.interact(ui.max_rect(), tree_id, Sense::click());
+            if background.clicked() {
+                background.request_focus();
+            }
+
+            let edit_parent = match &app.file_tree.edit {
+                Some((TreeEdit::NewFile(parent), _)) | Some((TreeEdit::NewFolder(parent), _)) => {
+                    Some(parent.clone())
+                }
+                _ => None,
+            };
+            if edit_parent.as_ref() == Some(&root) {
+                edit_row(app, ui, 0, &mut actions);
+            }
+
+            let mut drop_target = None;
+            let mut over_row = false;
+
+            for (entry, depth) in &rows {
+                let renaming = matches!(&app.file_tree.edit, Some((TreeEdit::Rename(path), _)) if *path == entry.path);
+                if renaming {
+                    edit_row(app, ui, *depth, &mut actions);
+                    continue;
+                }
+
+                let (rect, response) = ui.allocate_exact_size(
+                    Vec2::new(ui.available_width(), ROW_HEIGHT),
+                    Sense::click_and_drag(),
+                );
+                let is_selected = app.file_tree.selected.as_ref() == Some(&entry.path);
+                let is_expanded = app.file_tree.expanded.contains(&entry.path);
+
+                // Drag and drop onto folders, or onto a file's folder
+                let dragging = app.file_tree.dragging.is_some();
+                let is_hovered = dragging && pointer_pos.is_some_and(|pos| rect.contains(pos));
+                let is_drop_target = is_hovered && entry.is_dir;
+                if is_hovered {
+                    over_row = true;
+                    drop_target = if entry.is_dir {
+                        Some(entry.path.clone())
+                    } else {
+                        entry.path.parent().map(Path::to_path_buf)
+                    };
+                }
+
+                if is_selected || is_drop_target {
+                    ui.painter().rect_filled(rect, 0.0, selected_bg);
+                } else if response.hovered() {
+                    ui.painter().rect_filled(rect, 0.0, hover_bg);
+                }
+                if is_drop_target {
+                    ui.painter().rect_stroke(rect.shrink(1.0), 2.0, Stroke::new(1.0, accent));
+                }
+                if is_selected && app.file_tree.scroll_to_selected {
+                    ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                    app.file_tree.scroll_to_selected = false;
+                }
+
+                let x = rect.min.x + 8.0 + *depth as f32 * INDENT;
+                if entry.is_dir {
+                    paint_chevron(ui, Pos2::new(x + 5.0, rect.center().y), is_expanded, muted_color);
+                } else {
+                    let (badge, color) = file_icon(&entry.path);
+                    ui.painter().text(
+                        Pos2::new(x + 5.0, rect.center().y),
+                        egui::Align2::CENTER_CENTER,
+                        badge,
+                        FontId::monospace(8.0),
+                        color,
+                    );
+                }
+                ui.painter().text(
+                    Pos2::new(x + 14.0, rect.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    file_name(&entry.path),
+                    FontId::proportional(12.0),
+                    if is_selected { accent } else { text_color },
+                );
+
+                if response.clicked() {
+                    ui.memory_mut(|mem| mem.request_focus(tree_id));
+                    actions.push(TreeAction::Select(entry.path.clone()));
+                    if entry.is_dir {
+                        actions.push(TreeAction::Toggle(entry.path.clone()));
+                    } else {
+                        actions.push(TreeAction::Open(entry.path.clone()));
+                    }
+                }
+                if response.drag_started() {
+                    app.file_tree.dragging = Some(entry.path.clone());
+                }
+
+                let path = entry.path.clone();
+                let is_dir = entry.is_dir;
+                response.context_menu(|ui| {
+                    if is_dir {
+                        if ui.button("New File").clicked() {
+                            actions.push(TreeAction::Select(path.clone()));
+                            actions.push(TreeAction::StartEdit(TreeEdit::NewFile(path.clone())));
+                            ui.close_menu();
+                        }
+                        if ui.button("New Folder").clicked() {
+                            actions.push(TreeAction::Select(path.clone()));
+                            actions.push(TreeAction::StartEdit(TreeEdit::NewFolder(path.clone())));
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                    } else if ui.button("Open").clicked() {
+                        actions.push(TreeAction::Open(path.clone()));
+                        ui.close_menu();
+                    }
+                    if ui.button("Rename...").clicked() {
+                        actions.push(TreeAction::StartEdit(TreeEdit::Rename(path.clone())));
+                        ui.close_menu();
+                    }
+                    if ui.button("Delete").clicked() {
+                        actions.push(TreeAction::RequestDelete(path.clone()));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Copy Path").clicked() {
+                        ui.ctx().copy_text(path.display().to_string());
+                        ui.close_menu();
+                    }
+                });
+
+                if edit_parent.as_ref() == Some(&entry.path) && is_expanded {
+                    edit_row(app, ui, depth + 1, &mut actions);
+                }
+            }
+
+            // Dropping on empty space below the rows moves the entry to the root
+            if pointer_released {
+                if let Some(dragged) = app.file_tree.dragging.take() {
+                    let over_tree = pointer_pos.is_some_and(|pos| ui.clip_rect().contains(pos));
+                    let target = drop_target.or_else(|| (over_tree && !over_row).then(|| root.clone()));
+                    if let Some(target) = target {
+                        actions.push(TreeAction::Move(dragged, target));
+                    }
+                }
+            }
+        });
+
+    for action in actions {
+        match action {
+            TreeAction::Open(path) => {
+                editor::open_path(app, &path);
+            }
+            TreeAction::Toggle(path) => {
+                if !app.file_tree.expanded.remove(&path) {
+                    app.file_tree.expanded.insert(path);
+                }
+            }
+            TreeAction::Select(path) => {
+                app.file_tree.selected = Some(path);
+                app.file_tree.scroll_to_selected = true;
+            }
+            TreeAction::StartEdit(edit) => {
+                let name = match &edit {
+                    TreeEdit::Rename(path) => file_name(path),
+                    _ => String::new(),
+                };
+                if let TreeEdit::NewFile(parent) | TreeEdit::NewFolder(parent) = &edit {
+                    app.file_tree.expanded.insert(parent.clone());
+                }
+                app.file_tree.edit = Some((edit, name));
+            }
+            TreeAction::CommitEdit => commit_edit(app),
+            TreeAction::CancelEdit => app.file_tree.edit = None,
+            TreeAction::RequestDelete(path) => app.file_tree.pending_delete = Some(path),
+            TreeAction::Move(path, dir) => move_entry(app, &path, &dir),
+        }
+    }
+
+    delete_confirmation(app, ui.ctx());
+}
+
+/// Asks for confirmation before deleting an entry.
+fn delete_confirmation(app: &mut ZingApp, ctx: &egui::Context) {
+    let path = match app.file_tree.pending_delete.clone() {
+        Some(path) => path,
+        None => return,
+    };
+
+    let mut confirmed = false;
+    let mut cancelled = false;
+    egui::Window::new("Delete")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+        .show(ctx, |ui| {
+            let kind = if path.is_dir() { "folder and its contents" } else { "file" };
+            ui.label(format!("Permanently delete the {} '{}'?", kind, file_name(&path)));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Delete").clicked();
+                cancelled = ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(Key::Escape));
+            });
+        });
+
+    if confirmed {
+        app.file_tree.pending_delete = None;
+        match workspace::delete_path(&path) {
+            Ok(()) => {
+                if let Some(parent) = path.parent() {
+                    app.file_tree.refresh(parent);
+                }
+                app.file_tree.selected = None;
+                app.workspace.reindex();
+                app.set_status(format!("Deleted {}", file_name(&path)), 3.0);
+            }
+            Err(err) => {
+                log::error!("{:#}", err);
+                app.set_status(format!("{:#}", err), 5.0);
+            }
+        }
+    } else if cancelled {
+        app.file_tree.pending_delete = None;
+    }
+}
@@ -0,0 +1,102 @@
+//! Code folding for Zing text editor.
+//!
+//! Commands to fold and unfold the regions of the active buffer. A folded region
+//! is shown as its first line followed by a placeholder.
+
+use egui::Context;
+
+use crate::buffer::FoldRegion;
+use crate::ui::{editor, ZingApp};
+
+/// Returns the innermost region containing `line` that `filter` accepts.
+fn innermost(regions: &[FoldRegion], line: usize, filter: impl Fn(&FoldRegion) -> bool) -> Option<FoldRegion> {
+    regions
+        .iter()
+        .filter(|region| region.contains(line) && filter(region))
+        .max_by_key(|region| region.start_line)
+        .copied()
+}
+
+/// Folds or unfolds the region starting on `line`, as clicked in the gutter.
+pub fn toggle_line(app: &mut ZingApp, ctx: &Context, line: usize) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    if !buffer_lock.unfold(line) {
+        if let Err(err) = buffer_lock.fold(line) {
+            log::error!("Failed to fold: {}", err);
+        }
+    }
+    drop(buffer_lock);
+    editor::refresh_selection(app, ctx);
+}
+
+/// Folds the innermost unfolded region around the cursor.
+pub fn fold(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let regions = buffer_lock.fold_regions();
+    let region = innermost(&regions, app.cursor_line, |region| !buffer_lock.is_folded(region.start_line));
+    let Some(region) = region else {
+        drop(buffer_lock);
+        app.set_status("Nothing to fold here".to_string(), 2.0);
+        return;
+    };
+    buffer_lock.fold(region.start_line).ok();
+    drop(buffer_lock);
+    editor::refresh_selection(app, ctx);
+}
+
+/// Unfolds the folded region on the cursor line.
+pub fn unfold(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let regions = buffer_lock.fold_regions();
+    let region = innermost(&regions, app.cursor_line, |region| buffer_lock.is_folded(region.start_line));
+    let Some(region) = region else {
+        drop(buffer_lock);
+        app.set_status("Nothing folded here".to_string(), 2.0);
+        return;
+    };
+    buffer_lock.unfold(region.start_line);
+    drop(buffer_lock);
+    editor::refresh_selection(app, ctx);
+}
+
+/// Folds every region of the active buffer.
+pub fn fold_all(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    for region in buffer_lock.fold_regions() {
+        buffer_lock.fold(region.start_line).ok();
+    }
+    drop(buffer_lock);
+    editor::refresh_selection(app, ctx);
+}
+
+/// Unfolds every region of the active buffer.
+pub fn unfold_all(app: &mut ZingApp, ctx: &Context) {
+    app.buffer().lock().unwrap().unfold_all();
+    editor::refresh_selection(app, ctx);
+}
+
+/// Folds the regions nested `level` deep, where 1 is the outermost level.
+pub fn fold_level(app: &mut ZingApp, ctx: &Context, level: usize) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let regions: Vec<FoldRegion> = buffer_lock
+        .fold_regions()
+        .into_iter()
+        .filter(|region| region.depth == level)
+        .collect();
+    for region in &regions {
+        buffer_lock.fold(region.start_line).ok();
+    }
+    drop(buffer_lock);
+
+    if regions.is_empty() {
+        app.set_status(format!("No regions at level {}", level), 2.0);
+    } else {
+        app.set_status(format!("Folded level {}", level), 2.0);
+    }
+    editor::refresh_selection(app, ctx);
+}
@@ -0,0 +1,104 @@
+//! Go to line overlay for Zing text editor.
+//!
+//! Lets the user type a `line` or `line:column` to move the cursor there.
+
+use egui::{Align2, Context, Key, Modifiers, Vec2};
+
+use crate::ui::{editor, ZingApp};
+
+/// State of the go to line overlay.
+#[derive(Debug, Default)]
+pub struct GoToLine {
+    /// Whether the overlay is visible
+    pub open: bool,
+    /// The text typed by the user
+    query: String,
+}
+
+impl GoToLine {
+    /// Creates a closed go to line overlay.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Shows the go to line overlay.
+pub fn show(app: &mut ZingApp) {
+    app.go_to_line.open = true;
+    app.go_to_line.query.clear();
+}
+
+/// Parses `line` or `line:column`, both 1-based.
+fn parse(query: &str) -> Option<(usize, Option<usize>)> {
+    let query = query.trim();
+    let (line, column) = match query.split_once([':', ',']) {
+        Some((line, column)) => (line, Some(column.trim())),
+        None => (query, None),
+    };
+
+    let line = line.trim().parse::<usize>().ok().filter(|&line| line > 0)?;
+    let column = match column {
+        Some(column) => Some(column.parse::<usize>().ok().filter(|&column| column > 0)?),
+        None => None,
+    };
+    Some((line, column))
+}
+
+/// Jumps to the typed location and closes the overlay.
+fn accept(app: &mut ZingApp) {
+    match parse(&app.go_to_line.query) {
+        Some((line, column)) => {
+            app.go_to_line.open = false;
+            editor::go_to(app, line - 1, column.unwrap_or(1) - 1);
+        }
+        None => {
+            app.set_status("Enter a line number, optionally followed by :column".to_string(), 3.0);
+        }
+    }
+}
+
+/// Renders the go to line overlay if it is open.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.go_to_line.open {
+        return;
+    }
+
+    let (enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    if escape {
+        app.go_to_line.open = false;
+        return;
+    }
+    if enter {
+        accept(app);
+        return;
+    }
+
+    let line_count = app.buffer().lock().unwrap().len_lines();
+    let hint = format!(
+        "Line[:Column] (current {}:{}, 1-{})",
+        app.cursor_line + 1,
+        app.cursor_column + 1,
+        line_count
+    );
+
+    egui::Area::new("go_to_line")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(320.0);
+                ui.label("Go to Line");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut app.go_to_line.query)
+                        .hint_text(hint)
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+            });
+        });
+}
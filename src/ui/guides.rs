@@ -0,0 +1,186 @@
+//! Guides drawn over the editor text for Zing text editor.
+//!
+//! Draws whitespace as faint symbols, lines along indentation levels with the
+//! block around the cursor highlighted, and rulers at chosen columns.
+
+use egui::text_edit::TextEditOutput;
+use egui::{Align2, Color32, FontId, Galley, Pos2, Rect, Stroke, Ui};
+use std::ops::Range;
+
+use crate::config::ShowWhitespace;
+
+/// A line of the laid out text, which wraps over one row or more.
+struct DisplayLine {
+    /// Rows of the galley the line is laid out in
+    rows: Range<usize>,
+    /// Character index of the start of the line
+    start: usize,
+    /// Where the first character after the indentation starts, or `None`
+    /// for lines of only whitespace
+    indent: Option<f32>,
+    /// Character index where the whitespace at the end of the line starts
+    trailing: usize,
+}
+
+/// Splits the rows of `galley` into the lines they show.
+fn display_lines(galley: &Galley) -> Vec<DisplayLine> {
+    let mut lines = Vec::new();
+    let mut line: Option<DisplayLine> = None;
+    let mut index = 0;
+    for (row_index, row) in galley.rows.iter().enumerate() {
+        let current = line.get_or_insert(DisplayLine {
+            rows: row_index..row_index,
+            start: index,
+            indent: None,
+            trailing: index,
+        });
+        current.rows.end = row_index + 1;
+        for glyph in &row.glyphs {
+            if glyph.chr != ' ' && glyph.chr != '\t' {
+                if current.indent.is_none() {
+                    current.indent = Some(glyph.pos.x);
+                }
+                current.trailing = index + 1;
+            }
+            index += 1;
+        }
+        if row.ends_with_newline {
+            index += 1;
+            lines.extend(line.take());
+        }
+    }
+    lines.extend(line);
+    lines
+}
+
+/// Returns whether the rows of `galley` in `rows` are on screen.
+fn is_visible(ui: &Ui, output: &TextEditOutput, rows: Range<usize>) -> bool {
+    let clip = ui.clip_rect();
+    let top = output.text_draw_pos.y + output.galley.rows[rows.start].rect.min.y;
+    let bottom = output.text_draw_pos.y + output.galley.rows[rows.end - 1].rect.max.y;
+    bottom >= clip.min.y && top <= clip.max.y
+}
+
+/// Draws the whitespace `mode` asks for as faint symbols: a dot for spaces,
+/// an arrow for tabs and `¬` for line endings. `selection` is in characters
+/// of the laid out text.
+pub fn paint_whitespace(
+    ui: &Ui,
+    output: &TextEditOutput,
+    mode: ShowWhitespace,
+    selection: Range<usize>,
+    font_id: FontId,
+    color: Color32,
+) {
+    if mode == ShowWhitespace::None {
+        return;
+    }
+    let origin = output.text_draw_pos.to_vec2();
+    for line in display_lines(&output.galley) {
+        if !is_visible(ui, output, line.rows.clone()) {
+            continue;
+        }
+        let mut index = line.start;
+        for row in &output.galley.rows[line.rows.clone()] {
+            for glyph in &row.glyphs {
+                let symbol = match glyph.chr {
+                    ' ' => Some("·"),
+                    '\t' => Some("→"),
+                    _ => None,
+                };
+                let shown = match mode {
+                    ShowWhitespace::None => false,
+                    ShowWhitespace::Selection => selection.contains(&index),
+                    ShowWhitespace::Trailing => index >= line.trailing,
+                    ShowWhitespace::All => true,
+                };
+                if let (Some(symbol), true) = (symbol, shown) {
+                    let x = glyph.pos.x..=glyph.pos.x + glyph.size.x;
+                    let rect = Rect::from_x_y_ranges(x, row.rect.y_range()).translate(origin);
+                    ui.painter().text(rect.center(), Align2::CENTER_CENTER, symbol, font_id.clone(), color);
+                }
+                index += 1;
+            }
+            let ending_shown = match mode {
+                ShowWhitespace::Selection => selection.contains(&index),
+                ShowWhitespace::All => true,
+                _ => false,
+            };
+            if row.ends_with_newline && ending_shown {
+                let pos = Pos2::new(row.rect.max.x, row.rect.center().y) + origin;
+                ui.painter().text(pos, Align2::LEFT_CENTER, "¬", font_id.clone(), color);
+            }
+        }
+    }
+}
+
+/// Draws a line at every indentation level `step` points apart, highlighting
+/// the one along the block holding the `cursor` character.
+///
+/// Lines of only whitespace are as deep as the shallower of the lines around
+/// them, so guides run on through blank lines inside a block.
+pub fn paint_indent_guides(
+    ui: &Ui,
+    output: &TextEditOutput,
+    step: f32,
+    cursor: usize,
+    color: Color32,
+    active_color: Color32,
+) {
+    let lines = display_lines(&output.galley);
+    if lines.is_empty() || step <= 0.0 {
+        return;
+    }
+
+    let depth = |indent: f32| (indent / step + 0.01).floor() as usize;
+    let mut previous = vec![0; lines.len()];
+    let mut last = 0;
+    for (i, line) in lines.iter().enumerate() {
+        last = line.indent.map_or(last, depth);
+        previous[i] = last;
+    }
+    let mut next = 0;
+    let mut levels = vec![0; lines.len()];
+    for (i, line) in lines.iter().enumerate().rev() {
+        levels[i] = match line.indent {
+            Some(indent) => depth(indent),
+            None => previous[i].min(next),
+        };
+        next = line.indent.map_or(next, depth);
+    }
+
+    // The block around the cursor spans the lines at least as deep as its line
+    let cursor_line = lines.partition_point(|line| line.start <= cursor).saturating_sub(1);
+    let active_level = levels[cursor_line].checked_sub(1);
+    let mut block = cursor_line..cursor_line + 1;
+    if let Some(level) = active_level {
+        while block.start > 0 && levels[block.start - 1] > level {
+            block.start -= 1;
+        }
+        while block.end < lines.len() && levels[block.end] > level {
+            block.end += 1;
+        }
+    }
+
+    let origin = output.text_draw_pos;
+    for (i, line) in lines.iter().enumerate() {
+        if levels[i] == 0 || !is_visible(ui, output, line.rows.clone()) {
+            continue;
+        }
+        let top = origin.y + output.galley.rows[line.rows.start].rect.min.y;
+        let bottom = origin.y + output.galley.rows[line.rows.end - 1].rect.max.y;
+        for level in 0..levels[i] {
+            let active = Some(level) == active_level && block.contains(&i);
+            let stroke = Stroke::new(1.0, if active { active_color } else { color });
+            ui.painter().vline(origin.x + level as f32 * step, top..=bottom, stroke);
+        }
+    }
+}
+
+/// Draws a line down the editor at each of `columns`.
+pub fn paint_rulers(ui: &Ui, output: &TextEditOutput, columns: &[usize], char_width: f32, color: Color32) {
+    for &column in columns {
+        let x = output.text_draw_pos.x + char_width * column as f32;
+        ui.painter().vline(x, ui.clip_rect().y_range(), Stroke::new(1.0, color));
+    }
+}
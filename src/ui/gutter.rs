@@ -0,0 +1,152 @@
+//! Gutter component for Zing text editor.
+//!
+//! Draws line numbers, bookmark markers and fold toggles in a strip to the
+//! left of the text.
+
+use egui::text_edit::TextEditOutput;
+use egui::{Color32, FontId, Galley, Pos2, Rect, Rounding, Sense, Ui};
+
+use crate::buffer::{FoldMap, FoldRegion};
+use crate::config::Theme;
+use crate::ui::ZingApp;
+
+/// Width of the column holding bookmark markers.
+const MARKER_WIDTH: f32 = 14.0;
+/// Width of the column holding fold toggles, between the numbers and the text.
+const FOLD_WIDTH: f32 = 16.0;
+
+/// A click in the gutter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GutterClick {
+    /// Toggle the bookmark of a line
+    Bookmark(usize),
+    /// Fold or unfold the region starting on a line
+    Fold(usize),
+}
+
+/// Returns the width of the gutter for a buffer with `line_count` lines.
+pub fn width(app: &ZingApp, ui: &Ui, line_count: usize) -> f32 {
+    if !app.config.show_line_numbers {
+        return MARKER_WIDTH + FOLD_WIDTH;
+    }
+
+    let digits = line_count.max(1).ilog10() as usize + 1;
+    let font_id = FontId::monospace(app.config.font_size);
+    let digit_width = ui.fonts(|fonts| fonts.glyph_width(&font_id, '0'));
+    MARKER_WIDTH + digits.max(2) as f32 * digit_width + FOLD_WIDTH
+}
+
+/// Returns the buffer line of every row in `galley`, as wrapped lines take
+/// several rows and folded regions hide lines.
+pub fn row_lines(galley: &Galley, fold_map: &FoldMap) -> Vec<usize> {
+    let mut line = 0;
+    galley
+        .rows
+        .iter()
+        .map(|row| {
+            let row_line = fold_map.buffer_line(line);
+            if row.ends_with_newline {
+                line += 1;
+            }
+            row_line
+        })
+        .collect()
+}
+
+/// Draws the gutter for the text laid out by the editor and returns what was
+/// clicked in it.
+///
+/// The gutter occupies the `gutter_width` wide strip left of the text.
+pub fn ui(
+    app: &ZingApp,
+    ui: &mut Ui,
+    output: &TextEditOutput,
+    gutter_width: f32,
+    bookmarks: &[usize],
+    fold_regions: &[FoldRegion],
+    fold_map: &FoldMap,
+) -> Option<GutterClick> {
+    let galley = &output.galley;
+    let text_draw_pos = output.text_draw_pos;
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (number_color, current_color, marker_color) = if is_dark {
+        (Color32::from_gray(95), Color32::from_gray(200), Color32::from_rgb(86, 156, 255))
+    } else {
+        (Color32::from_gray(160), Color32::from_gray(40), Color32::from_rgb(0, 120, 215))
+    };
+
+    let rect = Rect::from_min_max(
+        Pos2::new(text_draw_pos.x - gutter_width, text_draw_pos.y),
+        Pos2::new(text_draw_pos.x, text_draw_pos.y + galley.size().y),
+    );
+    let response = ui.interact(rect, ui.id().with("gutter"), Sense::click());
+
+    let font_id = FontId::monospace(app.config.font_size);
+    let clip = ui.clip_rect();
+    let lines = row_lines(galley, fold_map);
+    let painter = ui.painter();
+
+    for (index, row) in galley.rows.iter().enumerate() {
+        let top = text_draw_pos.y + row.rect.min.y;
+        let bottom = text_draw_pos.y + row.rect.max.y;
+        if bottom < clip.min.y || top > clip.max.y {
+            continue;
+        }
+
+        // Only the first row of a wrapped line gets a number and marker
+        let line = lines[index];
+        if index > 0 && lines[index - 1] == line {
+            continue;
+        }
+        let center_y = (top + bottom) / 2.0;
+
+        if bookmarks.contains(&line) {
+            let marker = Rect::from_center_size(
+                Pos2::new(rect.min.x + MARKER_WIDTH / 2.0, center_y),
+                egui::vec2(6.0, (bottom - top).min(12.0)),
+            );
+            painter.rect_filled(marker, Rounding::same(1.5), marker_color);
+        }
+
+        if app.config.show_line_numbers {
+            let color = if line == app.cursor_line { current_color } else { number_color };
+            painter.text(
+                Pos2::new(rect.max.x - FOLD_WIDTH, center_y),
+                egui::Align2::RIGHT_CENTER,
+                (line + 1).to_string(),
+                font_id.clone(),
+                color,
+            );
+        }
+
+        if fold_regions.iter().any(|region| region.start_line == line) {
+            let folded = fold_map.is_folded(line);
+            painter.text(
+                Pos2::new(rect.max.x - FOLD_WIDTH / 2.0, center_y),
+                egui::Align2::CENTER_CENTER,
+                if folded { "▸" } else { "▾" },
+                FontId::proportional(app.config.font_size * 0.8),
+                if folded { current_color } else { number_color },
+            );
+        }
+    }
+
+    if !response.clicked() {
+        return None;
+    }
+
+    let pointer = response.interact_pointer_pos()?;
+    let line = galley
+        .rows
+        .iter()
+        .position(|row| pointer.y < text_draw_pos.y + row.rect.max.y)
+        .or(galley.rows.len().checked_sub(1))
+        .map(|index| lines[index])?;
+
+    let on_toggle = pointer.x >= rect.max.x - FOLD_WIDTH;
+    if on_toggle && fold_regions.iter().any(|region| region.start_line == line) {
+        Some(GutterClick::Fold(line))
+    } else {
+        Some(GutterClick::Bookmark(line))
+    }
+}
@@ -0,0 +1,227 @@
+//! Indentation editing for Zing text editor.
+//!
+//! Tab, Shift+Tab, Enter and Backspace in the editor go through here so they
+//! indent with the tab size and spaces or tabs of the file, as detected when
+//! it was opened or chosen in the status bar, or else as configured.
+
+use egui::{Context, Key, Modifiers};
+use std::ops::Range;
+
+use crate::buffer::{indent, IndentStyle, TextBuffer};
+use crate::ui::{editor, ZingApp};
+
+/// Tab sizes offered in the status bar.
+pub const TAB_SIZES: [usize; 4] = [2, 3, 4, 8];
+
+/// Returns the indentation style for the active buffer, taking what was
+/// detected or chosen for the buffer over the settings for its language, and
+/// those over the general configuration.
+pub fn style(app: &ZingApp) -> IndentStyle {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let indent = buffer_lock.indent.or(&app.config.language_indent(buffer_lock.language.id));
+    indent.resolve(app.config.use_spaces, app.config.tab_size)
+}
+
+/// Returns how the status bar describes `style`.
+pub fn label(style: IndentStyle) -> String {
+    if style.use_spaces {
+        format!("Spaces: {}", style.tab_size)
+    } else {
+        format!("Tab Size: {}", style.tab_size)
+    }
+}
+
+/// Makes the active buffer indent with spaces or tabs from now on.
+pub fn set_use_spaces(app: &mut ZingApp, use_spaces: bool) {
+    app.buffer().lock().unwrap().indent.use_spaces = Some(use_spaces);
+}
+
+/// Makes the active buffer use `tab_size` columns per level from now on.
+pub fn set_tab_size(app: &mut ZingApp, tab_size: usize) {
+    app.buffer().lock().unwrap().indent.tab_size = Some(tab_size);
+}
+
+/// Detects the indentation of the active buffer again from its content.
+pub fn detect(app: &mut ZingApp) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    buffer_lock.indent = indent::detect(&buffer_lock.content);
+    drop(buffer_lock);
+    let message = format!("Detected indentation: {}", label(style(app)));
+    app.set_status(message, 3.0);
+}
+
+/// Returns the selection clamped to the buffer.
+pub fn selection(app: &ZingApp, buffer: &TextBuffer) -> Range<usize> {
+    let len = buffer.len_chars();
+    app.selection.start.min(len)..app.selection.end.min(len)
+}
+
+/// Returns the lines `selection` touches, leaving out a last line the
+/// selection only reaches the start of.
+pub fn selected_lines(buffer: &TextBuffer, selection: &Range<usize>) -> Range<usize> {
+    let first = buffer.content.char_to_line(selection.start);
+    let mut last = buffer.content.char_to_line(selection.end);
+    if last > first && buffer.content.line_to_char(last) == selection.end {
+        last -= 1;
+    }
+    first..last + 1
+}
+
+/// Shows a failed edit in the status bar.
+fn report(app: &mut ZingApp, result: anyhow::Result<()>) {
+    if let Err(err) = result {
+        log::error!("Failed to change indentation: {}", err);
+        app.set_status(format!("Failed to change indentation: {}", err), 5.0);
+    }
+}
+
+/// Returns whether the editor leaves a key press to this module. Backspace is
+/// only taken when `backspace_indents`, as found by [`backspace_range`].
+pub fn takes_key(key: Key, modifiers: Modifiers, backspace_indents: bool) -> bool {
+    match key {
+        Key::Tab => modifiers.is_none() || modifiers.shift_only(),
+        Key::Enter => modifiers.is_none(),
+        Key::Backspace => backspace_indents && modifiers.is_none(),
+        _ => false,
+    }
+}
+
+/// Handles a key press taken by [`takes_key`].
+pub fn press_key(app: &mut ZingApp, ctx: &Context, key: Key, modifiers: Modifiers) {
+    match key {
+        Key::Tab if modifiers.shift => outdent(app, ctx),
+        Key::Tab => indent(app, ctx),
+        Key::Enter => newline(app, ctx),
+        Key::Backspace => backspace(app, ctx),
+        _ => {}
+    }
+}
+
+/// Indents the selected lines, or inserts indentation at the cursor when the
+/// selection is within one line.
+pub fn indent(app: &mut ZingApp, ctx: &Context) {
+    let style = style(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = selection(app, &buffer_lock);
+    let lines = selected_lines(&buffer_lock, &selection);
+
+    if lines.len() > 1 {
+        let result = buffer_lock.indent_lines(lines.clone(), style);
+        let range = buffer_lock.lines_range(&lines);
+        drop(buffer_lock);
+        report(app, result);
+        editor::set_selection(app, ctx, range);
+        return;
+    }
+
+    let line_start = buffer_lock.content.line_to_char(lines.start);
+    let before = buffer_lock.content.slice(line_start..selection.start).to_string();
+    let text = style.fill_to_next_stop(style.width(&before));
+    let result = buffer_lock.edit_group(|buffer| buffer.replace(selection.clone(), &text));
+    drop(buffer_lock);
+    report(app, result);
+    let cursor = selection.start + text.chars().count();
+    editor::set_selection(app, ctx, cursor..cursor);
+}
+
+/// Removes one level of indentation from the selected lines.
+pub fn outdent(app: &mut ZingApp, ctx: &Context) {
+    let style = style(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = selection(app, &buffer_lock);
+    let lines = selected_lines(&buffer_lock, &selection);
+    let line_start = buffer_lock.content.line_to_char(lines.start);
+    let removed = indent::outdent_len(&buffer_lock.content.line(lines.start).to_string(), style);
+
+    let result = buffer_lock.outdent_lines(lines.clone(), style);
+    let range = if lines.len() > 1 {
+        buffer_lock.lines_range(&lines)
+    } else {
+        // The cursor moves back with the text, but not past the start of the line
+        let shift = |pos: usize| line_start.max(pos.saturating_sub(removed));
+        shift(selection.start)..shift(selection.end)
+    };
+    drop(buffer_lock);
+    report(app, result);
+    editor::set_selection(app, ctx, range);
+}
+
+/// Starts a new line at the cursor, indented like the current one and one
+/// level deeper after an opening bracket or colon.
+pub fn newline(app: &mut ZingApp, ctx: &Context) {
+    let style = style(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let selection = selection(app, &buffer_lock);
+
+    let result = buffer_lock.edit_group(|buffer| {
+        buffer.replace(selection.clone(), "")?;
+        let (text, cursor) = indent::newline(&buffer.content, selection.start, style);
+        buffer.insert(selection.start, &text)?;
+        Ok(cursor)
+    });
+    drop(buffer_lock);
+    match result {
+        Ok(cursor) => editor::set_selection(app, ctx, selection.start + cursor..selection.start + cursor),
+        Err(err) => report(app, Err(err)),
+    }
+}
+
+/// Returns the range Backspace would remove as a whole indentation level, if
+/// the cursor sits in indentation made of spaces.
+pub fn backspace_range(app: &ZingApp) -> Option<Range<usize>> {
+    if !app.selection.is_empty() {
+        return None;
+    }
+    let style = style(app);
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    indent::backspace_range(&buffer_lock.content, app.selection.start, style)
+}
+
+/// Removes the indentation level before the cursor.
+pub fn backspace(app: &mut ZingApp, ctx: &Context) {
+    let Some(range) = backspace_range(app) else { return };
+    let buffer = app.buffer();
+    let result = buffer.lock().unwrap().remove(range.start, range.end);
+    report(app, result);
+    editor::set_selection(app, ctx, range.start..range.start);
+}
+
+/// Rewrites the indentation of the whole active buffer from its current
+/// style to `to`, which the buffer then keeps using.
+fn reindent(app: &mut ZingApp, ctx: &Context, to: IndentStyle, done: &str) {
+    let from = style(app);
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let result = buffer_lock.convert_indentation(from, to);
+    buffer_lock.indent.use_spaces = Some(to.use_spaces);
+    buffer_lock.indent.tab_size = Some(to.tab_size);
+    let len = buffer_lock.len_chars();
+    drop(buffer_lock);
+    match result {
+        Ok(0) => app.set_status(format!("Indentation already uses {}", done), 3.0),
+        Ok(lines) => {
+            app.set_status(format!("Converted indentation of {} lines to {}", lines, done), 3.0);
+            let cursor = app.cursor_pos.min(len);
+            editor::set_selection(app, ctx, cursor..cursor);
+        }
+        Err(err) => report(app, Err(err)),
+    }
+}
+
+/// Rewrites the indentation of the whole active buffer with spaces or tabs.
+pub fn convert(app: &mut ZingApp, ctx: &Context, to_spaces: bool) {
+    let to = IndentStyle::new(to_spaces, style(app).tab_size);
+    reindent(app, ctx, to, if to_spaces { "spaces" } else { "tabs" });
+}
+
+/// Re-indents the whole active buffer to `tab_size` columns per level.
+pub fn change_width(app: &mut ZingApp, ctx: &Context, tab_size: usize) {
+    let to = IndentStyle::new(style(app).use_spaces, tab_size);
+    reindent(app, ctx, to, &format!("a width of {}", tab_size));
+}
@@ -0,0 +1,116 @@
+//! Line editing commands for Zing text editor.
+//!
+//! These act on every line the selection touches, or on the line holding the
+//! cursor, and each undoes in one step.
+
+use egui::Context;
+use std::ops::Range;
+
+use crate::buffer::TextBuffer;
+use crate::ui::{editor, indentation, ZingApp};
+
+/// Returns the selection and the lines it touches in the active buffer.
+fn selected(app: &ZingApp, buffer: &TextBuffer) -> (Range<usize>, Range<usize>) {
+    let selection = indentation::selection(app, buffer);
+    let lines = indentation::selected_lines(buffer, &selection);
+    (selection, lines)
+}
+
+/// Shows a failed edit in the status bar.
+fn report<T>(app: &mut ZingApp, result: anyhow::Result<T>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log::error!("Failed to edit lines: {}", err);
+            app.set_status(format!("Failed to edit lines: {}", err), 5.0);
+            None
+        }
+    }
+}
+
+/// Moves the selected lines one line up or down, keeping them selected.
+pub fn move_lines(app: &mut ZingApp, ctx: &Context, up: bool) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (selection, lines) = selected(app, &buffer_lock);
+    let result = buffer_lock.move_lines(lines, up);
+    drop(buffer_lock);
+    if let Some(Some(shift)) = report(app, result) {
+        let shift = |pos: usize| (pos as isize + shift) as usize;
+        editor::set_selection(app, ctx, shift(selection.start)..shift(selection.end));
+    }
+}
+
+/// Duplicates the selected text, or the current lines when nothing is
+/// selected, and moves the selection to the copy.
+pub fn duplicate(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (selection, lines) = selected(app, &buffer_lock);
+    if !selection.is_empty() {
+        let text = buffer_lock.content.slice(selection.clone()).to_string();
+        let result = buffer_lock.insert(selection.end, &text);
+        drop(buffer_lock);
+        if report(app, result).is_some() {
+            editor::set_selection(app, ctx, selection.end..selection.end + selection.len());
+        }
+        return;
+    }
+    let result = buffer_lock.duplicate_lines(lines);
+    drop(buffer_lock);
+    if let Some(added) = report(app, result) {
+        editor::set_selection(app, ctx, selection.start + added..selection.start + added);
+    }
+}
+
+/// Deletes the selected lines.
+pub fn delete(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (_, lines) = selected(app, &buffer_lock);
+    let result = buffer_lock.delete_lines(lines);
+    drop(buffer_lock);
+    if let Some(cursor) = report(app, result) {
+        editor::set_selection(app, ctx, cursor..cursor);
+    }
+}
+
+/// Joins the selected lines, or the current line with the next one.
+pub fn join(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (_, lines) = selected(app, &buffer_lock);
+    let result = buffer_lock.join_lines(lines);
+    drop(buffer_lock);
+    if let Some(Some(cursor)) = report(app, result) {
+        editor::set_selection(app, ctx, cursor..cursor);
+    }
+}
+
+/// Starts a new line above or below the current one, wherever the cursor is
+/// on it.
+pub fn insert_line(app: &mut ZingApp, ctx: &Context, below: bool) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (_, lines) = selected(app, &buffer_lock);
+    let line = if below { lines.end - 1 } else { lines.start };
+    let result = buffer_lock.insert_line(line, below);
+    drop(buffer_lock);
+    if let Some(cursor) = report(app, result) {
+        editor::set_selection(app, ctx, cursor..cursor);
+    }
+}
+
+/// Rewrites the selected lines, or every line when the selection is within
+/// one line, with `transform`, and selects the result.
+pub fn transform(app: &mut ZingApp, ctx: &Context, transform: impl FnOnce(Vec<String>) -> Vec<String>) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let (_, lines) = selected(app, &buffer_lock);
+    let lines = if lines.len() > 1 { lines } else { 0..buffer_lock.text_lines() };
+    let result = buffer_lock.transform_lines(lines, transform);
+    drop(buffer_lock);
+    if let Some(range) = report(app, result) {
+        editor::set_selection(app, ctx, range);
+    }
+}
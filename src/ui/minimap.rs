@@ -0,0 +1,230 @@
+//! Minimap for Zing text editor.
+//!
+//! Draws a scaled-down overview of the whole buffer at the right edge of the
+//! editor, with the visible part of the text as a box that can be dragged to
+//! scroll. Large files are sampled so only as many lines as there are rows in
+//! the minimap get highlighted.
+
+use egui::{Color32, Pos2, Rect, Rounding, Sense, Stroke, Ui};
+use std::sync::{Arc, Mutex, Weak};
+use syntect::easy::HighlightLines;
+
+use crate::buffer::TextBuffer;
+use crate::config::Theme;
+use crate::syntax;
+use crate::ui::ZingApp;
+
+/// Width of the minimap column.
+pub const WIDTH: f32 = 90.0;
+/// Height of a row, each showing one sampled line.
+const ROW_HEIGHT: f32 = 3.0;
+/// Width of a character.
+const CHAR_WIDTH: f32 = 1.0;
+/// Number of characters of a line that are drawn.
+const MAX_COLUMNS: usize = 80;
+/// Space between the left edge and the text.
+const PADDING: f32 = 6.0;
+/// Smallest height of the viewport box, so it stays visible in huge files.
+const MIN_VIEWPORT_HEIGHT: f32 = 6.0;
+
+/// A run of characters drawn as one block.
+#[derive(Debug, Clone)]
+struct Block {
+    /// Column the run starts at
+    column: usize,
+    /// Number of characters in the run
+    len: usize,
+    /// Colour of the run
+    color: Color32,
+}
+
+/// The highlighted rows of a buffer, kept until the buffer changes.
+#[derive(Debug)]
+struct Overview {
+    /// The buffer the rows were built from
+    buffer: Weak<Mutex<TextBuffer>>,
+    /// Revision of the buffer the rows were built from
+    revision: u64,
+    /// Number of buffer lines each row stands for
+    step: usize,
+    /// Theme the rows were highlighted with
+    theme: Theme,
+    /// Id of the language the rows were highlighted as
+    language: &'static str,
+    /// Blocks of every row
+    rows: Vec<Vec<Block>>,
+}
+
+impl Overview {
+    /// Returns whether the rows still show `buffer` as it is now.
+    fn is_current(&self, buffer: &Arc<Mutex<TextBuffer>>, buffer_lock: &TextBuffer, step: usize, theme: Theme) -> bool {
+        Weak::ptr_eq(&self.buffer, &Arc::downgrade(buffer))
+            && self.revision == buffer_lock.revision()
+            && self.language == buffer_lock.language.id
+            && self.step == step
+            && self.theme == theme
+    }
+}
+
+/// State of the minimap kept between frames.
+#[derive(Debug, Default)]
+pub struct Minimap {
+    /// Rows drawn for the active buffer
+    overview: Option<Overview>,
+    /// Distance from the top of the viewport box to the pointer while dragging
+    drag_offset: Option<f32>,
+}
+
+impl Minimap {
+    /// Creates an empty minimap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Splits highlighted text into blocks of non-whitespace characters.
+fn blocks(ranges: &[(syntect::highlighting::Style, &str)]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut column = 0;
+    for (style, text) in ranges {
+        let color = Color32::from_rgba_unmultiplied(style.foreground.r, style.foreground.g, style.foreground.b, 170);
+        for c in text.chars() {
+            match c {
+                '\n' | '\r' => break,
+                '\t' => column += 4 - column % 4,
+                c if c.is_whitespace() => column += 1,
+                _ => {
+                    match blocks.last_mut() {
+                        Some(Block { column: start, len, color: last }) if *start + *len == column && *last == color => {
+                            *len += 1;
+                        }
+                        _ => blocks.push(Block { column, len: 1, color }),
+                    }
+                    column += 1;
+                }
+            }
+            if column >= MAX_COLUMNS {
+                return blocks;
+            }
+        }
+    }
+    blocks
+}
+
+/// Highlights every `step`th line of `buffer_lock`.
+fn build_rows(buffer_lock: &TextBuffer, step: usize, theme: Theme) -> Vec<Vec<Block>> {
+    let definition = syntax::find_syntax(buffer_lock.language.syntax);
+    let mut highlighter = HighlightLines::new(definition, syntax::highlighting_theme(theme));
+
+    (0..buffer_lock.len_lines())
+        .step_by(step)
+        .map(|line| {
+            let mut text: String = buffer_lock.content.line(line).chars().take(MAX_COLUMNS).collect();
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            highlighter
+                .highlight_line(&text, syntax::syntax_set())
+                .map(|ranges| blocks(&ranges))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Draws the minimap filling `ui` and scrolls the editor when it is clicked or
+/// dragged.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    let rect = ui.max_rect();
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (bg_color, viewport_color, changed_color, bookmark_color) = if is_dark {
+        (
+            Color32::from_rgb(16, 16, 22),
+            Color32::from_white_alpha(24),
+            Color32::from_rgb(80, 160, 100),
+            Color32::from_rgb(86, 156, 255),
+        )
+    } else {
+        (
+            Color32::from_rgb(244, 244, 248),
+            Color32::from_black_alpha(20),
+            Color32::from_rgb(60, 150, 80),
+            Color32::from_rgb(0, 120, 215),
+        )
+    };
+
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let line_count = buffer_lock.len_lines();
+    let rows_fit = ((rect.height() / ROW_HEIGHT) as usize).max(1);
+    let step = line_count.div_ceil(rows_fit).max(1);
+
+    let theme = app.config.theme;
+    let current = app.minimap.overview.as_ref()
+        .is_some_and(|overview| overview.is_current(&buffer, &buffer_lock, step, theme));
+    if !current {
+        app.minimap.overview = Some(Overview {
+            buffer: Arc::downgrade(&buffer),
+            revision: buffer_lock.revision(),
+            step,
+            theme,
+            language: buffer_lock.language.id,
+            rows: build_rows(&buffer_lock, step, theme),
+        });
+    }
+    let changed: Vec<usize> = buffer_lock.changed_lines().collect();
+    let bookmarked: Vec<usize> = buffer_lock.bookmarks().into_iter().map(|(line, _)| line).collect();
+    drop(buffer_lock);
+
+    let row_top = |line: usize| rect.top() + (line / step) as f32 * ROW_HEIGHT;
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, Rounding::ZERO, bg_color);
+    painter.line_segment([rect.left_top(), rect.left_bottom()], Stroke::new(1.0, viewport_color));
+
+    if let Some(overview) = &app.minimap.overview {
+        for (row, blocks) in overview.rows.iter().enumerate() {
+            let top = rect.top() + row as f32 * ROW_HEIGHT;
+            for block in blocks {
+                let min = Pos2::new(rect.left() + PADDING + block.column as f32 * CHAR_WIDTH, top);
+                let size = egui::vec2(block.len as f32 * CHAR_WIDTH, ROW_HEIGHT - 1.0);
+                painter.rect_filled(Rect::from_min_size(min, size), Rounding::ZERO, block.color);
+            }
+        }
+    }
+
+    // Changed lines on the left edge, bookmarks on the right
+    for line in changed {
+        let marker = Rect::from_min_size(Pos2::new(rect.left() + 1.0, row_top(line)), egui::vec2(2.0, ROW_HEIGHT));
+        painter.rect_filled(marker, Rounding::ZERO, changed_color);
+    }
+    for line in bookmarked {
+        let marker = Rect::from_min_size(Pos2::new(rect.right() - 4.0, row_top(line)), egui::vec2(4.0, ROW_HEIGHT));
+        painter.rect_filled(marker, Rounding::ZERO, bookmark_color);
+    }
+
+    // The part of the buffer shown in the editor
+    let visible = app.visible_lines.clone();
+    let viewport_top = row_top(visible.start);
+    let viewport_height = (visible.len() as f32 / step as f32 * ROW_HEIGHT).max(MIN_VIEWPORT_HEIGHT);
+    let viewport = Rect::from_min_size(Pos2::new(rect.left(), viewport_top), egui::vec2(rect.width(), viewport_height));
+    painter.rect_filled(viewport, Rounding::ZERO, viewport_color);
+
+    let response = ui.interact(rect, ui.id().with("minimap"), Sense::click_and_drag());
+    if response.drag_released() {
+        app.minimap.drag_offset = None;
+    }
+    let Some(pointer) = response.interact_pointer_pos() else { return };
+    if !(response.clicked() || response.dragged()) {
+        return;
+    }
+
+    // Dragging the box keeps the grabbed point under the pointer, anywhere else
+    // centres the box on the pointer
+    let offset = *app.minimap.drag_offset.get_or_insert_with(|| {
+        if viewport.contains(pointer) { pointer.y - viewport.top() } else { viewport_height / 2.0 }
+    });
+    if response.clicked() {
+        app.minimap.drag_offset = None;
+    }
+    let row = ((pointer.y - offset - rect.top()) / ROW_HEIGHT).max(0.0) as usize;
+    app.scroll_to_line = Some((row * step).min(line_count.saturating_sub(1)));
+}
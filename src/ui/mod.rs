@@ -2,22 +2,72 @@
 //!
 //! This module provides the user interface components for the editor.
 
+pub mod autosave;
+pub mod bookmarks;
+pub mod brackets;
+pub mod clipboard_history;
+pub mod command_palette;
+pub mod commands;
+pub mod comments;
 pub mod editor;
+pub mod file_drop;
+pub mod file_tree;
+pub mod folding;
+pub mod go_to_line;
+pub mod guides;
+pub mod gutter;
+pub mod indentation;
+pub mod line_editing;
+pub mod minimap;
+pub mod navigation;
+pub mod panes;
+pub mod quick_open;
+pub mod recent;
 pub mod statusbar;
+pub mod tab_switcher;
 pub mod toolbar;
 pub mod tabs;
+pub mod transforms;
+pub mod welcome;
 
 use editor::EditorView;
 use toolbar::Toolbar;
 use statusbar::StatusBar;
 use tabs::TabsView;
+use command_palette::CommandPalette;
+use quick_open::QuickOpen;
+use file_tree::FileTree;
+use clipboard_history::ClipboardHistory;
+use go_to_line::GoToLine;
+use navigation::NavigationHistory;
+use tab_switcher::TabSwitcher;
+use autosave::AutoSaveState;
+use bookmarks::BookmarkPrompt;
+use minimap::Minimap;
+use panes::Panes;
 
 use egui::{Context, Ui, Vec2, Rounding, Color32, Stroke};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use crate::buffer::TextBuffer;
+use crate::clipboard::ClipboardRing;
 use crate::config::{EditorConfig, Theme};
+use crate::session::Session;
+use crate::workspace::Workspace;
+
+/// The sections that can be shown in the left sidebar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidebarSection {
+    /// The list of open tabs
+    Tabs,
+    /// The workspace file tree
+    Files,
+    /// The bookmarks of all open files
+    Bookmarks,
+}
 
 /// Main application state.
 #[derive(Debug)]
@@ -30,6 +80,14 @@ pub struct ZingApp {
     pub cursor_line: usize,
     /// Cursor column position (0-indexed)
     pub cursor_column: usize,
+    /// Selected character range in the editor, empty when nothing is selected
+    pub selection: Range<usize>,
+    /// Character index the editor should move the cursor to and scroll into view
+    pub pending_jump: Option<usize>,
+    /// Line the editor should scroll to the top of the view, keeping the cursor
+    pub scroll_to_line: Option<usize>,
+    /// Buffer lines shown in the editor during the last frame
+    pub visible_lines: Range<usize>,
     /// Whether a file dialog is open
     pub file_dialog_open: bool,
     /// Status message to display
@@ -40,6 +98,41 @@ pub struct ZingApp {
     pub tabs: TabsView,
     /// Flag to track if the user has been warned about closing the last tab
     pub last_tab_close_warning: bool,
+    /// The open workspace folder
+    pub workspace: Workspace,
+    /// Quick open overlay
+    pub quick_open: QuickOpen,
+    /// Command palette overlay
+    pub command_palette: CommandPalette,
+    /// Go to line overlay
+    pub go_to_line: GoToLine,
+    /// Back and forward history of cursor jumps
+    pub navigation: NavigationHistory,
+    /// File tree of the workspace folder
+    pub file_tree: FileTree,
+    /// Which section the sidebar is showing
+    pub sidebar_section: SidebarSection,
+    /// Text copied or cut in the editor, most recent first
+    pub clipboard: ClipboardRing,
+    /// Clipboard history picker
+    pub clipboard_history: ClipboardHistory,
+    /// Prompt for naming a bookmark
+    pub bookmark_prompt: BookmarkPrompt,
+    /// Overview of the buffer beside the editor
+    pub minimap: Minimap,
+    /// Split editor panes, other than the state of the focused one above
+    pub panes: Panes,
+    /// Popup for switching between recently used tabs
+    pub tab_switcher: TabSwitcher,
+    /// State saved between runs
+    pub session: Session,
+    /// Whether the welcome screen is shown while nothing is open
+    pub show_welcome: bool,
+    /// Autosave state
+    pub autosave: AutoSaveState,
+    /// Buffer whose file changed on disk, waiting for the user to confirm
+    /// overwriting it
+    pub pending_overwrite: Option<Arc<Mutex<TextBuffer>>>,
 }
 
 impl ZingApp {
@@ -48,33 +141,150 @@ impl ZingApp {
         let config = EditorConfig::default();
         config.apply_to_context(ctx);
         
-        Self {
+        let mut app = Self {
             config,
             cursor_pos: 0,
             cursor_line: 0,
             cursor_column: 0,
+            selection: 0..0,
+            pending_jump: None,
+            scroll_to_line: None,
+            visible_lines: 0..0,
             file_dialog_open: false,
             status_message: None,
             status_timeout: 5.0,
             tabs: TabsView::new(),
             last_tab_close_warning: false,
-        }
+            workspace: Workspace::new(),
+            quick_open: QuickOpen::new(),
+            command_palette: CommandPalette::new(),
+            go_to_line: GoToLine::new(),
+            navigation: NavigationHistory::new(),
+            file_tree: FileTree::new(),
+            sidebar_section: SidebarSection::Tabs,
+            clipboard: ClipboardRing::new(),
+            clipboard_history: ClipboardHistory::new(),
+            bookmark_prompt: BookmarkPrompt::new(),
+            minimap: Minimap::new(),
+            panes: Panes::new(),
+            tab_switcher: TabSwitcher::new(),
+            session: Session::load(),
+            show_welcome: true,
+            autosave: AutoSaveState::new(),
+            pending_overwrite: None,
+        };
+        app.session.prune_recent();
+        panes::restore(&mut app);
+        app
     }
     
     /// Sets the current buffer.
+    ///
+    /// A file that is already open is shown in its existing tab instead.
     pub fn set_buffer(&mut self, buffer: TextBuffer) {
-        let title = buffer.file_path.as_ref()
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .unwrap_or("Untitled")
-            .to_string();
-        
-        let tab = tabs::Tab::with_buffer(title, buffer.file_path.clone(), buffer);
+        if let Some(path) = buffer.file_path.clone() {
+            if self.focus_open_file(&path) {
+                self.note_recent_file(path);
+                return;
+            }
+        }
+        let tab = self.tab_for(buffer);
         self.tabs.tabs.push(tab);
         self.tabs.active_tab = self.tabs.tabs.len() - 1;
         self.cursor_pos = 0;
     }
     
+    /// Creates a tab for `buffer`, restoring its bookmarks and folds from the session.
+    pub fn tab_for(&mut self, mut buffer: TextBuffer) -> tabs::Tab {
+        if let Some(path) = buffer.file_path.clone() {
+            for (line, name) in self.session.bookmarks_for(&path) {
+                buffer.set_bookmark(line, name).ok();
+            }
+            for line in self.session.folds_for(&path) {
+                buffer.fold(line).ok();
+            }
+            self.note_recent_file(path);
+        }
+        
+        tabs::Tab::with_buffer(buffer)
+    }
+    
+    /// Makes the tab showing `path` active, returning false if the file is not open.
+    ///
+    /// A file only open in another pane gets a tab here sharing its buffer.
+    pub fn focus_open_file(&mut self, path: &Path) -> bool {
+        if let Some(index) = self.tabs.tabs.iter().position(|tab| tab.shows_file(path)) {
+            self.tabs.active_tab = index;
+            return true;
+        }
+        
+        let shared = self.panes.other_tabs()
+            .flat_map(|tabs| tabs.tabs.iter())
+            .find(|tab| tab.shows_file(path))
+            .map(|tab| tab.buffer.clone());
+        match shared {
+            Some(buffer) => {
+                self.tabs.tabs.push(tabs::Tab::sharing(buffer));
+                self.tabs.active_tab = self.tabs.tabs.len() - 1;
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Makes `root` the workspace folder and shows it in the sidebar.
+    pub fn open_workspace(&mut self, root: PathBuf) {
+        self.session.note_recent_folder(root.clone());
+        self.workspace.set_root(root);
+        self.file_tree.reset();
+        self.sidebar_section = SidebarSection::Files;
+    }
+    
+    /// Updates open tabs after `from` was renamed or moved to `to`.
+    ///
+    /// Works for folders too, retargeting every tab whose file lives inside it.
+    pub fn retarget_paths(&mut self, from: &Path, to: &Path) {
+        let other_tabs = self.panes.other_tabs().flat_map(|tabs| tabs.tabs.iter());
+        for tab in self.tabs.tabs.iter().chain(other_tabs) {
+            let mut buffer = tab.buffer.lock().unwrap();
+            let new_path = buffer.file_path.as_ref()
+                .and_then(|path| path.strip_prefix(from).ok())
+                .map(|rest| if rest.as_os_str().is_empty() { to.to_path_buf() } else { to.join(rest) });
+            
+            if let Some(new_path) = new_path {
+                crate::file_io::set_path(&mut buffer, new_path);
+            }
+        }
+    }
+    
+    /// Moves `path` to the front of the recently opened files list.
+    pub fn note_recent_file(&mut self, path: PathBuf) {
+        self.session.note_recent_file(path);
+    }
+    
+    /// Stores the bookmarks and folds of the open files and the layout of the
+    /// panes in the session and writes it to disk.
+    pub fn save_session(&mut self) {
+        let other_tabs = self.panes.other_tabs().flat_map(|tabs| tabs.tabs.iter());
+        for tab in self.tabs.tabs.iter().chain(other_tabs) {
+            let buffer = tab.buffer.lock().unwrap();
+            if let Some(path) = &buffer.file_path {
+                let bookmarks = buffer
+                    .bookmarks()
+                    .into_iter()
+                    .map(|(line, name)| (line, name.map(str::to_string)))
+                    .collect();
+                self.session.set_bookmarks_for(path, bookmarks);
+                self.session.set_folds_for(path, buffer.folded_lines());
+            }
+        }
+        self.session.layout = Some(panes::saved_layout(self));
+        
+        if let Err(err) = self.session.save() {
+            log::error!("Failed to save session: {:#}", err);
+        }
+    }
+    
     /// Gets a reference to the current buffer.
     pub fn buffer(&self) -> Arc<Mutex<TextBuffer>> {
         self.tabs.active_buffer()
@@ -101,6 +311,24 @@ impl ZingApp {
     }
 }
 
+/// Draws the buttons that switch between the sidebar sections.
+fn sidebar_switcher(app: &mut ZingApp, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        ui.add_space(4.0);
+        let sections = [
+            (SidebarSection::Tabs, "Tabs"),
+            (SidebarSection::Files, "Files"),
+            (SidebarSection::Bookmarks, "Bookmarks"),
+        ];
+        for (section, label) in sections {
+            let text = egui::RichText::new(label).size(11.0);
+            if ui.selectable_label(app.sidebar_section == section, text).clicked() {
+                app.sidebar_section = section;
+            }
+        }
+    });
+}
+
 /// The main application UI.
 pub fn ui(app: &mut ZingApp, ctx: &Context) {
     // Set up the main panel with proper styling
@@ -124,7 +352,10 @@ pub fn ui(app: &mut ZingApp, ctx: &Context) {
             // Define fixed heights for status bar and toolbar (if needed)
             let statusbar_height = 24.0;
             let toolbar_height = 24.0;
-            let tabs_width = 140.0;
+            let tabs_width = match app.sidebar_section {
+                SidebarSection::Tabs => 160.0,
+                SidebarSection::Files | SidebarSection::Bookmarks => 220.0,
+            };
             
             // Determine if we need to show the toolbar (only on non-macOS platforms)
             #[cfg(not(target_os = "macos"))]
@@ -148,7 +379,16 @@ pub fn ui(app: &mut ZingApp, ctx: &Context) {
                     ui.set_min_width(tabs_width);
                     ui.set_max_width(tabs_width);
                     ui.set_min_height(total_size.y);
-                    app.tabs.ui(ui, app.config.theme);
+                    sidebar_switcher(app, ui);
+                    match app.sidebar_section {
+                        SidebarSection::Tabs => {
+                            if let Some(action) = app.tabs.ui(ui, app.config.theme) {
+                                tabs::handle(app, action);
+                            }
+                        }
+                        SidebarSection::Files => file_tree::ui(app, ui),
+                        SidebarSection::Bookmarks => bookmarks::ui(app, ui),
+                    }
                 });
                 
                 // Vertical separator
@@ -177,9 +417,13 @@ pub fn ui(app: &mut ZingApp, ctx: &Context) {
                         egui::Sense::hover()
                     );
                     
-                    // Create a child UI for the editor with the allocated rectangle
+                    // Create a child UI for the editor panes with the allocated rectangle
                     let mut child_ui = ui.child_ui(editor_rect.rect, egui::Layout::default());
-                    editor::ui(app, &mut child_ui);
+                    if welcome::is_shown(app) {
+                        welcome::ui(app, &mut child_ui);
+                    } else {
+                        panes::ui(app, &mut child_ui);
+                    }
                     
                     // Horizontal separator
                     ui.add(egui::Separator::default().horizontal().spacing(1.0));
@@ -193,4 +437,19 @@ pub fn ui(app: &mut ZingApp, ctx: &Context) {
                 });
             });
         });
+    
+    // Overlays drawn on top of the editor
+    quick_open::ui(app, ctx);
+    command_palette::ui(app, ctx);
+    clipboard_history::ui(app, ctx);
+    go_to_line::ui(app, ctx);
+    tab_switcher::ui(app, ctx);
+    bookmarks::prompt_ui(app, ctx);
+    file_drop::ui(app, ctx);
+    editor::overwrite_confirmation(app, ctx);
+    
+    // Remember the position left behind when the active tab changes
+    navigation::track(app);
+    app.tabs.note_active();
+    autosave::update(app, ctx);
 } 
\ No newline at end of file
@@ -0,0 +1,269 @@
+//! Navigation history for Zing text editor.
+//!
+//! Remembers where the cursor was before jumps and tab switches so the user can
+//! go back and forward through them, like in a web browser, and where the last
+//! edit was made.
+
+use egui::{Key, Modifiers};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::buffer::{AnchorId, ChangeEvent, Gravity, TextBuffer};
+use crate::ui::ZingApp;
+
+/// Maximum number of locations kept in each direction.
+const MAX_HISTORY: usize = 100;
+
+/// Shortcut going back: Ctrl+- on macOS, where Alt+Left moves by word, and
+/// Alt+Left elsewhere.
+pub const BACK_SHORTCUT: (Modifiers, Key) = if cfg!(target_os = "macos") {
+    (Modifiers::CTRL, Key::Minus)
+} else {
+    (Modifiers::ALT, Key::ArrowLeft)
+};
+
+/// Shortcut going forward: Ctrl+Shift+- on macOS and Alt+Right elsewhere.
+pub const FORWARD_SHORTCUT: (Modifiers, Key) = if cfg!(target_os = "macos") {
+    (Modifiers::CTRL.plus(Modifiers::SHIFT), Key::Minus)
+} else {
+    (Modifiers::ALT, Key::ArrowRight)
+};
+
+/// Names of [`BACK_SHORTCUT`] and [`FORWARD_SHORTCUT`] as shown in menus and tooltips.
+pub const SHORTCUT_LABELS: (&str, &str) = if cfg!(target_os = "macos") {
+    ("⌃-", "⌃⇧-")
+} else {
+    ("Alt+Left", "Alt+Right")
+};
+
+/// A cursor position in an open buffer, at the time it was seen.
+#[derive(Debug, Clone)]
+struct Cursor {
+    /// The buffer the position is in
+    buffer: Weak<Mutex<TextBuffer>>,
+    /// Character index of the cursor
+    position: usize,
+}
+
+impl Cursor {
+    /// Returns whether the cursor is in `buffer`.
+    fn is_in(&self, buffer: &Arc<Mutex<TextBuffer>>) -> bool {
+        Weak::ptr_eq(&self.buffer, &Arc::downgrade(buffer))
+    }
+}
+
+/// A remembered position, anchored so it follows later edits.
+#[derive(Debug)]
+struct Location {
+    /// The buffer the position is in, gone once its tab is closed
+    buffer: Weak<Mutex<TextBuffer>>,
+    /// Anchor at the position in the buffer
+    anchor: AnchorId,
+}
+
+impl Location {
+    /// Anchors `cursor` in its buffer, or returns `None` if the buffer is gone.
+    fn anchor(cursor: &Cursor) -> Option<Self> {
+        let buffer = cursor.buffer.upgrade()?;
+        let mut buffer_lock = buffer.lock().unwrap();
+        let position = cursor.position.min(buffer_lock.len_chars());
+        let anchor = buffer_lock.create_anchor(position, Gravity::Left).ok()?;
+        Some(Self {
+            buffer: cursor.buffer.clone(),
+            anchor,
+        })
+    }
+
+    /// Returns the current position of the location, if its buffer is still open.
+    fn resolve(&self) -> Option<(Arc<Mutex<TextBuffer>>, usize)> {
+        let buffer = self.buffer.upgrade()?;
+        let position = buffer.lock().unwrap().anchor_position(self.anchor)?;
+        Some((buffer, position))
+    }
+
+    /// Returns whether the location is where `cursor` is.
+    fn is_at(&self, cursor: &Cursor) -> bool {
+        Weak::ptr_eq(&self.buffer, &cursor.buffer)
+            && self.resolve().is_some_and(|(_, position)| position == cursor.position)
+    }
+
+    /// Stops tracking the location in its buffer.
+    fn release(self) {
+        if let Some(buffer) = self.buffer.upgrade() {
+            buffer.lock().unwrap().remove_anchor(self.anchor);
+        }
+    }
+}
+
+/// Back and forward stacks of visited locations.
+#[derive(Debug, Default)]
+pub struct NavigationHistory {
+    /// Locations to go back to, most recent last
+    back: Vec<Location>,
+    /// Locations to go forward to, most recent last
+    forward: Vec<Location>,
+    /// Where the cursor was at the end of the last frame
+    current: Option<Cursor>,
+    /// Change events of the open buffers
+    subscriptions: Vec<(Weak<Mutex<TextBuffer>>, Receiver<ChangeEvent>)>,
+    /// Where the most recent edit in any buffer ended
+    last_edit: Option<Location>,
+}
+
+impl NavigationHistory {
+    /// Creates an empty navigation history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers `cursor` as a place to come back to, dropping the forward history.
+    fn push(&mut self, cursor: &Cursor) {
+        if self.back.last().is_some_and(|last| last.is_at(cursor)) {
+            return;
+        }
+        let Some(location) = Location::anchor(cursor) else { return };
+
+        self.back.push(location);
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0).release();
+        }
+        for location in self.forward.drain(..) {
+            location.release();
+        }
+    }
+}
+
+/// Returns the cursor of the active tab.
+fn here(app: &ZingApp) -> Cursor {
+    Cursor {
+        buffer: Arc::downgrade(&app.buffer()),
+        position: app.cursor_pos,
+    }
+}
+
+/// Subscribes to the buffers of new tabs and remembers where the latest edit
+/// among them ended.
+fn watch_edits(app: &mut ZingApp) {
+    let history = &mut app.navigation;
+    history.subscriptions.retain(|(buffer, _)| buffer.strong_count() > 0);
+    for tab in &app.tabs.tabs {
+        let buffer = Arc::downgrade(&tab.buffer);
+        if !history.subscriptions.iter().any(|(watched, _)| Weak::ptr_eq(watched, &buffer)) {
+            let events = tab.buffer.lock().unwrap().subscribe();
+            history.subscriptions.push((buffer, events));
+        }
+    }
+
+    let mut latest = None;
+    for (buffer, events) in &history.subscriptions {
+        if let Some(event) = events.try_iter().last() {
+            latest = Some(Cursor {
+                buffer: buffer.clone(),
+                position: event.new_end(),
+            });
+        }
+    }
+    if let Some(location) = latest.as_ref().and_then(Location::anchor) {
+        if let Some(previous) = history.last_edit.replace(location) {
+            previous.release();
+        }
+    }
+}
+
+/// Records a tab switch, called once per frame after the editor has run.
+///
+/// Whatever changed the active tab, the position the user left behind in the
+/// previous tab becomes a place to go back to.
+pub fn track(app: &mut ZingApp) {
+    watch_edits(app);
+    let buffer = app.buffer();
+    if let Some(previous) = app.navigation.current.take() {
+        if !previous.is_in(&buffer) {
+            app.navigation.push(&previous);
+        }
+    }
+    app.navigation.current = Some(here(app));
+}
+
+/// Records the current cursor position before a jump elsewhere.
+pub fn record_jump(app: &mut ZingApp) {
+    let buffer = app.buffer();
+    match app.navigation.current.take() {
+        // A new tab was opened for the jump, so the previous tab is where we came from
+        Some(previous) if !previous.is_in(&buffer) => app.navigation.push(&previous),
+        _ => app.navigation.push(&here(app)),
+    }
+    app.navigation.current = Some(here(app));
+}
+
+/// Activates the tab of `location` and moves the cursor there.
+///
+/// Returns false if the tab has been closed since.
+fn restore(app: &mut ZingApp, location: &Location) -> bool {
+    let Some((buffer, position)) = location.resolve() else { return false };
+    let Some(index) = app.tabs.tabs.iter().position(|tab| Arc::ptr_eq(&tab.buffer, &buffer)) else {
+        return false;
+    };
+
+    app.tabs.active_tab = index;
+    app.pending_jump = Some(position);
+    app.cursor_pos = position;
+    // Moving through the history must not record the switch as a new jump
+    app.navigation.current = Some(here(app));
+    true
+}
+
+/// Moves to the most recent usable location popped from the back or forward
+/// stack, pushing the current position onto the other one.
+fn travel(app: &mut ZingApp, backwards: bool) -> bool {
+    track(app);
+    let here = here(app);
+    loop {
+        let stack = if backwards { &mut app.navigation.back } else { &mut app.navigation.forward };
+        let Some(location) = stack.pop() else { return false };
+
+        if location.is_at(&here) || !restore(app, &location) {
+            location.release();
+            continue;
+        }
+        location.release();
+
+        if let Some(from) = Location::anchor(&here) {
+            let other = if backwards { &mut app.navigation.forward } else { &mut app.navigation.back };
+            other.push(from);
+        }
+        return true;
+    }
+}
+
+/// Moves the cursor to where the last edit was made, in whichever tab.
+pub fn go_to_last_edit(app: &mut ZingApp) {
+    watch_edits(app);
+    let Some(location) = app.navigation.last_edit.take() else {
+        app.set_status("No edits to go to".to_string(), 2.0);
+        return;
+    };
+
+    if location.resolve().is_some() {
+        record_jump(app);
+    }
+    let found = restore(app, &location);
+    app.navigation.last_edit = Some(location);
+    if !found {
+        app.set_status("The last edited file is closed".to_string(), 2.0);
+    }
+}
+
+/// Goes back to the previous location in the history.
+pub fn go_back(app: &mut ZingApp) {
+    if !travel(app, true) {
+        app.set_status("No earlier location".to_string(), 2.0);
+    }
+}
+
+/// Goes forward to the location left by going back.
+pub fn go_forward(app: &mut ZingApp) {
+    if !travel(app, false) {
+        app.set_status("No later location".to_string(), 2.0);
+    }
+}
@@ -0,0 +1,601 @@
+//! Split editor panes for Zing text editor.
+//!
+//! The editor area can be split side by side or stacked, to any depth. Every
+//! pane has its own tabs, cursor and scroll position, and several panes can show
+//! the same buffer. The state of the focused pane lives in [`ZingApp`] itself so
+//! commands act on it, and each other pane is swapped in while it is drawn.
+
+use egui::{Color32, CursorIcon, Pos2, Rect, Sense, Stroke, Ui};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::config::Theme;
+use crate::session::SavedLayout;
+use crate::ui::minimap::{self, Minimap};
+use crate::ui::tabs::{Tab, TabsView};
+use crate::ui::{editor, ZingApp};
+
+/// Identifies a pane.
+pub type PaneId = usize;
+
+/// Width of the dividers between panes.
+const DIVIDER_WIDTH: f32 = 4.0;
+/// Smallest share of a split that either side can be resized to.
+const MIN_RATIO: f32 = 0.1;
+
+/// How the two sides of a split are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Next to each other, with a vertical divider
+    SideBySide,
+    /// One above the other, with a horizontal divider
+    Stacked,
+}
+
+/// A direction to move the focus or split a pane in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards the left edge
+    Left,
+    /// Towards the right edge
+    Right,
+    /// Towards the top edge
+    Up,
+    /// Towards the bottom edge
+    Down,
+}
+
+impl Direction {
+    /// Returns how panes next to each other in this direction are arranged.
+    fn orientation(self) -> Orientation {
+        match self {
+            Direction::Left | Direction::Right => Orientation::SideBySide,
+            Direction::Up | Direction::Down => Orientation::Stacked,
+        }
+    }
+
+    /// Returns whether the direction points to the left or top.
+    fn is_backward(self) -> bool {
+        matches!(self, Direction::Left | Direction::Up)
+    }
+
+    /// Returns a name for status messages.
+    fn label(self) -> &'static str {
+        match self {
+            Direction::Left => "to the left",
+            Direction::Right => "to the right",
+            Direction::Up => "above",
+            Direction::Down => "below",
+        }
+    }
+}
+
+/// The arrangement of the panes.
+#[derive(Debug, Clone)]
+enum Layout {
+    /// A single pane
+    Pane(PaneId),
+    /// Two layouts sharing the space
+    Split {
+        orientation: Orientation,
+        /// Share of the space taken by `first`
+        ratio: f32,
+        /// The left or top side
+        first: Box<Layout>,
+        /// The right or bottom side
+        second: Box<Layout>,
+    },
+}
+
+/// A divider between the two sides of a split.
+#[derive(Debug)]
+struct Divider {
+    /// Path to the split, taking the second side at each step that is true
+    path: Vec<bool>,
+    /// How the sides of the split are arranged
+    orientation: Orientation,
+    /// Where the divider is drawn
+    rect: Rect,
+    /// The space shared by the split
+    area: Rect,
+}
+
+/// Splits `area` in two, leaving room for a divider between the sides.
+fn split_rect(area: Rect, orientation: Orientation, ratio: f32) -> (Rect, Rect, Rect) {
+    match orientation {
+        Orientation::SideBySide => {
+            let x = area.left() + (area.width() - DIVIDER_WIDTH) * ratio;
+            let (first, rest) = area.split_left_right_at_x(x);
+            let (divider, second) = rest.split_left_right_at_x(x + DIVIDER_WIDTH);
+            (first, divider, second)
+        }
+        Orientation::Stacked => {
+            let y = area.top() + (area.height() - DIVIDER_WIDTH) * ratio;
+            let (first, rest) = area.split_top_bottom_at_y(y);
+            let (divider, second) = rest.split_top_bottom_at_y(y + DIVIDER_WIDTH);
+            (first, divider, second)
+        }
+    }
+}
+
+impl Layout {
+    /// Computes where every pane and divider goes when laid out in `area`.
+    fn arrange(&self, area: Rect, path: &mut Vec<bool>, panes: &mut Vec<(PaneId, Rect)>, dividers: &mut Vec<Divider>) {
+        match self {
+            Layout::Pane(id) => panes.push((*id, area)),
+            Layout::Split { orientation, ratio, first, second } => {
+                let (first_rect, rect, second_rect) = split_rect(area, *orientation, *ratio);
+                dividers.push(Divider {
+                    path: path.clone(),
+                    orientation: *orientation,
+                    rect,
+                    area,
+                });
+                path.push(false);
+                first.arrange(first_rect, path, panes, dividers);
+                path.pop();
+                path.push(true);
+                second.arrange(second_rect, path, panes, dividers);
+                path.pop();
+            }
+        }
+    }
+
+    /// Returns the ratio of the split at `path`.
+    fn ratio_mut(&mut self, path: &[bool]) -> Option<&mut f32> {
+        match self {
+            Layout::Pane(_) => None,
+            Layout::Split { ratio, first, second, .. } => match path.split_first() {
+                None => Some(ratio),
+                Some((false, rest)) => first.ratio_mut(rest),
+                Some((true, rest)) => second.ratio_mut(rest),
+            },
+        }
+    }
+
+    /// Splits pane `id` in two, putting `new_id` before or after it.
+    fn split(&mut self, id: PaneId, new_id: PaneId, orientation: Orientation, before: bool) -> bool {
+        match self {
+            Layout::Pane(pane) if *pane == id => {
+                let (first, second) = if before { (new_id, id) } else { (id, new_id) };
+                *self = Layout::Split {
+                    orientation,
+                    ratio: 0.5,
+                    first: Box::new(Layout::Pane(first)),
+                    second: Box::new(Layout::Pane(second)),
+                };
+                true
+            }
+            Layout::Pane(_) => false,
+            Layout::Split { first, second, .. } => {
+                first.split(id, new_id, orientation, before) || second.split(id, new_id, orientation, before)
+            }
+        }
+    }
+
+    /// Removes pane `id`, letting the other side of its split take its space.
+    ///
+    /// Returns the pane of that other side nearest to the removed one.
+    fn remove(&mut self, id: PaneId) -> Option<PaneId> {
+        let Layout::Split { first, second, .. } = self else { return None };
+        let rest = if matches!(**first, Layout::Pane(pane) if pane == id) {
+            std::mem::replace(&mut **second, Layout::Pane(id))
+        } else if matches!(**second, Layout::Pane(pane) if pane == id) {
+            std::mem::replace(&mut **first, Layout::Pane(id))
+        } else {
+            return first.remove(id).or_else(|| second.remove(id));
+        };
+        *self = rest;
+        Some(self.first_pane())
+    }
+
+    /// Returns the pane at the top left.
+    fn first_pane(&self) -> PaneId {
+        match self {
+            Layout::Pane(id) => *id,
+            Layout::Split { first, .. } => first.first_pane(),
+        }
+    }
+}
+
+/// The part of the application state that belongs to a pane.
+#[derive(Debug)]
+struct PaneState {
+    tabs: TabsView,
+    cursor_pos: usize,
+    cursor_line: usize,
+    cursor_column: usize,
+    selection: Range<usize>,
+    pending_jump: Option<usize>,
+    scroll_to_line: Option<usize>,
+    visible_lines: Range<usize>,
+    minimap: Minimap,
+}
+
+impl PaneState {
+    /// Creates the state of a pane showing `tabs` from their start.
+    fn new(tabs: TabsView) -> Self {
+        Self {
+            tabs,
+            cursor_pos: 0,
+            cursor_line: 0,
+            cursor_column: 0,
+            selection: 0..0,
+            pending_jump: None,
+            scroll_to_line: None,
+            visible_lines: 0..0,
+            minimap: Minimap::new(),
+        }
+    }
+}
+
+/// Exchanges the pane state held by `app` with `state`.
+fn swap(app: &mut ZingApp, state: &mut PaneState) {
+    std::mem::swap(&mut app.tabs, &mut state.tabs);
+    std::mem::swap(&mut app.cursor_pos, &mut state.cursor_pos);
+    std::mem::swap(&mut app.cursor_line, &mut state.cursor_line);
+    std::mem::swap(&mut app.cursor_column, &mut state.cursor_column);
+    std::mem::swap(&mut app.selection, &mut state.selection);
+    std::mem::swap(&mut app.pending_jump, &mut state.pending_jump);
+    std::mem::swap(&mut app.scroll_to_line, &mut state.scroll_to_line);
+    std::mem::swap(&mut app.visible_lines, &mut state.visible_lines);
+    std::mem::swap(&mut app.minimap, &mut state.minimap);
+}
+
+/// The editor panes and the state of the ones not focused.
+#[derive(Debug)]
+pub struct Panes {
+    /// How the panes are arranged
+    layout: Layout,
+    /// The pane that receives commands
+    focused: PaneId,
+    /// The pane whose state is in the application, which differs from the
+    /// focused one only while another pane is drawn
+    current: PaneId,
+    /// State of the panes other than the current one
+    parked: Vec<(PaneId, PaneState)>,
+    /// Id given to the next new pane
+    next_id: PaneId,
+    /// Where each pane was drawn in the last frame
+    rects: Vec<(PaneId, Rect)>,
+    /// Whether the focused pane's editor should take keyboard focus
+    refocus: bool,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self {
+            layout: Layout::Pane(0),
+            focused: 0,
+            current: 0,
+            parked: Vec::new(),
+            next_id: 1,
+            rects: Vec::new(),
+            refocus: false,
+        }
+    }
+}
+
+impl Panes {
+    /// Creates a layout with a single pane.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the pane whose state is in the application.
+    pub fn current(&self) -> PaneId {
+        self.current
+    }
+
+    /// Returns whether the pane being drawn is the focused one.
+    pub fn is_current_focused(&self) -> bool {
+        self.current == self.focused
+    }
+
+    /// Returns the number of panes.
+    pub fn count(&self) -> usize {
+        self.parked.len() + 1
+    }
+
+    /// Returns the tabs of the panes other than the current one.
+    pub fn other_tabs(&self) -> impl Iterator<Item = &TabsView> {
+        self.parked.iter().map(|(_, state)| &state.tabs)
+    }
+
+    /// Takes the state of pane `id` out of the parked panes.
+    fn unpark(&mut self, id: PaneId) -> Option<PaneState> {
+        let index = self.parked.iter().position(|(parked, _)| *parked == id)?;
+        Some(self.parked.swap_remove(index).1)
+    }
+}
+
+/// Draws the panes filling `ui`, with the dividers between them.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    let area = ui.max_rect();
+    let mut rects = Vec::new();
+    let mut dividers = Vec::new();
+    app.panes.layout.arrange(area, &mut Vec::new(), &mut rects, &mut dividers);
+
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (divider_color, focus_color) = if is_dark {
+        (Color32::from_rgb(30, 30, 40), Color32::from_rgb(86, 156, 255))
+    } else {
+        (Color32::from_rgb(225, 225, 232), Color32::from_rgb(0, 120, 215))
+    };
+
+    for divider in &dividers {
+        let response = ui.interact(divider.rect, ui.id().with(("pane_divider", &divider.path)), Sense::drag());
+        if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(match divider.orientation {
+                Orientation::SideBySide => CursorIcon::ResizeHorizontal,
+                Orientation::Stacked => CursorIcon::ResizeVertical,
+            });
+        }
+        if let (true, Some(pointer)) = (response.dragged(), response.interact_pointer_pos()) {
+            let ratio = match divider.orientation {
+                Orientation::SideBySide => (pointer.x - divider.area.left()) / divider.area.width(),
+                Orientation::Stacked => (pointer.y - divider.area.top()) / divider.area.height(),
+            };
+            if let Some(current) = app.panes.layout.ratio_mut(&divider.path) {
+                *current = ratio.clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+            }
+        }
+        ui.painter().rect_filled(divider.rect, 0.0, divider_color);
+    }
+
+    let focused = app.panes.focused;
+    let mut clicked = None;
+    for &(id, rect) in &rects {
+        // Clicks in overlays above the pane do not focus it
+        let pressed_at = ui.input(|i| i.pointer.interact_pos().filter(|_| i.pointer.any_pressed()));
+        if pressed_at.is_some_and(|pos| rect.contains(pos) && ui.ctx().layer_id_at(pos) == Some(ui.layer_id())) {
+            clicked = Some(id);
+        }
+
+        if id == focused {
+            pane_ui(app, ui, rect);
+        } else if let Some(mut state) = app.panes.unpark(id) {
+            swap(app, &mut state);
+            app.panes.current = id;
+            pane_ui(app, ui, rect);
+            swap(app, &mut state);
+            app.panes.current = focused;
+            app.panes.parked.push((id, state));
+        }
+
+        // Mark the focused pane when there are several
+        if id == focused && app.panes.count() > 1 {
+            ui.painter().line_segment([rect.left_top(), rect.right_top()], Stroke::new(2.0, focus_color));
+        }
+    }
+    app.panes.rects = rects;
+
+    if let Some(id) = clicked {
+        focus(app, id);
+    }
+}
+
+/// Draws the editor and minimap of the current pane in `rect`.
+fn pane_ui(app: &mut ZingApp, ui: &mut Ui, rect: Rect) {
+    let id = app.panes.current;
+    if app.panes.refocus && app.panes.is_current_focused() {
+        app.panes.refocus = false;
+        ui.memory_mut(|mem| mem.request_focus(editor::editor_id(id)));
+    }
+
+    // The minimap takes a column at the right of the editor
+    let mut text_rect = rect;
+    if app.config.show_minimap {
+        text_rect.max.x -= minimap::WIDTH;
+    }
+
+    let mut editor_ui = ui.child_ui_with_id_source(text_rect, egui::Layout::default(), ("pane", id));
+    editor::ui(app, &mut editor_ui);
+
+    if app.config.show_minimap {
+        let minimap_rect = Rect::from_min_max(egui::pos2(text_rect.max.x, rect.min.y), rect.max);
+        let mut minimap_ui = ui.child_ui_with_id_source(minimap_rect, egui::Layout::default(), ("minimap", id));
+        minimap::ui(app, &mut minimap_ui);
+    }
+}
+
+/// Returns the pane at `pos` and where it is, or the focused pane if there is
+/// no pane there.
+pub fn pane_at(app: &ZingApp, pos: Option<Pos2>) -> Option<(PaneId, Rect)> {
+    let rects = &app.panes.rects;
+    pos.and_then(|pos| rects.iter().find(|(_, rect)| rect.contains(pos)))
+        .or_else(|| rects.iter().find(|(id, _)| *id == app.panes.focused))
+        .copied()
+}
+
+/// Makes pane `id` the one receiving commands and keyboard input.
+pub fn focus(app: &mut ZingApp, id: PaneId) {
+    if id == app.panes.focused {
+        return;
+    }
+    let Some(mut state) = app.panes.unpark(id) else { return };
+    swap(app, &mut state);
+    app.panes.parked.push((app.panes.focused, state));
+    app.panes.focused = id;
+    app.panes.current = id;
+    app.panes.refocus = true;
+}
+
+/// Moves the focus to the nearest pane in `direction`.
+pub fn focus_direction(app: &mut ZingApp, direction: Direction) {
+    let rects = &app.panes.rects;
+    let Some(&(_, from)) = rects.iter().find(|(id, _)| *id == app.panes.focused) else { return };
+
+    let target = rects
+        .iter()
+        .filter(|(_, rect)| match direction {
+            Direction::Left => rect.right() <= from.left(),
+            Direction::Right => rect.left() >= from.right(),
+            Direction::Up => rect.bottom() <= from.top(),
+            Direction::Down => rect.top() >= from.bottom(),
+        })
+        .min_by(|(_, a), (_, b)| {
+            let a = from.center().distance(a.center());
+            let b = from.center().distance(b.center());
+            a.total_cmp(&b)
+        })
+        .map(|&(id, _)| id);
+
+    match target {
+        Some(id) => focus(app, id),
+        None => app.set_status(format!("No pane {}", direction.label()), 2.0),
+    }
+}
+
+/// Splits the focused pane, opening its active tab in a new pane in `direction`.
+///
+/// Both panes show the same buffer, each with its own cursor and scroll position.
+pub fn split(app: &mut ZingApp, direction: Direction) {
+    let id = app.panes.next_id;
+    app.panes.next_id += 1;
+    app.panes.layout.split(app.panes.focused, id, direction.orientation(), direction.is_backward());
+
+    let tab = app.tabs.tabs[app.tabs.active_tab].clone();
+    let mut state = PaneState {
+        cursor_pos: app.cursor_pos,
+        cursor_line: app.cursor_line,
+        cursor_column: app.cursor_column,
+        selection: app.selection.clone(),
+        pending_jump: Some(app.cursor_pos),
+        ..PaneState::new(TabsView::with_tabs(vec![tab], 0))
+    };
+    swap(app, &mut state);
+    app.panes.parked.push((app.panes.focused, state));
+    app.panes.focused = id;
+    app.panes.current = id;
+    app.panes.refocus = true;
+    // Closing the new pane's only tab closes the pane, not the editor
+    app.last_tab_close_warning = false;
+}
+
+/// Closes the focused pane, moving the focus to the pane that takes its space.
+///
+/// Returns false if it is the only pane.
+pub fn close(app: &mut ZingApp) -> bool {
+    let closed = app.panes.focused;
+    let Some(next) = app.panes.layout.remove(closed) else { return false };
+    let Some(mut state) = app.panes.unpark(next) else { return false };
+
+    // The closed pane's state ends up in `state` and is dropped
+    swap(app, &mut state);
+    app.panes.focused = next;
+    app.panes.current = next;
+    app.panes.refocus = true;
+    true
+}
+
+/// Returns the files open in `tabs` and the index of the active one among them.
+fn saved_pane(tabs: &TabsView) -> SavedLayout {
+    let mut files = Vec::new();
+    let mut active = 0;
+    for (index, tab) in tabs.tabs.iter().enumerate() {
+        if let Some(path) = tab.file_path() {
+            if index <= tabs.active_tab {
+                active = files.len();
+            }
+            files.push(path);
+        }
+    }
+    SavedLayout::Pane { files, active }
+}
+
+/// Describes the panes and the files open in them for the session.
+pub fn saved_layout(app: &ZingApp) -> SavedLayout {
+    fn save(app: &ZingApp, layout: &Layout) -> SavedLayout {
+        match layout {
+            Layout::Pane(id) if *id == app.panes.current => saved_pane(&app.tabs),
+            Layout::Pane(id) => app
+                .panes
+                .parked
+                .iter()
+                .find(|(parked, _)| parked == id)
+                .map(|(_, state)| saved_pane(&state.tabs))
+                .unwrap_or(SavedLayout::Pane { files: Vec::new(), active: 0 }),
+            Layout::Split { orientation, ratio, first, second } => SavedLayout::Split {
+                side_by_side: *orientation == Orientation::SideBySide,
+                ratio: *ratio,
+                first: Box::new(save(app, first)),
+                second: Box::new(save(app, second)),
+            },
+        }
+    }
+    save(app, &app.panes.layout)
+}
+
+/// Recreates the panes saved with the session, opening their files again.
+///
+/// Files open in several panes share one buffer, and files that can no longer
+/// be read are left out.
+pub fn restore(app: &mut ZingApp) {
+    let Some(saved) = app.session.layout.clone() else { return };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let mut opened: Vec<Tab> = Vec::new();
+    let mut states = Vec::new();
+    let mut open = |app: &mut ZingApp, path: &PathBuf| -> Option<Tab> {
+        if let Some(tab) = opened.iter().find(|tab| tab.shows_file(path)) {
+            return Some(tab.clone());
+        }
+        match runtime.block_on(crate::file_io::load_file(path)) {
+            Ok(buffer) => {
+                let tab = app.tab_for(buffer);
+                opened.push(tab.clone());
+                Some(tab)
+            }
+            Err(err) => {
+                log::warn!("Not restoring {}: {:#}", path.display(), err);
+                None
+            }
+        }
+    };
+
+    fn build(
+        app: &mut ZingApp,
+        saved: &SavedLayout,
+        open: &mut dyn FnMut(&mut ZingApp, &PathBuf) -> Option<Tab>,
+        states: &mut Vec<(PaneId, PaneState)>,
+    ) -> Layout {
+        match saved {
+            SavedLayout::Pane { files, active } => {
+                let tabs: Vec<Tab> = files.iter().filter_map(|path| open(app, path)).collect();
+                let tabs = if tabs.is_empty() {
+                    TabsView::new()
+                } else {
+                    let active = (*active).min(tabs.len() - 1);
+                    TabsView::with_tabs(tabs, active)
+                };
+                let id = states.len();
+                states.push((id, PaneState::new(tabs)));
+                Layout::Pane(id)
+            }
+            SavedLayout::Split { side_by_side, ratio, first, second } => Layout::Split {
+                orientation: if *side_by_side { Orientation::SideBySide } else { Orientation::Stacked },
+                ratio: ratio.clamp(MIN_RATIO, 1.0 - MIN_RATIO),
+                first: Box::new(build(app, first, open, states)),
+                second: Box::new(build(app, second, open, states)),
+            },
+        }
+    }
+
+    let layout = build(app, &saved, &mut open, &mut states);
+    let next_id = states.len();
+    let mut states = states.into_iter();
+    let Some((first, mut state)) = states.next() else { return };
+    swap(app, &mut state);
+    app.panes = Panes {
+        layout,
+        focused: first,
+        current: first,
+        parked: states.collect(),
+        next_id,
+        ..Panes::default()
+    };
+}
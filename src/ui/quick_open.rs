@@ -0,0 +1,266 @@
+//! Quick open overlay for Zing text editor.
+//!
+//! Lets the user fuzzy-find a file in the workspace by typing fragments of its
+//! path, optionally followed by `:line` or `:line:column` to jump straight there.
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Align2, Color32, Context, FontId, Key, Modifiers, Vec2};
+use std::path::PathBuf;
+
+use crate::config::Theme;
+use crate::ui::{editor, ZingApp};
+use crate::workspace::fuzzy::{fuzzy_match, FuzzyMatch};
+use crate::workspace::{current_file_location, split_location};
+
+/// Maximum number of results shown in the list.
+const MAX_RESULTS: usize = 50;
+/// Score bonus for the most recently opened file, decreasing with age.
+const RECENT_BONUS: i64 = 400;
+
+/// A file that matched the current query.
+#[derive(Debug, Clone)]
+struct QuickOpenResult {
+    /// Absolute path of the file
+    path: PathBuf,
+    /// Path shown in the list, relative to the workspace root when possible
+    display: String,
+    /// Matched character positions in `display`
+    indices: Vec<usize>,
+}
+
+/// State of the quick open overlay.
+#[derive(Debug, Default)]
+pub struct QuickOpen {
+    /// Whether the overlay is visible
+    pub open: bool,
+    /// The text typed by the user
+    query: String,
+    /// Index of the highlighted result
+    selected: usize,
+    /// Results for `cached_query`
+    results: Vec<QuickOpenResult>,
+    /// The query and index version the results were computed for
+    cached_query: Option<(String, (u64, usize))>,
+}
+
+impl QuickOpen {
+    /// Creates a closed quick open overlay.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Shows the quick open overlay, which only lists recent files when no
+/// workspace is open.
+pub fn show(app: &mut ZingApp) {
+    app.quick_open.open = true;
+    app.quick_open.query.clear();
+    app.quick_open.selected = 0;
+    app.quick_open.cached_query = None;
+}
+
+/// Recomputes the results if the query or the index changed.
+fn update_results(app: &mut ZingApp) {
+    // Only take a snapshot of the index when it or the query changed
+    let key = (app.quick_open.query.clone(), app.workspace.index_version());
+    if app.quick_open.cached_query.as_ref() == Some(&key) {
+        return;
+    }
+    // A bare `:line` jumps within the current file, so no file is listed
+    if current_file_location(&app.quick_open.query).is_some() {
+        app.quick_open.results.clear();
+        app.quick_open.selected = 0;
+        app.quick_open.cached_query = Some(key);
+        return;
+    }
+    let files = app.workspace.files();
+
+    let (pattern, _, _) = split_location(&app.quick_open.query);
+    let root = app.workspace.root.clone();
+
+    // Candidates are the indexed files plus recent files outside the workspace
+    let mut candidates: Vec<(PathBuf, String)> = files
+        .into_iter()
+        .map(|relative| {
+            let display = relative.to_string_lossy().replace('\\', "/");
+            let path = root.as_ref().map_or(relative.clone(), |root| root.join(&relative));
+            (path, display)
+        })
+        .collect();
+    for path in &app.session.recent_files {
+        if app.workspace.relative_path(path).is_none() {
+            candidates.push((path.clone(), path.to_string_lossy().to_string()));
+        }
+    }
+
+    let mut scored: Vec<(i64, QuickOpenResult)> = candidates
+        .into_iter()
+        .filter_map(|(path, display)| {
+            let FuzzyMatch { score, indices } = fuzzy_match(pattern, &display)?;
+            let recency = app
+                .session
+                .recent_files
+                .iter()
+                .position(|recent| *recent == path)
+                .map_or(0, |age| (RECENT_BONUS - age as i64 * 8).max(1));
+            Some((score + recency, QuickOpenResult { path, display, indices }))
+        })
+        .collect();
+
+    scored.sort_by(|(a, ra), (b, rb)| b.cmp(a).then_with(|| ra.display.cmp(&rb.display)));
+    scored.truncate(MAX_RESULTS);
+
+    app.quick_open.results = scored.into_iter().map(|(_, result)| result).collect();
+    app.quick_open.selected = app.quick_open.selected.min(app.quick_open.results.len().saturating_sub(1));
+    app.quick_open.cached_query = Some(key);
+}
+
+/// Opens the selected result and closes the overlay.
+fn accept(app: &mut ZingApp) {
+    app.quick_open.open = false;
+
+    // `:line` on its own jumps within the current file
+    if let Some((line, column)) = current_file_location(&app.quick_open.query) {
+        editor::go_to(app, line.saturating_sub(1), column.unwrap_or(1).saturating_sub(1));
+        return;
+    }
+
+    let (_, line, column) = split_location(&app.quick_open.query);
+    let Some(result) = app.quick_open.results.get(app.quick_open.selected).cloned() else {
+        return;
+    };
+    if editor::open_path(app, &result.path) {
+        if let Some(line) = line {
+            editor::go_to(app, line.saturating_sub(1), column.unwrap_or(1).saturating_sub(1));
+        }
+    }
+}
+
+/// Builds a label of `text` with the matched characters at `indices` highlighted.
+pub fn match_label(text: &str, indices: &[usize], text_color: Color32, match_color: Color32) -> LayoutJob {
+    let mut job = LayoutJob::default();
+    let font_id = FontId::monospace(13.0);
+
+    for (i, c) in text.chars().enumerate() {
+        let is_match = indices.contains(&i);
+        let format = TextFormat {
+            font_id: font_id.clone(),
+            color: if is_match { match_color } else { text_color },
+            underline: if is_match {
+                egui::Stroke::new(1.0, match_color)
+            } else {
+                egui::Stroke::NONE
+            },
+            ..Default::default()
+        };
+        let mut buf = [0; 4];
+        job.append(c.encode_utf8(&mut buf), 0.0, format);
+    }
+
+    job
+}
+
+/// Renders the quick open overlay if it is open.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.quick_open.open {
+        return;
+    }
+
+    update_results(app);
+    if app.workspace.is_indexing() {
+        ctx.request_repaint();
+    }
+
+    // Handle navigation keys before the text field sees them
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::ArrowUp),
+            i.consume_key(Modifiers::NONE, Key::ArrowDown),
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+    let count = app.quick_open.results.len();
+    if up && app.quick_open.selected > 0 {
+        app.quick_open.selected -= 1;
+    }
+    if down && app.quick_open.selected + 1 < count {
+        app.quick_open.selected += 1;
+    }
+    if escape {
+        app.quick_open.open = false;
+        return;
+    }
+    if enter {
+        accept(app);
+        return;
+    }
+
+    let is_dark = matches!(app.config.theme, Theme::Dark);
+    let (text_color, match_color) = if is_dark {
+        (Color32::from_rgb(200, 200, 215), Color32::from_rgb(86, 156, 255))
+    } else {
+        (Color32::from_rgb(50, 50, 60), Color32::from_rgb(0, 120, 215))
+    };
+
+    let mut clicked = None;
+    egui::Area::new("quick_open")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(520.0);
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut app.quick_open.query)
+                        .hint_text("Search files by name (append :line to jump)")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    app.quick_open.selected = 0;
+                }
+
+                ui.separator();
+
+                if app.workspace.root.is_none() && !app.quick_open.results.is_empty() {
+                    ui.weak("No folder open, showing recent files");
+                }
+                if let Some((line, column)) = current_file_location(&app.quick_open.query) {
+                    let message = match column {
+                        Some(column) => format!("Go to line {}, column {} in the current file", line, column),
+                        None => format!("Go to line {} in the current file", line),
+                    };
+                    ui.weak(message);
+                } else if app.quick_open.results.is_empty() {
+                    let message = if app.workspace.root.is_none() {
+                        "No folder open. Open a folder to search its files"
+                    } else if app.workspace.is_indexing() {
+                        "Indexing..."
+                    } else {
+                        "No matching files"
+                    };
+                    ui.weak(message);
+                }
+
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (index, result) in app.quick_open.results.iter().enumerate() {
+                        let is_selected = index == app.quick_open.selected;
+                        let label = match_label(&result.display, &result.indices, text_color, match_color);
+                        let response = ui.selectable_label(is_selected, label);
+                        if is_selected && (up || down) {
+                            response.scroll_to_me(None);
+                        }
+                        if response.clicked() {
+                            clicked = Some(index);
+                        }
+                    }
+                });
+            });
+        });
+
+    if let Some(index) = clicked {
+        app.quick_open.selected = index;
+        accept(app);
+    }
+}
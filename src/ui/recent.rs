@@ -0,0 +1,76 @@
+//! Recently opened files and folders for Zing text editor.
+//!
+//! Lists the files and folders remembered in the session so they can be opened
+//! again from the menu bar, the toolbar and the welcome screen.
+
+use egui::Ui;
+use std::path::{Path, PathBuf};
+
+use crate::ui::{editor, ZingApp};
+
+/// Number of files and of folders listed in the Open Recent menu.
+const MENU_ENTRIES: usize = 10;
+
+/// Opens a recently used file, or a folder as the workspace, forgetting it if
+/// it no longer exists.
+pub fn open(app: &mut ZingApp, path: &Path) {
+    if path.is_dir() {
+        app.open_workspace(path.to_path_buf());
+        app.set_status(format!("Opened folder: {}", path.display()), 3.0);
+    } else if path.is_file() {
+        editor::open_path(app, path);
+    } else {
+        app.session.forget_recent(path);
+        app.set_status(format!("{} no longer exists", path.display()), 5.0);
+    }
+}
+
+/// Forgets every recently opened file and folder.
+pub fn clear(app: &mut ZingApp) {
+    app.session.clear_recent();
+    app.save_session();
+    app.set_status("Cleared recently opened files".to_string(), 2.0);
+}
+
+/// Returns the label of a recent entry: its name, then the folder it is in.
+fn label(path: &Path) -> String {
+    let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+    match path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        Some(parent) => format!("{}  —  {}", name, parent.display()),
+        None => name,
+    }
+}
+
+/// Draws the entries of the Open Recent menu.
+pub fn menu(app: &mut ZingApp, ui: &mut Ui) {
+    let folders: Vec<PathBuf> = app.session.recent_folders.iter().take(MENU_ENTRIES).cloned().collect();
+    let files: Vec<PathBuf> = app.session.recent_files.iter().take(MENU_ENTRIES).cloned().collect();
+    if folders.is_empty() && files.is_empty() {
+        ui.add_enabled(false, egui::Button::new("No Recent Files"));
+        return;
+    }
+
+    let mut picked = None;
+    let mut entries = |ui: &mut Ui, paths: &[PathBuf]| {
+        for path in paths {
+            if ui.button(label(path)).on_hover_text(path.display().to_string()).clicked() {
+                picked = Some(path.clone());
+                ui.close_menu();
+            }
+        }
+    };
+    entries(ui, &folders);
+    if !folders.is_empty() && !files.is_empty() {
+        ui.separator();
+    }
+    entries(ui, &files);
+    ui.separator();
+    if ui.button("Clear Recent").clicked() {
+        clear(app);
+        ui.close_menu();
+    }
+
+    if let Some(path) = picked {
+        open(app, &path);
+    }
+}
@@ -2,8 +2,10 @@
 
 use egui::{Color32, Ui, Stroke, Rect, Pos2, FontId, Rounding, Vec2, Sense};
 
-use crate::ui::ZingApp;
-use crate::config::Theme;
+use crate::buffer::format::LineEnding;
+use crate::language;
+use crate::ui::{indentation, ZingApp};
+use crate::config::{AutoSave, Theme, TrimWhitespace};
 
 /// Status bar component.
 #[derive(Debug)]
@@ -94,6 +96,182 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
         text_color,
     );
     
+    // Draw the autosave mode left of the cursor position, briefly replaced by
+    // "Saved" after files were saved automatically
+    let (autosave_text, autosave_color) = if app.autosave.recently_saved() {
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+        ("✓ Saved".to_string(), text_color)
+    } else if app.config.autosave == AutoSave::Off {
+        ("Auto Save Off".to_string(), text_color.linear_multiply(0.4))
+    } else {
+        (format!("Auto Save: {}", app.config.autosave.label()), text_color.linear_multiply(0.6))
+    };
+    let autosave_rect = ui.painter().text(
+        text_rect.left_center() - Vec2::new(12.0, 0.0),
+        egui::Align2::RIGHT_CENTER,
+        autosave_text,
+        FontId::proportional(10.0),
+        autosave_color,
+    );
+    let autosave_response = ui
+        .interact(autosave_rect, ui.id().with("autosave"), Sense::click())
+        .on_hover_text("Choose when files are saved automatically");
+    let autosave_popup = ui.make_persistent_id("autosave_popup");
+    if autosave_response.clicked() {
+        ui.memory_mut(|mem| mem.toggle_popup(autosave_popup));
+    }
+    egui::popup::popup_above_or_below_widget(ui, autosave_popup, &autosave_response, egui::AboveOrBelow::Above, |ui| {
+        ui.set_min_width(140.0);
+        for mode in AutoSave::ALL {
+            ui.radio_value(&mut app.config.autosave, mode, mode.label());
+        }
+    });
+    
+    // Draw the indentation of the file left of the autosave mode
+    let style = indentation::style(app);
+    let indent_rect = ui.painter().text(
+        autosave_rect.left_center() - Vec2::new(12.0, 0.0),
+        egui::Align2::RIGHT_CENTER,
+        indentation::label(style),
+        FontId::proportional(10.0),
+        text_color.linear_multiply(0.6),
+    );
+    let indent_response = ui
+        .interact(indent_rect, ui.id().with("indentation"), Sense::click())
+        .on_hover_text("Choose how this file is indented");
+    let indent_popup = ui.make_persistent_id("indentation_popup");
+    if indent_response.clicked() {
+        ui.memory_mut(|mem| mem.toggle_popup(indent_popup));
+    }
+    let ctx = ui.ctx().clone();
+    egui::popup::popup_above_or_below_widget(ui, indent_popup, &indent_response, egui::AboveOrBelow::Above, |ui| {
+        ui.set_min_width(180.0);
+        let mut use_spaces = style.use_spaces;
+        ui.radio_value(&mut use_spaces, true, "Indent Using Spaces");
+        ui.radio_value(&mut use_spaces, false, "Indent Using Tabs");
+        if use_spaces != style.use_spaces {
+            indentation::set_use_spaces(app, use_spaces);
+        }
+        ui.separator();
+        let mut tab_size = style.tab_size;
+        ui.horizontal(|ui| {
+            ui.label("Width:");
+            for size in indentation::TAB_SIZES {
+                ui.radio_value(&mut tab_size, size, size.to_string());
+            }
+        });
+        if tab_size != style.tab_size {
+            indentation::set_tab_size(app, tab_size);
+        }
+        ui.horizontal(|ui| {
+            ui.label("Re-indent to:");
+            for size in indentation::TAB_SIZES {
+                if ui.button(size.to_string()).clicked() {
+                    indentation::change_width(app, &ctx, size);
+                }
+            }
+        });
+        ui.separator();
+        if ui.button("Convert Indentation to Spaces").clicked() {
+            indentation::convert(app, &ctx, true);
+        }
+        if ui.button("Convert Indentation to Tabs").clicked() {
+            indentation::convert(app, &ctx, false);
+        }
+        if ui.button("Detect from Content").clicked() {
+            indentation::detect(app);
+        }
+    });
+    
+    // Draw the line ending the file is saved with left of the indentation
+    let line_ending = {
+        let buffer = app.buffer();
+        let buffer_lock = buffer.lock().unwrap();
+        buffer_lock.format.line_ending
+            .or(app.config.line_ending)
+            .or_else(|| LineEnding::detect(&buffer_lock.content))
+            .unwrap_or(LineEnding::Lf)
+    };
+    let ending_rect = ui.painter().text(
+        indent_rect.left_center() - Vec2::new(12.0, 0.0),
+        egui::Align2::RIGHT_CENTER,
+        line_ending.label(),
+        FontId::proportional(10.0),
+        text_color.linear_multiply(0.6),
+    );
+    let ending_response = ui
+        .interact(ending_rect, ui.id().with("line_ending"), Sense::click())
+        .on_hover_text("Choose the line endings and what happens on save");
+    let ending_popup = ui.make_persistent_id("line_ending_popup");
+    if ending_response.clicked() {
+        ui.memory_mut(|mem| mem.toggle_popup(ending_popup));
+    }
+    egui::popup::popup_above_or_below_widget(ui, ending_popup, &ending_response, egui::AboveOrBelow::Above, |ui| {
+        ui.set_min_width(180.0);
+        let mut chosen = line_ending;
+        ui.horizontal(|ui| {
+            ui.label("This file:");
+            for ending in LineEnding::ALL {
+                ui.radio_value(&mut chosen, ending, ending.label());
+            }
+        });
+        if chosen != line_ending {
+            app.buffer().lock().unwrap().format.line_ending = Some(chosen);
+        }
+        ui.separator();
+        ui.label("On Save");
+        for mode in TrimWhitespace::ALL {
+            ui.radio_value(&mut app.config.trim_trailing_whitespace, mode, mode.label());
+        }
+        ui.checkbox(&mut app.config.final_newline, "Ensure Final Newline");
+        ui.horizontal(|ui| {
+            ui.label("Line endings:");
+            ui.radio_value(&mut app.config.line_ending, None, "Keep");
+            for ending in LineEnding::ALL {
+                ui.radio_value(&mut app.config.line_ending, Some(ending), ending.label());
+            }
+        });
+        ui.checkbox(&mut app.config.format_on_save, "Format on Save");
+    });
+    
+    // Draw the language of the file left of the line ending
+    let current = app.buffer().lock().unwrap().language;
+    let language_rect = ui.painter().text(
+        ending_rect.left_center() - Vec2::new(12.0, 0.0),
+        egui::Align2::RIGHT_CENTER,
+        current.name,
+        FontId::proportional(10.0),
+        text_color.linear_multiply(0.6),
+    );
+    let language_response = ui
+        .interact(language_rect, ui.id().with("language"), Sense::click())
+        .on_hover_text("Choose the language of this file");
+    let language_popup = ui.make_persistent_id("language_popup");
+    if language_response.clicked() {
+        ui.memory_mut(|mem| mem.toggle_popup(language_popup));
+    }
+    egui::popup::popup_above_or_below_widget(ui, language_popup, &language_response, egui::AboveOrBelow::Above, |ui| {
+        ui.set_min_width(160.0);
+        if ui.button("Detect from File").clicked() {
+            let buffer = app.buffer();
+            let mut buffer_lock = buffer.lock().unwrap();
+            buffer_lock.language = language::detect(buffer_lock.file_path.as_deref(), &buffer_lock.content);
+        }
+        ui.separator();
+        egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+            for choice in language::LANGUAGES {
+                if ui.selectable_label(std::ptr::eq(choice, current), choice.name).clicked() {
+                    app.buffer().lock().unwrap().language = choice;
+                }
+            }
+        });
+        ui.separator();
+        let mut auto_close = app.config.auto_close_for(current.id);
+        if ui.checkbox(&mut auto_close, format!("Auto-Close Pairs in {}", current.name)).changed() {
+            app.config.languages.entry(current.id.to_string()).or_default().auto_close_pairs = Some(auto_close);
+        }
+    });
+    
     // Allocate the space for the status bar
     ui.allocate_rect(status_rect, Sense::hover());
 } 
\ No newline at end of file
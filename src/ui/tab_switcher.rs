@@ -0,0 +1,108 @@
+//! Tab switcher overlay for Zing text editor.
+//!
+//! Ctrl+Tab shows the open tabs from the most to the least recently used and
+//! moves through them while Ctrl is held. Releasing Ctrl switches to the
+//! selected tab.
+
+use egui::{Align2, Context, Key, Modifiers, RichText, Vec2};
+
+use crate::ui::ZingApp;
+
+/// State of the tab switcher overlay.
+#[derive(Debug, Default)]
+pub struct TabSwitcher {
+    /// Whether the overlay is visible
+    pub open: bool,
+    /// Tab indices from the most to the least recently used
+    order: Vec<usize>,
+    /// Position in `order` of the selected tab
+    selected: usize,
+}
+
+impl TabSwitcher {
+    /// Creates a closed tab switcher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Opens the switcher on the previously used tab, or moves the selection if it
+/// is already open.
+pub fn cycle(app: &mut ZingApp, forward: bool) {
+    let switcher = &mut app.tab_switcher;
+    if !switcher.open {
+        switcher.order = app.tabs.recent_order();
+        switcher.selected = 0;
+        switcher.open = true;
+    }
+
+    let count = switcher.order.len();
+    if count < 2 {
+        switcher.open = false;
+        return;
+    }
+    switcher.selected = if forward {
+        (switcher.selected + 1) % count
+    } else {
+        (switcher.selected + count - 1) % count
+    };
+}
+
+/// Renders the tab switcher overlay if it is open.
+pub fn ui(app: &mut ZingApp, ctx: &Context) {
+    if !app.tab_switcher.open {
+        return;
+    }
+
+    let (escape, enter, ctrl_held) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::CTRL, Key::Escape) || i.consume_key(Modifiers::NONE, Key::Escape),
+            i.consume_key(Modifiers::CTRL, Key::Enter) || i.consume_key(Modifiers::NONE, Key::Enter),
+            i.modifiers.ctrl,
+        )
+    });
+    if escape {
+        app.tab_switcher.open = false;
+        return;
+    }
+    if enter || !ctrl_held {
+        app.tab_switcher.open = false;
+        if let Some(&index) = app.tab_switcher.order.get(app.tab_switcher.selected) {
+            if index < app.tabs.tabs.len() {
+                app.tabs.active_tab = index;
+            }
+        }
+        return;
+    }
+
+    let mut clicked = None;
+    egui::Area::new("tab_switcher")
+        .anchor(Align2::CENTER_TOP, Vec2::new(0.0, 48.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(320.0);
+                let titles = app.tabs.titles();
+                for (position, &index) in app.tab_switcher.order.iter().enumerate() {
+                    let (Some(tab), Some(title)) = (app.tabs.tabs.get(index), titles.get(index)) else { continue };
+                    let mut label = RichText::new(title.to_string());
+                    if position == app.tab_switcher.selected {
+                        label = label.strong();
+                    }
+                    let response = ui.selectable_label(position == app.tab_switcher.selected, label);
+                    let response = match tab.file_path() {
+                        Some(path) => response.on_hover_text(path.to_string_lossy()),
+                        None => response,
+                    };
+                    if response.clicked() {
+                        clicked = Some(position);
+                    }
+                }
+            });
+        });
+    ctx.request_repaint();
+
+    if let Some(position) = clicked {
+        app.tab_switcher.selected = position;
+    }
+}
@@ -1,73 +1,191 @@
-use egui::{Color32, Rect, Response, Sense, Stroke, Ui, Vec2};
-use std::path::PathBuf;
+use egui::{Color32, Rect, Sense, Stroke, Ui, Vec2};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::config::Theme;
 use crate::buffer::TextBuffer;
+use crate::ui::{editor, file_tree, ZingApp};
+use crate::workspace::fuzzy::fuzzy_match;
 
+/// Identifies a tab for as long as it is open, wherever it is moved.
+pub type TabId = u64;
+
+/// Returns a new tab id.
+fn next_tab_id() -> TabId {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An open document, whose path and modified state come from its buffer.
 #[derive(Debug, Clone)]
 pub struct Tab {
-    pub title: String,
-    pub file_path: Option<PathBuf>,
-    pub is_modified: bool,
+    pub id: TabId,
     pub buffer: Arc<Mutex<TextBuffer>>,
+    /// Pinned tabs come first and are kept by Close Others
+    pub pinned: bool,
 }
 
 impl Tab {
-    pub fn new(title: String, file_path: Option<PathBuf>) -> Self {
+    pub fn new() -> Self {
+        Self::with_buffer(TextBuffer::new())
+    }
+
+    pub fn with_buffer(buffer: TextBuffer) -> Self {
+        Self::sharing(Arc::new(Mutex::new(buffer)))
+    }
+
+    /// Creates a tab for a buffer that is already open in another tab.
+    pub fn sharing(buffer: Arc<Mutex<TextBuffer>>) -> Self {
         Self {
-            title,
-            file_path,
-            is_modified: false,
-            buffer: Arc::new(Mutex::new(TextBuffer::new())),
+            id: next_tab_id(),
+            buffer,
+            pinned: false,
         }
     }
 
-    pub fn with_buffer(title: String, file_path: Option<PathBuf>, buffer: TextBuffer) -> Self {
-        Self {
-            title,
-            file_path,
-            is_modified: false,
-            buffer: Arc::new(Mutex::new(buffer)),
+    /// Returns the path of the file shown in the tab.
+    pub fn file_path(&self) -> Option<PathBuf> {
+        self.buffer.lock().unwrap().file_path.clone()
+    }
+
+    /// Returns whether the tab shows the file at `path`.
+    pub fn shows_file(&self, path: &Path) -> bool {
+        match self.file_path() {
+            Some(file_path) if file_path == path => true,
+            Some(file_path) => match (file_path.canonicalize(), path.canonicalize()) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => false,
+            },
+            None => false,
         }
     }
+}
 
-    pub fn display_name(&self) -> String {
-        if let Some(path) = &self.file_path {
-            path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Untitled")
-                .to_string()
-        } else {
-            "Untitled".to_string()
+/// The name of a tab, with the folders telling apart files of the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabTitle {
+    /// File name of the tab
+    pub name: String,
+    /// End of the parent folder path, when another tab has the same name
+    pub folder: Option<String>,
+}
+
+impl std::fmt::Display for TabTitle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.folder {
+            Some(folder) => write!(f, "{} — {}", self.name, folder),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Returns the names of the folders `path` is in, from the outermost.
+fn folders(path: &Path) -> Vec<String> {
+    path.parent()
+        .into_iter()
+        .flat_map(|parent| parent.components())
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the last `count` folders `path` is in, joined with `/`.
+fn parent_folders(path: &Path, count: usize) -> String {
+    let folders = folders(path);
+    folders[folders.len().saturating_sub(count)..].join("/")
+}
+
+/// Returns the titles of tabs showing `paths`, adding as many parent folders
+/// as it takes to tell apart files sharing a name.
+pub fn titles(paths: &[Option<PathBuf>]) -> Vec<TabTitle> {
+    let mut titles: Vec<TabTitle> = paths
+        .iter()
+        .map(|path| TabTitle {
+            name: path.as_ref()
+                .and_then(|path| path.file_name())
+                .map_or("Untitled".to_string(), |name| name.to_string_lossy().to_string()),
+            folder: None,
+        })
+        .collect();
+
+    for (index, path) in paths.iter().enumerate() {
+        let Some(path) = path else { continue };
+        let others: Vec<&PathBuf> = paths
+            .iter()
+            .zip(&titles)
+            .filter(|(_, title)| title.name == titles[index].name)
+            .filter_map(|(other, _)| other.as_ref().filter(|other| *other != path))
+            .collect();
+        let depth = folders(path).len();
+        if others.is_empty() || depth == 0 {
+            continue;
         }
+
+        let count = (1..=depth)
+            .find(|&count| {
+                let folder = parent_folders(path, count);
+                others.iter().all(|other| parent_folders(other, count) != folder)
+            })
+            .unwrap_or(depth);
+        titles[index].folder = Some(parent_folders(path, count));
     }
+    titles
+}
+
+/// Something picked in the tab list that the rest of the application handles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabAction {
+    /// Close the tab at an index
+    Close(usize),
+    /// Show the file of the tab at an index in the file tree
+    Reveal(usize),
 }
 
 #[derive(Debug, Clone)]
 pub struct TabsView {
     pub tabs: Vec<Tab>,
     pub active_tab: usize,
+    /// Tab ids from the most to the least recently active
+    recent: Vec<TabId>,
+    /// Text typed in the filter box shown when the tabs do not fit
+    filter: String,
+    /// Index of the tab being dragged to a new place
+    dragging: Option<usize>,
 }
 
 impl TabsView {
     pub fn new() -> Self {
-        let mut tabs = Vec::new();
-        tabs.push(Tab::new("Untitled".to_string(), None));
+        Self::with_tabs(vec![Tab::new()], 0)
+    }
+
+    /// Creates a tab list holding `tabs`, with the one at `active_tab` active.
+    pub fn with_tabs(tabs: Vec<Tab>, active_tab: usize) -> Self {
         Self {
             tabs,
-            active_tab: 0,
+            active_tab,
+            recent: Vec::new(),
+            filter: String::new(),
+            dragging: None,
         }
     }
 
+    /// Returns the titles of the tabs, telling apart files of the same name.
+    pub fn titles(&self) -> Vec<TabTitle> {
+        let paths: Vec<Option<PathBuf>> = self.tabs.iter().map(Tab::file_path).collect();
+        titles(&paths)
+    }
+
     pub fn active_buffer(&self) -> Arc<Mutex<TextBuffer>> {
         self.tabs[self.active_tab].buffer.clone()
     }
-    
+
     /// Creates a new tab and makes it active
     pub fn new_tab(&mut self) {
         // Create a new tab
-        self.tabs.push(Tab::new("Untitled".to_string(), None));
+        self.tabs.push(Tab::new());
         self.active_tab = self.tabs.len() - 1;
     }
 
@@ -78,21 +196,108 @@ impl TabsView {
         if self.tabs.len() <= 1 {
             return false;
         }
-        
+
         // Remove the active tab
         self.tabs.remove(self.active_tab);
-        
+
         // Adjust the active tab index if needed
         if self.active_tab >= self.tabs.len() {
             self.active_tab = self.tabs.len() - 1;
         }
-        
+
         true
     }
 
-    pub fn ui(&mut self, ui: &mut Ui, theme: Theme) -> Response {
+    /// Returns the number of pinned tabs, which are the first ones.
+    fn pinned_count(&self) -> usize {
+        self.tabs.iter().take_while(|tab| tab.pinned).count()
+    }
+
+    /// Changes the tabs with `change`, keeping the same tab active if it is
+    /// still open and the tab at `fallback` otherwise.
+    fn keep_active(&mut self, fallback: usize, change: impl FnOnce(&mut Vec<Tab>)) {
+        let active = self.tabs[self.active_tab].id;
+        let fallback = self.tabs[fallback].id;
+        change(&mut self.tabs);
+        self.active_tab = self
+            .index_of(active)
+            .or_else(|| self.index_of(fallback))
+            .unwrap_or(0);
+    }
+
+    /// Returns the index of the tab with `id`.
+    pub fn index_of(&self, id: TabId) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.id == id)
+    }
+
+    /// Moves the tab at `from` to `to`, keeping pinned tabs before the others.
+    pub fn move_tab(&mut self, from: usize, to: usize) {
+        let pinned = self.pinned_count();
+        let to = if self.tabs[from].pinned {
+            to.min(pinned - 1)
+        } else {
+            to.clamp(pinned, self.tabs.len() - 1)
+        };
+        self.keep_active(from, |tabs| {
+            let tab = tabs.remove(from);
+            tabs.insert(to, tab);
+        });
+    }
+
+    /// Pins or unpins the tab at `index`, moving it to the end of the pinned tabs.
+    pub fn toggle_pin(&mut self, index: usize) {
+        let pinned = self.pinned_count();
+        let to = if self.tabs[index].pinned { pinned - 1 } else { pinned };
+        self.keep_active(index, |tabs| {
+            let mut tab = tabs.remove(index);
+            tab.pinned = !tab.pinned;
+            tabs.insert(to, tab);
+        });
+    }
+
+    /// Closes every tab but the one at `index` and the pinned ones.
+    pub fn close_others(&mut self, index: usize) {
+        let keep = self.tabs[index].id;
+        self.keep_active(index, |tabs| tabs.retain(|tab| tab.pinned || tab.id == keep));
+    }
+
+    /// Closes the tabs after `index` that are not pinned.
+    pub fn close_to_the_right(&mut self, index: usize) {
+        let mut position = 0;
+        self.keep_active(index, |tabs| {
+            tabs.retain(|tab| {
+                position += 1;
+                position <= index + 1 || tab.pinned
+            })
+        });
+    }
+
+    /// Moves the active tab to the front of the recently used tabs.
+    ///
+    /// Called once per frame, so every way of switching tabs is noticed.
+    pub fn note_active(&mut self) {
+        let Some(active) = self.tabs.get(self.active_tab).map(|tab| tab.id) else { return };
+        if self.recent.first() == Some(&active) {
+            return;
+        }
+        let tabs = &self.tabs;
+        self.recent.retain(|&id| id != active && tabs.iter().any(|tab| tab.id == id));
+        self.recent.insert(0, active);
+    }
+
+    /// Returns the indices of the tabs from the most to the least recently used.
+    pub fn recent_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = self.recent.iter().filter_map(|&id| self.index_of(id)).collect();
+        // Tabs never active yet come last, in their order in the list
+        let unused: Vec<usize> = (0..self.tabs.len()).filter(|index| !order.contains(index)).collect();
+        order.extend(unused);
+        order
+    }
+
+    /// Draws the tab list and returns what was picked that needs the application.
+    pub fn ui(&mut self, ui: &mut Ui, theme: Theme) -> Option<TabAction> {
         let is_dark = matches!(theme, Theme::Dark);
-        
+
         // Colors for the tabs
         let (bg_color, active_bg_color, hover_bg_color, text_color, active_text_color) = if is_dark {
             (
@@ -111,11 +316,16 @@ impl TabsView {
                 Color32::from_rgb(40, 40, 60),      // Dark text for active tab
             )
         };
+        let indicator_color = if is_dark {
+            Color32::from_rgb(86, 156, 255)  // Brighter blue for dark mode
+        } else {
+            Color32::from_rgb(0, 120, 215)   // Standard blue for light mode
+        };
 
         // Set up the tabs panel
         let panel_rect = Rect::from_min_size(
-            ui.min_rect().min,
-            Vec2::new(140.0, ui.available_height()),  // Increased width from 100.0 to 140.0
+            ui.cursor().min,
+            Vec2::new(ui.available_width(), ui.available_height()),
         );
 
         // Draw the tabs background
@@ -125,10 +335,10 @@ impl TabsView {
             bg_color,
         );
 
-        let mut clicked_tab = None;
         let tab_height = 32.0;  // Slightly shorter tabs
         let tab_padding = Vec2::new(8.0, 0.0);  // Less horizontal padding
-        
+        let new_tab_height = 30.0;
+
         // Separator color - very subtle
         let separator_color = if is_dark {
             Color32::from_rgba_premultiplied(255, 255, 255, 10)  // Almost invisible white
@@ -138,123 +348,249 @@ impl TabsView {
 
         // Add top padding before the first tab
         let top_padding = 8.0;
+        let list_rect = Rect::from_min_max(
+            panel_rect.min + Vec2::new(0.0, top_padding),
+            egui::pos2(panel_rect.max.x, panel_rect.max.y - new_tab_height),
+        );
+        let mut list_ui = ui.child_ui(list_rect, egui::Layout::top_down(egui::Align::Min));
+        list_ui.spacing_mut().item_spacing = Vec2::ZERO;
 
-        // Draw each tab
-        for (index, tab) in self.tabs.iter().enumerate() {
-            let is_active = index == self.active_tab;
-            let tab_rect = Rect::from_min_size(
-                panel_rect.min + Vec2::new(0.0, top_padding + index as f32 * tab_height),
-                Vec2::new(panel_rect.width(), tab_height),
+        // Offer a filter when the tabs do not all fit
+        let fitting = (list_rect.height() / tab_height) as usize;
+        if self.tabs.len() > fitting || !self.filter.is_empty() {
+            let response = list_ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Filter tabs")
+                    .desired_width(f32::INFINITY),
             );
+            if response.lost_focus() && list_ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                self.filter.clear();
+            }
+            list_ui.add_space(4.0);
+        }
+        let titles = self.titles();
+        let shown: Vec<usize> = (0..self.tabs.len())
+            .filter(|&index| fuzzy_match(&self.filter, &titles[index].to_string()).is_some())
+            .collect();
 
-            // Handle interactions
-            let response = ui.allocate_rect(tab_rect, Sense::click());
-            let is_hovered = response.hovered();
+        let mut clicked_tab = None;
+        let mut action = None;
+        let mut pin = None;
+        let mut close_others = None;
+        let mut close_to_the_right = None;
+        // Where the dragged tab would be dropped, with the line marking the spot
+        let mut drop_target = None;
+        let pointer = ui.input(|i| i.pointer.interact_pos());
 
-            // Background color based on state
-            let bg_color = if is_active {
-                active_bg_color
-            } else if is_hovered {
-                hover_bg_color
-            } else {
-                bg_color
-            };
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(&mut list_ui, |ui| {
+                // Draw each tab
+                for (position, &index) in shown.iter().enumerate() {
+                    let tab = &self.tabs[index];
+                    let is_active = index == self.active_tab;
+                    let (tab_rect, response) = ui.allocate_exact_size(
+                        Vec2::new(ui.available_width(), tab_height),
+                        Sense::click_and_drag(),
+                    );
+                    let is_hovered = response.hovered();
 
-            // Draw tab background with subtle rounded corners on the right side
-            if is_active || is_hovered {
-                let rounding = egui::Rounding {
-                    ne: 4.0,
-                    se: 4.0,
-                    ..Default::default()
-                };
-                
-                ui.painter().rect_filled(
-                    tab_rect,
-                    rounding,
-                    bg_color,
-                );
-            } else {
-                ui.painter().rect_filled(
-                    tab_rect,
-                    0.0,
-                    bg_color,
-                );
-            }
+                    // Background color based on state
+                    let bg_color = if is_active {
+                        active_bg_color
+                    } else if is_hovered {
+                        hover_bg_color
+                    } else {
+                        bg_color
+                    };
 
-            // Active tab indicator - make it more stylish
-            if is_active {
-                let indicator_color = if is_dark {
-                    Color32::from_rgb(86, 156, 255)  // Brighter blue for dark mode
-                } else {
-                    Color32::from_rgb(0, 120, 215)   // Standard blue for light mode
-                };
-                
-                // Draw a thicker, rounded indicator
-                ui.painter().rect_filled(
-                    Rect::from_min_size(
-                        tab_rect.min,
-                        Vec2::new(3.0, tab_height),
-                    ),
-                    egui::Rounding {
-                        ne: 2.0,
-                        se: 2.0,
-                        ..Default::default()
-                    },
-                    indicator_color,
-                );
-                
-                // Draw a subtle highlight at the top of the active tab
-                ui.painter().rect_filled(
-                    Rect::from_min_size(
-                        tab_rect.min,
-                        Vec2::new(panel_rect.width(), 1.0),
-                    ),
-                    0.0,
-                    indicator_color.linear_multiply(0.7),
-                );
-            }
+                    // Draw tab background with subtle rounded corners on the right side
+                    if is_active || is_hovered {
+                        let rounding = egui::Rounding {
+                            ne: 4.0,
+                            se: 4.0,
+                            ..Default::default()
+                        };
 
-            // Draw tab title with icon
-            let text_color = if is_active { active_text_color } else { text_color };
-            let mut text = tab.display_name();
-            
-            // Truncate long filenames to fit in the tab
-            if text.len() > 10 {
-                text = format!("{}...", &text[0..7]);
-            }
-            
-            // Add file icon and modified indicator using simple text characters instead of emojis
-            let icon = if tab.is_modified {
-                "● "  // Filled circle for modified
-            } else {
-                "○ "  // Empty circle for unmodified
-            };
-            
-            let display_text = format!("{}{}", icon, text);
-
-            ui.painter().text(
-                tab_rect.min + tab_padding + Vec2::new(4.0, tab_height/2.0),
-                egui::Align2::LEFT_CENTER,
-                display_text,
-                egui::FontId::proportional(12.0),  // Slightly smaller font
-                text_color,
-            );
+                        ui.painter().rect_filled(
+                            tab_rect,
+                            rounding,
+                            bg_color,
+                        );
+                    } else {
+                        ui.painter().rect_filled(
+                            tab_rect,
+                            0.0,
+                            bg_color,
+                        );
+                    }
 
-            if response.clicked() {
-                clicked_tab = Some(index);
-            }
-            
-            // Draw separator line AFTER the tab content (not through it)
-            // Only draw separator if not the last tab
-            if index < self.tabs.len() - 1 {
-                let separator_y = tab_rect.min.y + tab_height;
-                ui.painter().line_segment(
-                    [
-                        egui::pos2(tab_rect.min.x + 8.0, separator_y),
-                        egui::pos2(tab_rect.max.x - 8.0, separator_y)
-                    ],
-                    egui::Stroke::new(1.0, separator_color)
-                );
+                    // Active tab indicator - make it more stylish
+                    if is_active {
+                        // Draw a thicker, rounded indicator
+                        ui.painter().rect_filled(
+                            Rect::from_min_size(
+                                tab_rect.min,
+                                Vec2::new(3.0, tab_height),
+                            ),
+                            egui::Rounding {
+                                ne: 2.0,
+                                se: 2.0,
+                                ..Default::default()
+                            },
+                            indicator_color,
+                        );
+
+                        // Draw a subtle highlight at the top of the active tab
+                        ui.painter().rect_filled(
+                            Rect::from_min_size(
+                                tab_rect.min,
+                                Vec2::new(tab_rect.width(), 1.0),
+                            ),
+                            0.0,
+                            indicator_color.linear_multiply(0.7),
+                        );
+                    }
+
+                    // Draw tab title with icon
+                    let text_color = if is_active { active_text_color } else { text_color };
+                    let title = &titles[index];
+                    let mut text = title.name.clone();
+
+                    // Truncate long filenames to fit in the tab
+                    if text.chars().count() > 10 {
+                        text = format!("{}...", text.chars().take(7).collect::<String>());
+                    }
+
+                    // Add file icon and modified indicator using simple text characters instead of emojis
+                    let icon = if tab.buffer.lock().unwrap().is_modified() {
+                        "● "  // Filled circle for modified
+                    } else {
+                        "○ "  // Empty circle for unmodified
+                    };
+
+                    let display_text = format!("{}{}", icon, text);
+
+                    let text_rect = ui.painter().text(
+                        tab_rect.min + tab_padding + Vec2::new(4.0, tab_height/2.0),
+                        egui::Align2::LEFT_CENTER,
+                        display_text,
+                        egui::FontId::proportional(12.0),  // Slightly smaller font
+                        text_color,
+                    );
+
+                    // Folders telling apart files of the same name, dimmed
+                    if let Some(folder) = &title.folder {
+                        ui.painter().text(
+                            egui::pos2(text_rect.right() + 6.0, tab_rect.center().y),
+                            egui::Align2::LEFT_CENTER,
+                            folder,
+                            egui::FontId::proportional(11.0),
+                            text_color.linear_multiply(0.6),
+                        );
+                    }
+
+                    if tab.pinned {
+                        ui.painter().text(
+                            egui::pos2(tab_rect.max.x - 8.0, tab_rect.center().y),
+                            egui::Align2::RIGHT_CENTER,
+                            "📌",
+                            egui::FontId::proportional(10.0),
+                            text_color,
+                        );
+                    }
+
+                    let path = tab.file_path();
+                    let response = match &path {
+                        Some(path) => response.on_hover_text(path.display().to_string()),
+                        None => response,
+                    };
+                    if response.clicked() {
+                        clicked_tab = Some(index);
+                    }
+                    if response.middle_clicked() {
+                        action = Some(TabAction::Close(index));
+                    }
+                    if response.drag_started() {
+                        self.dragging = Some(index);
+                    }
+
+                    // Tabs are dropped before the tab under the pointer, or after it
+                    // when the pointer is in its lower half
+                    if let (Some(_), Some(pointer)) = (self.dragging, pointer) {
+                        if tab_rect.y_range().contains(pointer.y) {
+                            let after = pointer.y > tab_rect.center().y;
+                            let y = if after { tab_rect.bottom() } else { tab_rect.top() };
+                            let to = if after { shown.get(position + 1).copied().unwrap_or(index + 1) } else { index };
+                            drop_target = Some((to, y));
+                        }
+                    }
+
+                    response.context_menu(|ui| {
+                        if ui.button("Close").clicked() {
+                            action = Some(TabAction::Close(index));
+                            ui.close_menu();
+                        }
+                        if ui.button("Close Others").clicked() {
+                            close_others = Some(index);
+                            ui.close_menu();
+                        }
+                        if ui.button("Close to the Right").clicked() {
+                            close_to_the_right = Some(index);
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        if ui.add_enabled(path.is_some(), egui::Button::new("Copy Path")).clicked() {
+                            if let Some(path) = &path {
+                                ui.ctx().copy_text(path.display().to_string());
+                            }
+                            ui.close_menu();
+                        }
+                        if ui.add_enabled(path.is_some(), egui::Button::new("Reveal in File Tree")).clicked() {
+                            action = Some(TabAction::Reveal(index));
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        if ui.button(if tab.pinned { "Unpin" } else { "Pin" }).clicked() {
+                            pin = Some(index);
+                            ui.close_menu();
+                        }
+                    });
+
+                    // Draw separator line AFTER the tab content (not through it)
+                    // Only draw separator if not the last tab
+                    if position + 1 < shown.len() {
+                        let separator_y = tab_rect.min.y + tab_height;
+                        ui.painter().line_segment(
+                            [
+                                egui::pos2(tab_rect.min.x + 8.0, separator_y),
+                                egui::pos2(tab_rect.max.x - 8.0, separator_y)
+                            ],
+                            egui::Stroke::new(1.0, separator_color)
+                        );
+                    }
+                }
+
+                if let Some((_, y)) = drop_target {
+                    let left = ui.max_rect().left();
+                    let right = ui.max_rect().right();
+                    ui.painter().line_segment(
+                        [egui::pos2(left, y), egui::pos2(right, y)],
+                        Stroke::new(2.0, indicator_color),
+                    );
+                }
+            });
+
+        // Drop the dragged tab where the pointer was released
+        if let Some(from) = self.dragging {
+            if ui.input(|i| i.pointer.any_released()) {
+                self.dragging = None;
+                if let Some((to, _)) = drop_target {
+                    let to = if to > from { to - 1 } else { to };
+                    if to != from && from < self.tabs.len() {
+                        self.move_tab(from, to);
+                    }
+                }
             }
         }
 
@@ -262,16 +598,25 @@ impl TabsView {
         if let Some(index) = clicked_tab {
             self.active_tab = index;
         }
-        
+        if let Some(index) = pin {
+            self.toggle_pin(index);
+        }
+        if let Some(index) = close_others {
+            self.close_others(index);
+        }
+        if let Some(index) = close_to_the_right {
+            self.close_to_the_right(index);
+        }
+
         // Add a "New Tab" button at the bottom of the panel
         let new_tab_rect = Rect::from_min_size(
-            panel_rect.min + Vec2::new(0.0, panel_rect.height() - 30.0),
-            Vec2::new(panel_rect.width(), 30.0),
+            panel_rect.min + Vec2::new(0.0, panel_rect.height() - new_tab_height),
+            Vec2::new(panel_rect.width(), new_tab_height),
         );
-        
+
         let new_tab_response = ui.allocate_rect(new_tab_rect, Sense::click());
         let is_new_tab_hovered = new_tab_response.hovered();
-        
+
         // Draw button background
         let new_tab_bg = if is_new_tab_hovered {
             if is_dark {
@@ -282,13 +627,13 @@ impl TabsView {
         } else {
             bg_color
         };
-        
+
         ui.painter().rect_filled(
             new_tab_rect,
             egui::Rounding::same(2.0),
             new_tab_bg,
         );
-        
+
         // Draw a subtle border
         if is_new_tab_hovered {
             let border_color = if is_dark {
@@ -296,21 +641,21 @@ impl TabsView {
             } else {
                 Color32::from_rgba_premultiplied(0, 0, 0, 20)
             };
-            
+
             ui.painter().rect_stroke(
                 new_tab_rect,
                 egui::Rounding::same(2.0),
                 egui::Stroke::new(1.0, border_color)
             );
         }
-        
+
         // Draw plus icon and text
         let new_tab_text_color = if is_dark {
             Color32::from_rgb(160, 160, 180)
         } else {
             Color32::from_rgb(100, 100, 120)
         };
-        
+
         ui.painter().text(
             new_tab_rect.center(),
             egui::Align2::CENTER_CENTER,
@@ -318,15 +663,34 @@ impl TabsView {
             egui::FontId::proportional(11.0),
             new_tab_text_color,
         );
-        
+
         // Handle new tab button click
         if new_tab_response.clicked() {
             // Create a new tab
-            self.tabs.push(Tab::new("Untitled".to_string(), None));
-            self.active_tab = self.tabs.len() - 1;
+            self.new_tab();
         }
 
-        // Return the overall response
-        ui.allocate_rect(panel_rect, Sense::hover())
+        // Take up the whole panel
+        ui.allocate_rect(panel_rect, Sense::hover());
+        action
     }
-} 
\ No newline at end of file
+}
+
+/// Carries out what was picked in the tab list.
+pub fn handle(app: &mut ZingApp, action: TabAction) {
+    match action {
+        TabAction::Close(index) => {
+            // Closing another tab leaves the active one active
+            let active = app.tabs.tabs[app.tabs.active_tab].id;
+            app.tabs.active_tab = index;
+            editor::close_tab(app);
+            if let Some(index) = app.tabs.index_of(active) {
+                app.tabs.active_tab = index;
+            }
+        }
+        TabAction::Reveal(index) => {
+            app.tabs.active_tab = index;
+            file_tree::reveal_active_file(app);
+        }
+    }
+}
@@ -4,7 +4,10 @@ use egui::{Color32, RichText, Ui, Vec2, Stroke, Rounding};
 
 use crate::ui::ZingApp;
 use crate::ui::editor;
-use crate::config::Theme;
+use crate::ui::commands::{self, Command};
+use crate::ui::navigation;
+use crate::ui::recent;
+use crate::config::{ShowWhitespace, Theme};
 
 /// Toolbar component.
 #[derive(Debug)]
@@ -627,6 +630,163 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
                         );
                     }
                 },
+                "back" | "forward" => {
+                    // Arrow icon
+                    let dir = if icon_type == "back" { -1.0 } else { 1.0 };
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x - 6.0 * dir, center.y),
+                            egui::pos2(center.x + 6.0 * dir, center.y)
+                        ],
+                        stroke
+                    );
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x + 6.0 * dir, center.y),
+                            egui::pos2(center.x + 2.0 * dir, center.y - 4.0)
+                        ],
+                        stroke
+                    );
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x + 6.0 * dir, center.y),
+                            egui::pos2(center.x + 2.0 * dir, center.y + 4.0)
+                        ],
+                        stroke
+                    );
+                },
+                "cut" => {
+                    // Scissors icon
+                    painter.circle_stroke(egui::pos2(center.x - 3.0, center.y + 4.0), 2.0, stroke);
+                    painter.circle_stroke(egui::pos2(center.x + 3.0, center.y + 4.0), 2.0, stroke);
+                    
+                    // Blades
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x - 2.0, center.y + 2.5),
+                            egui::pos2(center.x + 3.0, center.y - 6.0)
+                        ],
+                        stroke
+                    );
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x + 2.0, center.y + 2.5),
+                            egui::pos2(center.x - 3.0, center.y - 6.0)
+                        ],
+                        stroke
+                    );
+                },
+                "copy" => {
+                    // Two overlapping pages
+                    let size = 8.0;
+                    painter.rect_stroke(
+                        egui::Rect::from_min_size(
+                            egui::pos2(center.x - 5.0, center.y - 5.0),
+                            egui::vec2(size, size)
+                        ),
+                        Rounding::same(1.0),
+                        stroke
+                    );
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(
+                            egui::pos2(center.x - 2.0, center.y - 2.0),
+                            egui::vec2(size, size)
+                        ),
+                        Rounding::same(1.0),
+                        ui.visuals().widgets.inactive.bg_fill
+                    );
+                    painter.rect_stroke(
+                        egui::Rect::from_min_size(
+                            egui::pos2(center.x - 2.0, center.y - 2.0),
+                            egui::vec2(size, size)
+                        ),
+                        Rounding::same(1.0),
+                        stroke
+                    );
+                },
+                "paste" => {
+                    // Clipboard icon
+                    let width = 10.0;
+                    let height = 12.0;
+                    let left = center.x - width/2.0;
+                    let top = center.y - height/2.0;
+                    
+                    // Board
+                    painter.rect_stroke(
+                        egui::Rect::from_min_max(
+                            egui::pos2(left, top + 1.0),
+                            egui::pos2(left + width, top + height)
+                        ),
+                        Rounding::same(1.0),
+                        stroke
+                    );
+                    
+                    // Clip
+                    painter.rect_stroke(
+                        egui::Rect::from_min_max(
+                            egui::pos2(left + 3.0, top - 1.0),
+                            egui::pos2(left + width - 3.0, top + 2.0)
+                        ),
+                        Rounding::ZERO,
+                        stroke
+                    );
+                },
+                "minimap" => {
+                    // Page with a narrow overview strip on its right
+                    let width = 12.0;
+                    let height = 12.0;
+                    let left = center.x - width/2.0;
+                    let top = center.y - height/2.0;
+                    
+                    painter.rect_stroke(
+                        egui::Rect::from_min_max(
+                            egui::pos2(left, top),
+                            egui::pos2(left + width, top + height)
+                        ),
+                        Rounding::same(1.0),
+                        stroke
+                    );
+                    painter.line_segment(
+                        [
+                            egui::pos2(left + width - 4.0, top),
+                            egui::pos2(left + width - 4.0, top + height)
+                        ],
+                        stroke
+                    );
+                    
+                    // Viewport box
+                    painter.rect_filled(
+                        egui::Rect::from_min_max(
+                            egui::pos2(left + width - 3.5, top + 3.0),
+                            egui::pos2(left + width - 0.5, top + 6.0)
+                        ),
+                        Rounding::ZERO,
+                        text_color
+                    );
+                },
+                "split" => {
+                    // Page divided into two panes
+                    let width = 12.0;
+                    let height = 12.0;
+                    let left = center.x - width/2.0;
+                    let top = center.y - height/2.0;
+                    
+                    painter.rect_stroke(
+                        egui::Rect::from_min_max(
+                            egui::pos2(left, top),
+                            egui::pos2(left + width, top + height)
+                        ),
+                        Rounding::same(1.0),
+                        stroke
+                    );
+                    painter.line_segment(
+                        [
+                            egui::pos2(center.x, top),
+                            egui::pos2(center.x, top + height)
+                        ],
+                        stroke
+                    );
+                },
                 _ => {}
             }
             
@@ -638,6 +798,22 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
             editor::open_file(app);
         }
         
+        // Open Recent button, with the recent files and folders in a popup
+        let recent_button = ui.add_sized(button_size, egui::Button::new(" ")).on_hover_text("Open Recent");
+        let center = recent_button.rect.center();
+        let stroke = Stroke::new(1.5, text_color);
+        ui.painter().circle_stroke(center, 6.0, stroke);
+        ui.painter().line_segment([center, egui::pos2(center.x, center.y - 4.0)], stroke);
+        ui.painter().line_segment([center, egui::pos2(center.x + 3.0, center.y)], stroke);
+        let recent_popup = ui.make_persistent_id("recent_popup");
+        if recent_button.clicked() {
+            ui.memory_mut(|mem| mem.toggle_popup(recent_popup));
+        }
+        egui::popup_below_widget(ui, recent_popup, &recent_button, |ui| {
+            ui.set_min_width(320.0);
+            recent::menu(app, ui);
+        });
+        
         // Save button
         if draw_button(ui, "save", "Save (Ctrl+S)") {
             editor::save_file(app, false);
@@ -649,7 +825,7 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
         }
         
         // Print button
-        if draw_button(ui, "print", "Print (Ctrl+P)") {
+        if draw_button(ui, "print", "Print (Ctrl+Alt+P)") {
             editor::print_file(app);
         }
         
@@ -665,6 +841,50 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
         );
         ui.add_space(8.0);
         
+        // Clipboard buttons
+        let ctx = ui.ctx().clone();
+        if draw_button(ui, "cut", "Cut (Ctrl+X)") {
+            commands::execute(app, &ctx, Command::Cut);
+        }
+        if draw_button(ui, "copy", "Copy (Ctrl+C)") {
+            commands::execute(app, &ctx, Command::Copy);
+        }
+        if draw_button(ui, "paste", "Paste (Ctrl+V)") {
+            commands::execute(app, &ctx, Command::Paste);
+        }
+        
+        ui.add_space(8.0);
+        // Draw vertical divider
+        let divider_rect = ui.available_rect_before_wrap();
+        ui.painter().line_segment(
+            [
+                egui::pos2(divider_rect.min.x, divider_rect.min.y + 8.0),
+                egui::pos2(divider_rect.min.x, divider_rect.max.y - 8.0)
+            ],
+            Stroke::new(1.0, divider_color)
+        );
+        ui.add_space(8.0);
+        
+        // Navigation buttons
+        if draw_button(ui, "back", &format!("Go Back ({})", navigation::SHORTCUT_LABELS.0)) {
+            navigation::go_back(app);
+        }
+        if draw_button(ui, "forward", &format!("Go Forward ({})", navigation::SHORTCUT_LABELS.1)) {
+            navigation::go_forward(app);
+        }
+        
+        ui.add_space(8.0);
+        // Draw vertical divider
+        let divider_rect = ui.available_rect_before_wrap();
+        ui.painter().line_segment(
+            [
+                egui::pos2(divider_rect.min.x, divider_rect.min.y + 8.0),
+                egui::pos2(divider_rect.min.x, divider_rect.max.y - 8.0)
+            ],
+            Stroke::new(1.0, divider_color)
+        );
+        ui.add_space(8.0);
+        
         // Theme toggle button
         if draw_button(ui, "theme", if is_dark { "Switch to Light Mode" } else { "Switch to Dark Mode" }) {
             app.toggle_theme(ui.ctx());
@@ -726,5 +946,32 @@ pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
         if draw_button(ui, "word_wrap", if app.config.word_wrap { "Disable Word Wrap" } else { "Enable Word Wrap" }) {
             app.config.toggle_word_wrap();
         }
+        
+        // Split button
+        if draw_button(ui, "split", "Split Editor Right (Ctrl+Alt+Shift+Right)") {
+            crate::ui::panes::split(app, crate::ui::panes::Direction::Right);
+        }
+        
+        // Minimap button
+        if draw_button(ui, "minimap", if app.config.show_minimap { "Hide Minimap" } else { "Show Minimap" }) {
+            app.config.toggle_minimap();
+        }
+        
+        // Whitespace, indentation guides and rulers
+        let guides = ui.add_sized(button_size, egui::Button::new("¶")).on_hover_text("Whitespace, Guides and Rulers");
+        let guides_popup = ui.make_persistent_id("guides_popup");
+        if guides.clicked() {
+            ui.memory_mut(|mem| mem.toggle_popup(guides_popup));
+        }
+        egui::popup::popup_below_widget(ui, guides_popup, &guides, |ui| {
+            ui.set_min_width(160.0);
+            ui.label("Show Whitespace:");
+            for mode in ShowWhitespace::ALL {
+                ui.radio_value(&mut app.config.show_whitespace, mode, mode.label());
+            }
+            ui.separator();
+            ui.checkbox(&mut app.config.show_indent_guides, "Indentation Guides");
+            ui.checkbox(&mut app.config.show_rulers, "Rulers");
+        });
     });
 } 
\ No newline at end of file
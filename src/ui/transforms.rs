@@ -0,0 +1,67 @@
+//! Text transformation commands for Zing text editor.
+//!
+//! Transformations rewrite every selection, or the word at the cursor when
+//! nothing is selected, and undo in one step.
+
+use egui::Context;
+use std::ops::Range;
+
+use crate::buffer::transform::{self, Transform};
+use crate::buffer::TextBuffer;
+use crate::ui::{editor, indentation, ZingApp};
+
+/// Returns the ranges a transformation acts on in the active buffer.
+fn targets(app: &ZingApp, buffer: &TextBuffer) -> Vec<Range<usize>> {
+    let selection = indentation::selection(app, buffer);
+    if selection.is_empty() {
+        vec![buffer.word_range(selection.start)]
+    } else {
+        vec![selection]
+    }
+}
+
+/// Rewrites the selected text with `transform`, keeping the new text selected.
+pub fn apply(app: &mut ZingApp, ctx: &Context, transform: Transform) {
+    let buffer = app.buffer();
+    let mut buffer_lock = buffer.lock().unwrap();
+    let ranges = targets(app, &buffer_lock);
+    if ranges.iter().all(|range| range.is_empty()) {
+        return;
+    }
+    let result = buffer_lock.transform_ranges(&ranges, |text| transform.apply(text));
+    drop(buffer_lock);
+
+    match result {
+        Ok(ranges) => {
+            if let Some(range) = ranges.into_iter().next() {
+                editor::set_selection(app, ctx, range);
+            }
+        }
+        Err(err) => {
+            log::error!("Failed to transform text: {:#}", err);
+            app.set_status(format!("Failed to transform text: {:#}", err), 5.0);
+        }
+    }
+}
+
+/// Copies the SHA-256 hash of the selected text to the clipboard, one line
+/// per selection.
+pub fn copy_sha256(app: &mut ZingApp, ctx: &Context) {
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    let hashes: Vec<String> = targets(app, &buffer_lock)
+        .into_iter()
+        .filter_map(|range| buffer_lock.slice(range.start, range.end).ok())
+        .filter(|text| !text.is_empty())
+        .map(|text| transform::sha256_hex(&text))
+        .collect();
+    drop(buffer_lock);
+
+    if hashes.is_empty() {
+        return;
+    }
+    let text = hashes.join("\n");
+    ctx.copy_text(text.clone());
+    app.clipboard.push(text, false);
+    app.set_status("Copied SHA-256 hash".to_string(), 2.0);
+}
@@ -0,0 +1,104 @@
+//! Welcome screen for Zing text editor.
+//!
+//! Shown in place of the editor while nothing has been opened yet, with
+//! buttons to start and the recently opened folders and files.
+
+use egui::{Color32, Key, RichText, Ui};
+use std::path::PathBuf;
+
+use crate::config::Theme;
+use crate::ui::{editor, recent, ZingApp};
+
+/// Number of folders and of files listed on the welcome screen.
+const LISTED_ENTRIES: usize = 8;
+
+/// Returns whether the welcome screen replaces the editor, which is while it
+/// has not been dismissed and the only tab is empty and unsaved.
+pub fn is_shown(app: &ZingApp) -> bool {
+    if !app.show_welcome || app.panes.count() > 1 || app.tabs.tabs.len() > 1 {
+        return false;
+    }
+    let buffer = app.buffer();
+    let buffer_lock = buffer.lock().unwrap();
+    buffer_lock.file_path.is_none() && buffer_lock.len_chars() == 0
+}
+
+/// Draws a list of recent entries under `heading` and returns the one clicked.
+fn recent_list(ui: &mut Ui, heading: &str, paths: &[PathBuf], weak_color: Color32) -> Option<PathBuf> {
+    if paths.is_empty() {
+        return None;
+    }
+    let mut picked = None;
+    ui.add_space(16.0);
+    ui.label(RichText::new(heading).strong());
+    for path in paths.iter().take(LISTED_ENTRIES) {
+        ui.horizontal(|ui| {
+            let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+            if ui.link(name).on_hover_text(path.display().to_string()).clicked() {
+                picked = Some(path.clone());
+            }
+            if let Some(parent) = path.parent() {
+                ui.label(RichText::new(parent.display().to_string()).color(weak_color).small());
+            }
+        });
+    }
+    picked
+}
+
+/// Draws the welcome screen filling `ui`.
+pub fn ui(app: &mut ZingApp, ui: &mut Ui) {
+    let weak_color = match app.config.theme {
+        Theme::Dark => Color32::from_gray(120),
+        Theme::Light => Color32::from_gray(140),
+    };
+
+    if ui.input(|i| i.key_pressed(Key::Escape)) {
+        app.show_welcome = false;
+        return;
+    }
+
+    let folders = app.session.recent_folders.clone();
+    let files = app.session.recent_files.clone();
+    let mut picked = None;
+
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        ui.add_space(48.0);
+        ui.vertical_centered(|ui| {
+            ui.label(RichText::new("Zing").size(32.0).strong());
+            ui.label(RichText::new("A fast, beautiful text editor").color(weak_color));
+            ui.add_space(24.0);
+            ui.horizontal(|ui| {
+                // Centre the row of buttons
+                ui.add_space((ui.available_width() - 330.0).max(0.0) / 2.0);
+                if ui.button("New File").clicked() {
+                    app.show_welcome = false;
+                }
+                if ui.button("Open File...").clicked() {
+                    editor::open_file(app);
+                }
+                if ui.button("Open Folder...").clicked() {
+                    editor::open_folder(app);
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.add_space((ui.available_width() - 480.0).max(24.0) / 2.0);
+            ui.vertical(|ui| {
+                let folder = recent_list(ui, "Recent Folders", &folders, weak_color);
+                let file = recent_list(ui, "Recent Files", &files, weak_color);
+                picked = folder.or(file);
+                if !folders.is_empty() || !files.is_empty() {
+                    ui.add_space(12.0);
+                    if ui.link(RichText::new("Clear Recent").color(weak_color)).clicked() {
+                        recent::clear(app);
+                    }
+                }
+            });
+        });
+    });
+
+    if let Some(path) = picked {
+        recent::open(app, &path);
+    }
+}
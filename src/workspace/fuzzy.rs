@@ -0,0 +1,172 @@
+//! Fuzzy matching of file paths for the quick open finder.
+
+/// The result of a successful fuzzy match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Character indices in the candidate that matched the pattern
+    pub indices: Vec<usize>,
+}
+
+/// Bonus for a match at the start of a word or path component.
+const BOUNDARY_BONUS: i64 = 8;
+/// Bonus for a match directly following the previous matched character.
+const CONSECUTIVE_BONUS: i64 = 10;
+/// Bonus for every matched character inside the file name.
+const FILE_NAME_BONUS: i64 = 2;
+/// Base score for every matched character.
+const MATCH_SCORE: i64 = 16;
+/// Penalty for starting a gap between two matches.
+const GAP_START_PENALTY: i64 = 3;
+/// Penalty for every skipped character between two matches.
+const GAP_PENALTY: i64 = 1;
+
+/// Fuzzy matches `pattern` against `candidate`, ignoring case and whitespace in the pattern.
+///
+/// Every pattern character must appear in the candidate in order. Among all such
+/// alignments the one with the highest score is returned, favouring matches at
+/// word boundaries, runs of consecutive characters and matches in the file name.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = candidate.chars().collect();
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch { score: 0, indices: Vec::new() });
+    }
+    if pattern.len() > chars.len() {
+        return None;
+    }
+
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let name_start = chars
+        .iter()
+        .rposition(|&c| c == '/' || c == '\\')
+        .map_or(0, |i| i + 1);
+
+    let bonus = |j: usize| -> i64 {
+        let mut bonus = if j >= name_start { FILE_NAME_BONUS } else { 0 };
+        let is_boundary = match j.checked_sub(1).map(|p| chars[p]) {
+            None => true,
+            Some(prev) => {
+                matches!(prev, '/' | '\\' | '_' | '-' | '.' | ' ')
+                    || (prev.is_lowercase() && chars[j].is_uppercase())
+            }
+        };
+        if is_boundary {
+            bonus += BOUNDARY_BONUS;
+        }
+        bonus
+    };
+
+    // best[i][j]: best score with pattern[i] matched at chars[j]
+    let n = chars.len();
+    let m = pattern.len();
+    let mut best = vec![vec![i64::MIN; n]; m];
+    let mut from = vec![vec![usize::MAX; n]; m];
+
+    for j in 0..n {
+        if lower[j] == pattern[0] {
+            best[0][j] = MATCH_SCORE + bonus(j) - (j as i64).min(10) * GAP_PENALTY;
+        }
+    }
+
+    for i in 1..m {
+        // Track the best predecessor seen so far, adjusted for the gap to `j`
+        let mut running: Option<(i64, usize)> = None;
+        for j in i..n {
+            let k = j - 1;
+            if best[i - 1][k] != i64::MIN {
+                let candidate = best[i - 1][k] + (k as i64) * GAP_PENALTY;
+                let improves = match running {
+                    Some((score, _)) => candidate > score,
+                    None => true,
+                };
+                if improves {
+                    running = Some((candidate, k));
+                }
+            }
+
+            if lower[j] != pattern[i] {
+                continue;
+            }
+
+            let mut score = i64::MIN;
+            let mut prev = usize::MAX;
+
+            if let Some((adjusted, k)) = running {
+                score = adjusted - (j as i64 - 1) * GAP_PENALTY - GAP_START_PENALTY;
+                prev = k;
+            }
+            if best[i - 1][j - 1] != i64::MIN {
+                let consecutive = best[i - 1][j - 1] + CONSECUTIVE_BONUS;
+                if consecutive >= score {
+                    score = consecutive;
+                    prev = j - 1;
+                }
+            }
+
+            if prev != usize::MAX {
+                best[i][j] = score + MATCH_SCORE + bonus(j);
+                from[i][j] = prev;
+            }
+        }
+    }
+
+    let (mut j, score) = best[m - 1]
+        .iter()
+        .enumerate()
+        .filter(|(_, &score)| score != i64::MIN)
+        .max_by_key(|(j, &score)| (score, usize::MAX - j))
+        .map(|(j, &score)| (j, score))?;
+
+    let mut indices = vec![0; m];
+    for i in (0..m).rev() {
+        indices[i] = j;
+        j = from[i][j];
+    }
+
+    // Prefer shorter candidates when everything else is equal
+    Some(FuzzyMatch {
+        score: score - (n as i64) / 8,
+        indices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsequence_required() {
+        assert!(fuzzy_match("abc", "a_b_c").is_some());
+        assert!(fuzzy_match("abc", "acb").is_none());
+        assert!(fuzzy_match("", "anything").is_some());
+    }
+
+    #[test]
+    fn test_indices_and_case() {
+        let m = fuzzy_match("BMod", "src/buffer/mod.rs").unwrap();
+        let chars: Vec<char> = "src/buffer/mod.rs".chars().collect();
+        let matched: String = m.indices.iter().map(|&i| chars[i]).collect();
+        assert_eq!(matched.to_lowercase(), "bmod");
+        // The `mod` part should be matched in the file name, not in `buffer`
+        assert_eq!(m.indices[1], 11);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let score = |p: &str, c: &str| fuzzy_match(p, c).unwrap().score;
+        // File name matches beat directory matches
+        assert!(score("tabs", "src/ui/tabs.rs") > score("tabs", "tests/a/b/s.rs"));
+        // Word boundaries beat scattered characters
+        assert!(score("sb", "src/statusbar.rs") < score("sb", "src/status_bar.rs"));
+    }
+}
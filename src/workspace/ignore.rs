@@ -0,0 +1,226 @@
+//! Minimal `.gitignore` support for the workspace walker.
+//!
+//! Only the commonly used subset of the gitignore syntax is supported: `*`, `?`,
+//! `**`, character classes, negation with `!`, directory-only patterns ending in
+//! `/` and patterns anchored to the directory containing the `.gitignore`.
+
+use std::path::{Path, PathBuf};
+
+/// Directory names that are always hidden, regardless of ignore files.
+const ALWAYS_IGNORED: &[&str] = &[".git", ".hg", ".svn"];
+
+/// A single pattern from an ignore file.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    /// Directory containing the ignore file the rule came from
+    base: PathBuf,
+    /// The glob pattern, without the leading `!`, `/` or trailing `/`
+    pattern: Vec<char>,
+    /// Whether the rule re-includes previously ignored paths
+    negated: bool,
+    /// Whether the rule only applies to directories
+    dir_only: bool,
+    /// Whether the pattern is matched against the path relative to `base`
+    anchored: bool,
+}
+
+/// A set of ignore rules collected from `.gitignore` files.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Creates an empty rule set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the `.gitignore` file in `dir`, if there is one.
+    pub fn add_dir(&mut self, dir: &Path) {
+        if let Ok(text) = std::fs::read_to_string(dir.join(".gitignore")) {
+            self.add_patterns(dir, &text);
+        }
+    }
+
+    /// Adds the patterns in `text` as if they were read from an ignore file in `base`.
+    pub fn add_patterns(&mut self, base: &Path, text: &str) {
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let anchored = line.contains('/');
+            let line = line.trim_start_matches('/');
+            if line.is_empty() {
+                continue;
+            }
+
+            self.rules.push(IgnoreRule {
+                base: base.to_path_buf(),
+                pattern: line.chars().collect(),
+                negated,
+                dir_only,
+                anchored,
+            });
+        }
+    }
+
+    /// Returns whether `path` should be hidden.
+    ///
+    /// Parent directories are not checked; callers walking a tree are expected
+    /// to skip the contents of ignored directories themselves.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+        if is_dir && ALWAYS_IGNORED.contains(&name) {
+            return true;
+        }
+
+        let name: Vec<char> = name.chars().collect();
+        let mut ignored = false;
+
+        // Later rules take precedence, so the last matching rule decides
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let relative = match path.strip_prefix(&rule.base) {
+                Ok(relative) => relative,
+                Err(_) => continue,
+            };
+
+            let matched = if rule.anchored {
+                let relative: Vec<char> = relative
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .chars()
+                    .collect();
+                glob_match(&rule.pattern, &relative)
+            } else {
+                glob_match(&rule.pattern, &name)
+            };
+
+            if matched {
+                ignored = !rule.negated;
+            }
+        }
+
+        ignored
+    }
+}
+
+/// Matches `text` against a gitignore-style glob.
+pub fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // `**` matches across directory separators
+            let mut rest = &pattern[2..];
+            if rest.first() == Some(&'/') {
+                rest = &rest[1..];
+            }
+            (0..=text.len()).any(|i| {
+                (i == 0 || text[i - 1] == '/' || rest.is_empty()) && glob_match(rest, &text[i..])
+            })
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_match(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            !text.is_empty() && text[0] != '/' && glob_match(&pattern[1..], &text[1..])
+        }
+        Some('[') => {
+            let close = match pattern.iter().skip(1).position(|&c| c == ']') {
+                Some(offset) => offset + 1,
+                None => return text.first() == Some(&'[') && glob_match(&pattern[1..], &text[1..]),
+            };
+            let c = match text.first() {
+                Some(&c) if c != '/' => c,
+                _ => return false,
+            };
+            let mut class = &pattern[1..close];
+            let negated = matches!(class.first(), Some('!') | Some('^'));
+            if negated {
+                class = &class[1..];
+            }
+
+            let mut in_class = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    in_class |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    in_class |= class[i] == c;
+                    i += 1;
+                }
+            }
+
+            in_class != negated && glob_match(&pattern[close + 1..], &text[1..])
+        }
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(&c) => text.first() == Some(&c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(text: &str) -> IgnoreRules {
+        let mut rules = IgnoreRules::new();
+        rules.add_patterns(Path::new("/repo"), text);
+        rules
+    }
+
+    #[test]
+    fn test_basename_patterns() {
+        let rules = rules("*.log\ntarget/\n");
+        assert!(rules.is_ignored(Path::new("/repo/debug.log"), false));
+        assert!(rules.is_ignored(Path::new("/repo/src/deep/trace.log"), false));
+        assert!(rules.is_ignored(Path::new("/repo/target"), true));
+        assert!(!rules.is_ignored(Path::new("/repo/target"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/src/main.rs"), false));
+    }
+
+    #[test]
+    fn test_anchored_and_negated_patterns() {
+        let rules = rules("/build\ndocs/**/*.html\n*.txt\n!keep.txt\n");
+        assert!(rules.is_ignored(Path::new("/repo/build"), true));
+        assert!(!rules.is_ignored(Path::new("/repo/src/build"), true));
+        assert!(rules.is_ignored(Path::new("/repo/docs/a/b/index.html"), false));
+        assert!(rules.is_ignored(Path::new("/repo/docs/index.html"), false));
+        assert!(rules.is_ignored(Path::new("/repo/notes.txt"), false));
+        assert!(!rules.is_ignored(Path::new("/repo/keep.txt"), false));
+    }
+
+    #[test]
+    fn test_always_ignored() {
+        let rules = IgnoreRules::new();
+        assert!(rules.is_ignored(Path::new("/repo/.git"), true));
+        assert!(!rules.is_ignored(Path::new("/repo/.gitignore"), false));
+    }
+}
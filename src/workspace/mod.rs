@@ -0,0 +1,294 @@
+//! Workspace module for Zing text editor.
+//!
+//! A workspace is a root folder whose files are indexed in the background so
+//! they can be found quickly, honouring `.gitignore` files along the way.
+
+pub mod fuzzy;
+pub mod ignore;
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use ignore::IgnoreRules;
+
+/// Upper bound on the number of files indexed, to keep huge trees responsive.
+const MAX_INDEXED_FILES: usize = 200_000;
+
+/// The folder currently open in the editor and an index of its files.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// The workspace root folder, if one is open
+    pub root: Option<PathBuf>,
+    /// Files found under the root, relative to it
+    files: Arc<Mutex<Vec<PathBuf>>>,
+    /// Whether the background indexer is still running
+    indexing: Arc<AtomicBool>,
+    /// Number of times the root folder has been indexed
+    generation: u64,
+}
+
+impl Workspace {
+    /// Creates a workspace with no folder open.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens `root` as the workspace folder and starts indexing it.
+    pub fn set_root(&mut self, root: PathBuf) {
+        if self.root.as_ref() == Some(&root) {
+            return;
+        }
+        self.root = Some(root);
+        self.reindex();
+    }
+
+    /// Discards the current index and walks the root folder again in the background.
+    pub fn reindex(&mut self) {
+        let root = match &self.root {
+            Some(root) => root.clone(),
+            None => return,
+        };
+
+        // Give the new walk its own storage so a previous walk can't clobber it
+        self.files = Arc::new(Mutex::new(Vec::new()));
+        self.indexing = Arc::new(AtomicBool::new(true));
+        self.generation += 1;
+
+        let files = Arc::clone(&self.files);
+        let indexing = Arc::clone(&self.indexing);
+        std::thread::spawn(move || {
+            index_folder(&root, &files);
+            indexing.store(false, Ordering::Release);
+            log::info!("Indexed {} files under {}", files.lock().unwrap().len(), root.display());
+        });
+    }
+
+    /// Returns whether the background indexer is still running.
+    pub fn is_indexing(&self) -> bool {
+        self.indexing.load(Ordering::Acquire)
+    }
+
+    /// Returns the number of times the root folder has been indexed and the
+    /// number of files found so far, which together change whenever the
+    /// index does.
+    pub fn index_version(&self) -> (u64, usize) {
+        (self.generation, self.files.lock().unwrap().len())
+    }
+
+    /// Returns a snapshot of the indexed files, relative to the root.
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files.lock().unwrap().clone()
+    }
+
+    /// Returns `path` relative to the workspace root, if it is inside it.
+    pub fn relative_path<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        self.root.as_ref().and_then(|root| path.strip_prefix(root).ok())
+    }
+}
+
+/// Creates an empty file at `path`, failing if something already exists there.
+pub fn create_file(path: &Path) -> Result<()> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("Failed to create file: {}", path.display()))?;
+    Ok(())
+}
+
+/// Creates a folder at `path`, including any missing parents.
+pub fn create_folder(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path)
+        .with_context(|| format!("Failed to create folder: {}", path.display()))
+}
+
+/// Renames `from` to `to`, refusing to overwrite an existing path.
+pub fn rename_path(from: &Path, to: &Path) -> Result<()> {
+    if to.exists() {
+        return Err(anyhow::anyhow!("{} already exists", to.display()));
+    }
+    std::fs::rename(from, to)
+        .with_context(|| format!("Failed to rename {} to {}", from.display(), to.display()))
+}
+
+/// Moves `path` into the folder `dir`, returning its new location.
+pub fn move_path(path: &Path, dir: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Cannot move {}", path.display()))?;
+    if dir.starts_with(path) {
+        return Err(anyhow::anyhow!("Cannot move a folder into itself"));
+    }
+    let target = dir.join(name);
+    rename_path(path, &target)?;
+    Ok(target)
+}
+
+/// Deletes a file, or a folder and everything in it.
+pub fn delete_path(path: &Path) -> Result<()> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.with_context(|| format!("Failed to delete {}", path.display()))
+}
+
+/// Splits a trailing `:line` or `:line:column` suffix off a location query.
+///
+/// Line and column numbers are returned as typed, i.e. 1-based.
+pub fn split_location(query: &str) -> (&str, Option<usize>, Option<usize>) {
+    let mut parts = query.rsplitn(3, ':');
+    let last = parts.next().unwrap_or_default();
+    let middle = parts.next();
+    let first = parts.next();
+
+    match (first, middle, last.parse::<usize>()) {
+        (Some(name), Some(line), Ok(column)) => match line.parse::<usize>() {
+            Ok(line) => (name, Some(line), Some(column)),
+            Err(_) => (&query[..name.len() + 1 + line.len()], Some(column), None),
+        },
+        (None, Some(name), Ok(line)) => (name, Some(line), None),
+        _ if query.ends_with(':') => (&query[..query.len() - 1], None, None),
+        _ => (query, None, None),
+    }
+}
+
+/// Returns the 1-based line and column of a query that is only a location,
+/// like `:12` or `:12:3`, which jumps within the current file.
+pub fn current_file_location(query: &str) -> Option<(usize, Option<usize>)> {
+    match split_location(query) {
+        (name, Some(line), column) if name.trim().is_empty() => Some((line, column)),
+        _ => None,
+    }
+}
+
+/// Walks `root`, appending every non-ignored file to `files` as it goes.
+fn index_folder(root: &Path, files: &Mutex<Vec<PathBuf>>) {
+    let mut rules = IgnoreRules::new();
+    let mut stack = vec![root.to_path_buf()];
+    let mut found = Vec::new();
+    let mut count = 0;
+
+    while let Some(dir) = stack.pop() {
+        rules.add_dir(&dir);
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::warn!("Failed to read directory {}: {}", dir.display(), err);
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            // Don't follow symlinks to directories, they can form cycles
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+            let path = entry.path();
+            let is_dir = file_type.is_dir();
+
+            if rules.is_ignored(&path, is_dir) {
+                continue;
+            }
+
+            if is_dir {
+                stack.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                found.push(relative.to_path_buf());
+                count += 1;
+            }
+        }
+
+        // Publish in batches so the finder can show results while indexing
+        if found.len() >= 512 || stack.is_empty() {
+            files.lock().unwrap().append(&mut found);
+        }
+        if count >= MAX_INDEXED_FILES {
+            log::warn!("Stopped indexing {} after {} files", root.display(), count);
+            break;
+        }
+    }
+
+    files.lock().unwrap().append(&mut found);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_split_location() {
+        assert_eq!(split_location("main.rs"), ("main.rs", None, None));
+        assert_eq!(split_location("main.rs:42"), ("main.rs", Some(42), None));
+        assert_eq!(split_location("main.rs:42:7"), ("main.rs", Some(42), Some(7)));
+        assert_eq!(split_location("main.rs:"), ("main.rs", None, None));
+        assert_eq!(split_location("a:b:3"), ("a:b", Some(3), None));
+        assert_eq!(split_location(":12"), ("", Some(12), None));
+    }
+
+    #[test]
+    fn test_current_file_location() {
+        assert_eq!(current_file_location(":12"), Some((12, None)));
+        assert_eq!(current_file_location(":12:3"), Some((12, Some(3))));
+        assert_eq!(current_file_location(" :7"), Some((7, None)));
+        assert_eq!(current_file_location("main.rs:12"), None);
+        assert_eq!(current_file_location(":"), None);
+        assert_eq!(current_file_location(""), None);
+    }
+
+    #[test]
+    fn test_file_operations() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+
+        create_folder(&root.join("a/b"))?;
+        create_file(&root.join("a/notes.txt"))?;
+        assert!(create_file(&root.join("a/notes.txt")).is_err());
+
+        rename_path(&root.join("a/notes.txt"), &root.join("a/todo.txt"))?;
+        assert!(root.join("a/todo.txt").is_file());
+
+        let moved = move_path(&root.join("a/todo.txt"), &root.join("a/b"))?;
+        assert_eq!(moved, root.join("a/b/todo.txt"));
+        assert!(move_path(&root.join("a"), &root.join("a/b")).is_err());
+
+        delete_path(&root.join("a"))?;
+        assert!(!root.join("a").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("target/debug/zing"), "").unwrap();
+        std::fs::write(root.join("trace.log"), "").unwrap();
+
+        let mut workspace = Workspace::new();
+        workspace.set_root(root.to_path_buf());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while workspace.is_indexing() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut files = workspace.files();
+        files.sort();
+        assert_eq!(files, vec![PathBuf::from(".gitignore"), PathBuf::from("src/main.rs")]);
+        assert_eq!(workspace.index_version(), (1, 2));
+
+        // Indexing again changes the version even before any file is found
+        workspace.reindex();
+        assert_eq!(workspace.index_version().0, 2);
+    }
+}